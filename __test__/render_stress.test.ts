@@ -284,4 +284,44 @@ describe('Render Stress Tests', () => {
     
     expect(errors).toHaveLength(0)
   }, 30000)
+
+  test('renderView should render as many frames as render with a Buffer', async () => {
+    const width = 1280
+    const height = 720
+    const frameCount = 200
+
+    const eventLoop = process.platform === 'linux' ? getOrCreateEventLoop() : new EventLoop()
+    const window = new WindowBuilder()
+      .withTitle('Render View Frames')
+      .withInnerSize(width, height)
+      .build(eventLoop)
+
+    const renderer = PixelRenderer.withOptions({ bufferWidth: width, bufferHeight: height })
+    const view = new Uint8ClampedArray(width * height * 4).fill(255)
+    const buffer = Buffer.from(view.buffer, view.byteOffset, view.byteLength)
+
+    eventLoop.runIteration()
+
+    const renderFrames = (render: () => void) => {
+      let rendered = 0
+      for (let i = 0; i < frameCount; i++) {
+        render()
+        rendered++
+        eventLoop.runIteration()
+      }
+      return rendered
+    }
+
+    const bufferFrames = renderFrames(() => renderer.render(window, buffer))
+    const viewFrames = renderFrames(() => renderer.renderView(window, view))
+
+    try {
+      window.close()
+    } catch (e) {
+      // Ignore cleanup errors
+    }
+
+    expect(bufferFrames).toBe(frameCount)
+    expect(viewFrames).toBe(bufferFrames)
+  }, 60000)
 })
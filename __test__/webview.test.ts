@@ -477,7 +477,8 @@ describe('Interfaces y tipos de la biblioteca webview', () => {
       name: 'Monitor Principal',
       size: { width: 1920, height: 1080 },
       position: { x: 0, y: 0 },
-      scaleFactor: 1.0
+      scaleFactor: 1.0,
+      isPrimary: true
     }
     expect(monitorInfo.name).toBe('Monitor Principal')
     expect(monitorInfo.size.width).toBe(1920)
@@ -485,6 +486,7 @@ describe('Interfaces y tipos de la biblioteca webview', () => {
     expect(monitorInfo.position.x).toBe(0)
     expect(monitorInfo.position.y).toBe(0)
     expect(monitorInfo.scaleFactor).toBe(1.0)
+    expect(monitorInfo.isPrimary).toBe(true)
   })
 })
 
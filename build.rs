@@ -1,5 +1,25 @@
 extern crate napi_build;
 
+/// Returns the version of `package` resolved in Cargo.lock, if any
+fn locked_version(lock: &str, package: &str) -> Option<String> {
+  let name = format!("name = \"{}\"", package);
+  let mut lines = lock.lines();
+  while let Some(line) = lines.next() {
+    if line.trim() == name {
+      let version = lines.next()?.trim().strip_prefix("version = ")?;
+      return Some(version.trim_matches('"').to_string());
+    }
+  }
+  None
+}
+
 fn main() {
   napi_build::setup();
+
+  println!("cargo:rerun-if-changed=Cargo.lock");
+  let lock = std::fs::read_to_string("Cargo.lock").unwrap_or_default();
+  for (package, var) in [("tao", "TAO_VERSION"), ("wry", "WRY_VERSION")] {
+    let version = locked_version(&lock, package).unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env={}={}", var, version);
+  }
 }
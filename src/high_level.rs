@@ -1,7 +1,9 @@
 use napi::bindgen_prelude::*;
 use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use napi_derive::napi;
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
 
 #[napi]
 pub type IpcHandler = ThreadsafeFunction<String>;
@@ -18,7 +20,7 @@ pub(crate) enum PendingWebviewAction {
 }
 
 #[allow(unused_imports)]
-use crate::tao::enums::{TaoControlFlow, TaoFullscreenType, TaoTheme};
+use crate::tao::enums::{TaoControlFlow, TaoFullscreenType, TaoTheme, WindowLevel};
 use crate::tao::structs::Position;
 #[cfg(target_os = "macos")]
 use tao::platform::macos::WindowBuilderExtMacOS;
@@ -168,6 +170,12 @@ pub struct WebviewOptions {
   pub back_forward_navigation_gestures: Option<bool>,
 }
 
+#[napi(object)]
+pub struct AppOptions {
+  pub window: Option<BrowserWindowOptions>,
+  pub webview: Option<WebviewOptions>,
+}
+
 type PendingWindow = (
   BrowserWindowOptions,
   Arc<Mutex<Option<crate::tao::structs::Window>>>,
@@ -196,15 +204,13 @@ pub struct Application {
 impl Application {
   #[napi(constructor)]
   pub fn new(_options: Option<ApplicationOptions>) -> Self {
-    // Force X11 backend on Linux before creating the event loop
-    // This must happen BEFORE the event loop is created to prevent Wayland protocol errors
-    #[cfg(target_os = "linux")]
-    {
-      let _ = crate::tao::platform::platform_info();
-    }
+    // Backend preferences (see `prefer_x11_backend`) must be applied BEFORE this point:
+    // the first event loop initializes GTK, which picks its backend only once
+    crate::tao::platform::mark_backend_initialized();
 
     let event_loop = tao::event_loop::EventLoop::new();
     let event_loop_proxy = event_loop.create_proxy();
+    crate::tao::render::thread::register_loop_proxy(&event_loop_proxy);
     Self {
       #[allow(clippy::arc_with_non_send_sync)]
       event_loop: Arc::new(Mutex::new(Some(event_loop))),
@@ -274,50 +280,10 @@ impl Application {
   fn process_pending_items(&self, event_loop_target: &tao::event_loop::EventLoopWindowTarget<()>) {
     let mut pending = self.windows_to_create.lock().unwrap();
     for (opts, win_handle, webviews_to_create) in pending.drain(..) {
-      let mut builder = tao::window::WindowBuilder::new()
-        .with_title(opts.title.clone().unwrap_or_default())
-        .with_inner_size(tao::dpi::LogicalSize::new(
-          opts.width.unwrap_or(800.0),
-          opts.height.unwrap_or(600.0),
-        ))
-        .with_resizable(opts.resizable.unwrap_or(true))
-        .with_decorations(opts.decorations.unwrap_or(true))
-        .with_always_on_top(opts.always_on_top.unwrap_or(false))
-        .with_maximized(opts.maximized.unwrap_or(false))
-        .with_focused(opts.focused.unwrap_or(true))
-        .with_transparent(opts.transparent.unwrap_or(false))
-        .with_visible(opts.visible.unwrap_or(true));
-
-      if opts.transparent.unwrap_or(false) {
-        #[cfg(target_os = "windows")]
-        {
-          builder = builder.with_undecorated_shadow(false);
-        }
-        #[cfg(target_os = "macos")]
-        {
-          builder = builder
-            .with_titlebar_transparent(true)
-            .with_fullsize_content_view(true);
-        }
-        #[cfg(any(
-          target_os = "linux",
-          target_os = "dragonfly",
-          target_os = "freebsd",
-          target_os = "netbsd",
-          target_os = "openbsd"
-        ))]
-        {
-          builder = builder.with_rgba_visual(true);
-        }
-      }
-
-      if let Some(x) = opts.x {
-        if let Some(y) = opts.y {
-          builder = builder.with_position(tao::dpi::LogicalPosition::new(x, y));
-        }
-      }
+      let builder = window_builder_from_options(&opts);
 
       if let Ok(window) = builder.build(event_loop_target) {
+        apply_window_level_from_options(&window, &opts);
         let mut handle = win_handle.lock().unwrap();
         *handle = Some(crate::tao::structs::Window {
           #[allow(clippy::arc_with_non_send_sync)]
@@ -330,59 +296,7 @@ impl Application {
           pending_webviews.drain(..)
         {
           if let Ok(mut builder) = crate::wry::structs::WebViewBuilder::new() {
-            if let Some(url) = webview_opts.url {
-              let _ = builder.with_url(url);
-            }
-            if let Some(html) = webview_opts.html {
-              let _ = builder.with_html(html);
-            }
-            if let Some(width) = webview_opts.width {
-              let _ = builder.with_width(width as u32);
-            }
-            if let Some(height) = webview_opts.height {
-              let _ = builder.with_height(height as u32);
-            }
-            if let Some(x) = webview_opts.x {
-              let _ = builder.with_x(x as i32);
-            }
-            if let Some(y) = webview_opts.y {
-              let _ = builder.with_y(y as i32);
-            }
-            if let Some(user_agent) = webview_opts.user_agent {
-              let _ = builder.with_user_agent(user_agent);
-            }
-            if let Some(transparent) = webview_opts.transparent {
-              let _ = builder.with_transparent(transparent);
-            }
-            if let Some(devtools) = webview_opts.enable_devtools {
-              let _ = builder.with_devtools(devtools);
-            }
-            if let Some(incognito) = webview_opts.incognito {
-              let _ = builder.with_incognito(incognito);
-            }
-            if let Some(hotkeys_zoom) = webview_opts.hotkeys_zoom {
-              let _ = builder.with_hotkeys_zoom(hotkeys_zoom);
-            }
-            if let Some(clipboard) = webview_opts.clipboard {
-              let _ = builder.with_clipboard(clipboard);
-            }
-            if let Some(autoplay) = webview_opts.autoplay {
-              let _ = builder.with_autoplay(autoplay);
-            }
-            if let Some(back_forward_navigation_gestures) =
-              webview_opts.back_forward_navigation_gestures
-            {
-              let _ =
-                builder.with_back_forward_navigation_gestures(back_forward_navigation_gestures);
-            }
-            // Apply preload script as initialization script
-            if let Some(preload) = webview_opts.preload {
-              let init_script = crate::wry::structs::InitializationScript {
-                js: preload,
-                once: false,
-              };
-              let _ = builder.with_initialization_script(init_script);
-            }
+            apply_webview_options(&mut builder, webview_opts);
             // Build the webview - pass the ipc_listeners Arc directly to setup_ipc_handler
             if let Ok(webview) = builder.build_on_window(
               handle.as_ref().unwrap(),
@@ -410,33 +324,30 @@ impl Application {
       #[allow(clippy::arc_with_non_send_sync)]
       let app_ref = Arc::new(self.clone_internal());
 
-      event_loop.run(move |event, event_loop_target, control_flow| {
-        *control_flow = tao::event_loop::ControlFlow::Wait;
-
-        if *exit_requested.lock().unwrap() {
-          *control_flow = tao::event_loop::ControlFlow::Exit;
-          return;
-        }
-
-        app_ref.process_pending_items(event_loop_target);
-
-        if let tao::event::Event::WindowEvent {
-          event: tao::event::WindowEvent::CloseRequested,
-          ..
-        } = event
-        {
-          let mut h = handler_clone.lock().unwrap();
-          if let Some(handler) = h.as_mut() {
-            let _ = handler.call(
-              Ok(ApplicationEvent {
-                event: WebviewApplicationEvent::WindowCloseRequested,
-              }),
-              ThreadsafeFunctionCallMode::NonBlocking,
-            );
+      event_loop.run(crate::tao::structs::blocking_loop(
+        None,
+        move || *exit_requested.lock().unwrap(),
+        move |event, event_loop_target| {
+          app_ref.process_pending_items(event_loop_target);
+          if let tao::event::Event::WindowEvent {
+            event: tao::event::WindowEvent::CloseRequested,
+            ..
+          } = event
+          {
+            let mut h = handler_clone.lock().unwrap();
+            if let Some(handler) = h.as_mut() {
+              let _ = handler.call(
+                Ok(ApplicationEvent {
+                  event: WebviewApplicationEvent::WindowCloseRequested,
+                }),
+                ThreadsafeFunctionCallMode::NonBlocking,
+              );
+            }
+            return true;
           }
-          *control_flow = tao::event_loop::ControlFlow::Exit;
-        }
-      });
+          false
+        },
+      ));
     }
   }
 
@@ -471,6 +382,7 @@ impl Application {
         *control_flow = tao::event_loop::ControlFlow::Poll;
 
         app_ref.process_pending_items(event_loop_target);
+        crate::tao::structs::dispatch_internal_event(&event);
 
         match event {
           tao::event::Event::WindowEvent {
@@ -489,6 +401,9 @@ impl Application {
             keep_running = false;
             *control_flow = tao::event_loop::ControlFlow::Exit;
           }
+          tao::event::Event::MainEventsCleared => {
+            crate::tao::structs::run_idle_tasks(None);
+          }
           tao::event::Event::RedrawEventsCleared => {
             *control_flow = tao::event_loop::ControlFlow::Exit;
           }
@@ -500,6 +415,121 @@ impl Application {
   }
 }
 
+/// Applies the always-on-top / always-on-bottom options to a built window.
+fn apply_window_level_from_options(window: &tao::window::Window, opts: &BrowserWindowOptions) {
+  crate::tao::structs::apply_window_level(
+    window,
+    crate::tao::structs::window_level_from_flags(
+      opts.always_on_top.unwrap_or(false),
+      opts.always_on_bottom.unwrap_or(false),
+    ),
+  );
+}
+
+/// Creates a tao window builder from high-level window options.
+fn window_builder_from_options(opts: &BrowserWindowOptions) -> tao::window::WindowBuilder {
+  let mut builder = tao::window::WindowBuilder::new()
+    .with_title(opts.title.clone().unwrap_or_default())
+    .with_inner_size(tao::dpi::LogicalSize::new(
+      opts.width.unwrap_or(800.0),
+      opts.height.unwrap_or(600.0),
+    ))
+    .with_resizable(opts.resizable.unwrap_or(true))
+    .with_decorations(opts.decorations.unwrap_or(true))
+    .with_maximized(opts.maximized.unwrap_or(false))
+    .with_focused(opts.focused.unwrap_or(true))
+    .with_transparent(opts.transparent.unwrap_or(false))
+    .with_visible(opts.visible.unwrap_or(true));
+
+  if opts.transparent.unwrap_or(false) {
+    #[cfg(target_os = "windows")]
+    {
+      builder = builder.with_undecorated_shadow(false);
+    }
+    #[cfg(target_os = "macos")]
+    {
+      builder = builder
+        .with_titlebar_transparent(true)
+        .with_fullsize_content_view(true);
+    }
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    ))]
+    {
+      builder = builder.with_rgba_visual(true);
+    }
+  }
+
+  if let Some(x) = opts.x {
+    if let Some(y) = opts.y {
+      builder = builder.with_position(tao::dpi::LogicalPosition::new(x, y));
+    }
+  }
+
+  builder
+}
+
+/// Applies high-level webview options to a webview builder.
+fn apply_webview_options(
+  builder: &mut crate::wry::structs::WebViewBuilder,
+  webview_opts: WebviewOptions,
+) {
+  if let Some(url) = webview_opts.url {
+    let _ = builder.with_url(url);
+  }
+  if let Some(html) = webview_opts.html {
+    let _ = builder.with_html(html);
+  }
+  if let Some(width) = webview_opts.width {
+    let _ = builder.with_width(width as u32);
+  }
+  if let Some(height) = webview_opts.height {
+    let _ = builder.with_height(height as u32);
+  }
+  if let Some(x) = webview_opts.x {
+    let _ = builder.with_x(x as i32);
+  }
+  if let Some(y) = webview_opts.y {
+    let _ = builder.with_y(y as i32);
+  }
+  if let Some(user_agent) = webview_opts.user_agent {
+    let _ = builder.with_user_agent(user_agent);
+  }
+  if let Some(transparent) = webview_opts.transparent {
+    let _ = builder.with_transparent(transparent);
+  }
+  if let Some(devtools) = webview_opts.enable_devtools {
+    let _ = builder.with_devtools(devtools);
+  }
+  if let Some(incognito) = webview_opts.incognito {
+    let _ = builder.with_incognito(incognito);
+  }
+  if let Some(hotkeys_zoom) = webview_opts.hotkeys_zoom {
+    let _ = builder.with_hotkeys_zoom(hotkeys_zoom);
+  }
+  if let Some(clipboard) = webview_opts.clipboard {
+    let _ = builder.with_clipboard(clipboard);
+  }
+  if let Some(autoplay) = webview_opts.autoplay {
+    let _ = builder.with_autoplay(autoplay);
+  }
+  if let Some(back_forward_navigation_gestures) = webview_opts.back_forward_navigation_gestures {
+    let _ = builder.with_back_forward_navigation_gestures(back_forward_navigation_gestures);
+  }
+  // Apply preload script as initialization script
+  if let Some(preload) = webview_opts.preload {
+    let init_script = crate::wry::structs::InitializationScript {
+      js: preload,
+      once: false,
+    };
+    let _ = builder.with_initialization_script(init_script);
+  }
+}
+
 #[napi]
 pub struct BrowserWindow {
   pub(crate) inner: Arc<Mutex<Option<crate::tao::structs::Window>>>,
@@ -758,7 +788,11 @@ impl BrowserWindow {
   }
 
   #[napi]
-  pub fn set_always_on_bottom(&self, _enabled: bool) {}
+  pub fn set_always_on_bottom(&self, enabled: bool) {
+    if let Some(win) = self.inner.lock().unwrap().as_ref() {
+      let _ = win.set_always_on_bottom(enabled);
+    }
+  }
 
   #[napi]
   pub fn set_decorations(&self, enabled: bool) {
@@ -822,6 +856,16 @@ fn apply_pending_actions(
   }
 }
 
+impl Webview {
+  fn clone_handle(&self) -> Self {
+    Self {
+      inner: self.inner.clone(),
+      ipc_listeners: self.ipc_listeners.clone(),
+      pending_actions: self.pending_actions.clone(),
+    }
+  }
+}
+
 #[napi]
 impl Webview {
   #[napi(getter)]
@@ -978,7 +1022,1106 @@ impl Webview {
   }
 }
 
+/// Maximum size in bytes of a single bridge message chunk.
+const BRIDGE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Page-side bridge script. `__BRIDGE_ID__` and `__CHUNK_SIZE__` are replaced
+/// before injection. The script is idempotent so it can be injected repeatedly;
+/// injected again by a later bridge, it only takes over that bridge's ID.
+const BRIDGE_SCRIPT: &str = r#"(function () {
+  if (window.__webview_bridge__) {
+    window.__webview_bridge__.__id = __BRIDGE_ID__;
+    return;
+  }
+  var listeners = {};
+  var chunks = {};
+  var nextId = 0;
+  function dispatch(channel, json) {
+    var payload = JSON.parse(json);
+    (listeners[channel] || []).slice().forEach(function (fn) { fn(payload); });
+  }
+  var bridge = window.__webview_bridge__ = {
+    __id: __BRIDGE_ID__,
+    on: function (channel, fn) {
+      (listeners[channel] = listeners[channel] || []).push(fn);
+    },
+    off: function (channel, fn) {
+      var list = listeners[channel];
+      if (list && list.indexOf(fn) >= 0) list.splice(list.indexOf(fn), 1);
+    },
+    emit: function (channel, payload) {
+      var json = JSON.stringify(payload === undefined ? null : payload);
+      var id = nextId++;
+      var parts = [];
+      var start = 0;
+      do {
+        var end = Math.min(start + __CHUNK_SIZE__, json.length);
+        // Keep surrogate pairs in one chunk, a lone surrogate is not valid JSON text
+        var last = json.charCodeAt(end - 1);
+        if (end < json.length && end - start > 1 && last >= 0xd800 && last <= 0xdbff) end--;
+        parts.push(json.slice(start, end));
+        start = end;
+      } while (start < json.length);
+      for (var seq = 0; seq < parts.length; seq++) {
+        window.ipc.postMessage(JSON.stringify({
+          __bridge: bridge.__id, id: id, seq: seq, total: parts.length, channel: channel,
+          data: parts[seq]
+        }));
+      }
+    },
+    __receive: function (id, seq, total, channel, data) {
+      var parts = chunks[id] = chunks[id] || [];
+      parts.push(data);
+      if (parts.length === total) {
+        delete chunks[id];
+        dispatch(channel, parts.join(''));
+      }
+    }
+  };
+})();"#;
+
+type BridgeCallback = ThreadsafeFunction<serde_json::Value>;
+
+/// A page-to-Node message that has not received all of its chunks yet.
+struct PartialBridgeMessage {
+  channel: String,
+  total: u64,
+  received: u64,
+  data: String,
+}
+
+/// Shared state for a bridge, reachable from the IPC handler.
+struct BridgeState {
+  /// Identity of the bridged `Webview`, which has at most one open bridge
+  webview: usize,
+  channels: Mutex<HashMap<String, Vec<BridgeCallback>>>,
+  /// Keyed by the instance of the posting webview and the message ID
+  partial: Mutex<HashMap<(u64, u64), PartialBridgeMessage>>,
+}
+
+static NEXT_BRIDGE_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Registry of live bridges, keyed by bridge ID.
+static BRIDGES: LazyLock<Mutex<HashMap<u32, Arc<BridgeState>>>> =
+  LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Splits a string into chunks of at most `max_bytes` bytes on char boundaries.
+fn chunk_str(s: &str, max_bytes: usize) -> Vec<&str> {
+  if s.is_empty() || max_bytes == 0 {
+    return vec![s];
+  }
+  let mut chunks = Vec::new();
+  let mut start = 0;
+  while start < s.len() {
+    let mut end = (start + max_bytes).min(s.len());
+    while !s.is_char_boundary(end) {
+      end -= 1;
+    }
+    if end == start {
+      // A single character is larger than max_bytes, emit it whole
+      end = start + s[start..].chars().next().map_or(1, |c| c.len_utf8());
+    }
+    chunks.push(&s[start..end]);
+    start = end;
+  }
+  chunks
+}
+
+/// Routes an IPC message posted by webview `instance` to its bridge.
+///
+/// Returns true if the message was a bridge message and has been consumed,
+/// in which case it must not be forwarded to the raw IPC listeners.
+pub(crate) fn dispatch_bridge_message(instance: u64, msg: &str) -> bool {
+  if !msg.starts_with("{\"__bridge\":") {
+    return false;
+  }
+  let Ok(envelope) = serde_json::from_str::<serde_json::Value>(msg) else {
+    return false;
+  };
+  let (Some(bridge_id), Some(id), Some(total), Some(channel), Some(data)) = (
+    envelope["__bridge"].as_u64(),
+    envelope["id"].as_u64(),
+    envelope["total"].as_u64(),
+    envelope["channel"].as_str(),
+    envelope["data"].as_str(),
+  ) else {
+    return false;
+  };
+  let Some(state) = BRIDGES.lock().unwrap().get(&(bridge_id as u32)).cloned() else {
+    // The bridge was closed, drop the message
+    return true;
+  };
+
+  let json = {
+    let mut partial = state.partial.lock().unwrap();
+    let entry = partial
+      .entry((instance, id))
+      .or_insert_with(|| PartialBridgeMessage {
+        channel: channel.to_string(),
+        total,
+        received: 0,
+        data: String::new(),
+      });
+    entry.data.push_str(data);
+    entry.received += 1;
+    if entry.received < entry.total {
+      return true;
+    }
+    partial.remove(&(instance, id)).map(|m| (m.channel, m.data))
+  };
+
+  if let Some((channel, data)) = json {
+    match serde_json::from_str::<serde_json::Value>(&data) {
+      Ok(payload) => {
+        let channels = state.channels.lock().unwrap();
+        for callback in channels.get(&channel).into_iter().flatten() {
+          let _ = callback.call(Ok(payload.clone()), ThreadsafeFunctionCallMode::NonBlocking);
+        }
+      }
+      Err(e) => {
+        log_warn!(
+          "bridge",
+          "Dropping message on channel '{}': invalid JSON payload: {}",
+          channel,
+          e
+        );
+      }
+    }
+  }
+  true
+}
+
+/// Drops the chunks of page messages webview `instance` had not finished sending.
+///
+/// Called when a page starts loading, as the page numbering them is gone.
+pub(crate) fn discard_partial_bridge_messages(instance: u64) {
+  for state in BRIDGES.lock().unwrap().values() {
+    state
+      .partial
+      .lock()
+      .unwrap()
+      .retain(|(posted_by, _), _| *posted_by != instance);
+  }
+}
+
+/// Channel-based messaging between Node and the page of a webview.
+///
+/// Payloads are serialized as JSON in both directions. In the page, the bridge
+/// is available as `window.__webview_bridge__` with `on(channel, fn)`,
+/// `off(channel, fn)` and `emit(channel, payload)`.
+///
+/// Messages are delivered in the order they were emitted, per direction.
+/// Payloads larger than 64 KiB are split into chunks and reassembled on the
+/// receiving side before listeners are called, so a listener always sees
+/// the complete payload. A page message still incomplete when the page
+/// navigates away is dropped.
+///
+/// The page-side script is injected when the bridge is created and again with
+/// every `emit`. To make it available immediately after navigation, pass
+/// [`Bridge::script`] as the webview `preload` option.
+///
+/// A webview has a single page-side bridge, so creating a second `Bridge` for
+/// it throws until the first is closed or garbage-collected.
+#[napi]
+pub struct Bridge {
+  id: u32,
+  state: Arc<BridgeState>,
+  webview: Webview,
+  next_message_id: AtomicU64,
+}
+
+#[napi]
+impl Bridge {
+  #[napi(constructor)]
+  pub fn new(webview: &Webview) -> Result<Self> {
+    let webview_key = Arc::as_ptr(&webview.inner) as usize;
+    let id = NEXT_BRIDGE_ID.fetch_add(1, Ordering::SeqCst);
+    let state = Arc::new(BridgeState {
+      webview: webview_key,
+      channels: Mutex::new(HashMap::new()),
+      partial: Mutex::new(HashMap::new()),
+    });
+    {
+      let mut bridges = BRIDGES.lock().unwrap();
+      // The page has a single bridge object, reporting one bridge ID
+      if bridges.values().any(|bridge| bridge.webview == webview_key) {
+        return Err(napi::Error::new(
+          napi::Status::InvalidArg,
+          "The webview already has an open bridge; close it first".to_string(),
+        ));
+      }
+      bridges.insert(id, state.clone());
+    }
+
+    let bridge = Self {
+      id,
+      state,
+      webview: webview.clone_handle(),
+      next_message_id: AtomicU64::new(0),
+    };
+    let _ = bridge.webview.evaluate_script(bridge.script());
+    Ok(bridge)
+  }
+
+  /// The page-side bridge script, suitable for the webview `preload` option.
+  #[napi(getter)]
+  pub fn script(&self) -> String {
+    BRIDGE_SCRIPT
+      .replace("__BRIDGE_ID__", &self.id.to_string())
+      .replace("__CHUNK_SIZE__", &BRIDGE_CHUNK_SIZE.to_string())
+  }
+
+  /// Sends a payload to the page listeners of `channel`.
+  #[napi]
+  pub fn emit(&self, channel: String, payload: serde_json::Value) -> Result<()> {
+    let json = serde_json::to_string(&payload).map_err(|e| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to serialize payload: {}", e),
+      )
+    })?;
+    let channel_json = serde_json::to_string(&channel).map_err(|e| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to serialize channel: {}", e),
+      )
+    })?;
+
+    let id = self.next_message_id.fetch_add(1, Ordering::SeqCst);
+    let chunks = chunk_str(&json, BRIDGE_CHUNK_SIZE);
+    let total = chunks.len();
+    for (seq, chunk) in chunks.into_iter().enumerate() {
+      // Chunks are JSON text; encode them as JS string literals
+      let chunk_json = serde_json::to_string(chunk).map_err(|e| {
+        napi::Error::new(
+          napi::Status::GenericFailure,
+          format!("Failed to serialize payload: {}", e),
+        )
+      })?;
+      let receive = format!(
+        "window.__webview_bridge__.__receive({}, {}, {}, {}, {});",
+        id, seq, total, channel_json, chunk_json
+      );
+      let js = if seq == 0 {
+        format!("{}\n{}", self.script(), receive)
+      } else {
+        receive
+      };
+      self.webview.evaluate_script(js)?;
+    }
+    Ok(())
+  }
+
+  /// Registers a callback for payloads the page emits on `channel`.
+  #[napi(ts_args_type = "channel: string, callback: (error: Error | null, payload: any) => void")]
+  pub fn on(&self, channel: String, callback: BridgeCallback) {
+    self
+      .state
+      .channels
+      .lock()
+      .unwrap()
+      .entry(channel)
+      .or_default()
+      .push(callback);
+  }
+
+  /// Removes all callbacks for `channel`.
+  #[napi]
+  pub fn off(&self, channel: String) {
+    self.state.channels.lock().unwrap().remove(&channel);
+  }
+
+  /// Unregisters the bridge. Page messages for it are dropped afterwards.
+  #[napi]
+  pub fn close(&self) {
+    BRIDGES.lock().unwrap().remove(&self.id);
+  }
+}
+
+impl Drop for Bridge {
+  fn drop(&mut self) {
+    // Releases the channel callbacks, which would keep Node running
+    self.close();
+  }
+}
+
+/// A single window with an attached webview, created in one call.
+///
+/// Creates the event loop, the window and the webview in the order the
+/// platform requires (GTK init, event loop, window, webview). On Linux only
+/// one event loop may exist per process, so only one `App` can be created.
+#[napi]
+pub struct App {
+  event_loop: crate::tao::structs::EventLoop,
+  window: crate::tao::structs::Window,
+  webview: crate::wry::structs::WebView,
+}
+
+#[napi]
+impl App {
+  #[napi(constructor)]
+  pub fn new(
+    options: Option<AppOptions>,
+    ipc_handler: Option<crate::wry::structs::IpcHandler>,
+  ) -> Result<Self> {
+    let (window_opts, webview_opts) = match options {
+      Some(opts) => (opts.window, opts.webview),
+      None => (None, None),
+    };
+
+    // EventLoop::new enforces the single event loop rule on Linux/GTK
+    let event_loop = crate::tao::structs::EventLoop::new()?;
+    let el = event_loop.inner.as_ref().ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Event loop already running or consumed".to_string(),
+      )
+    })?;
+
+    let window_opts = window_opts.unwrap_or(BrowserWindowOptions {
+      resizable: Some(true),
+      title: Some("Webview".to_string()),
+      width: Some(800.0),
+      height: Some(600.0),
+      x: None,
+      y: None,
+      content_protection: None,
+      always_on_top: None,
+      always_on_bottom: None,
+      visible: Some(true),
+      decorations: Some(true),
+      visible_on_all_workspaces: None,
+      maximized: None,
+      maximizable: None,
+      minimizable: None,
+      focused: None,
+      transparent: None,
+      fullscreen: None,
+    });
+    let tao_window = window_builder_from_options(&window_opts)
+      .build(el)
+      .map_err(|e| {
+        napi::Error::new(
+          napi::Status::GenericFailure,
+          format!("Failed to create window: {}", e),
+        )
+      })?;
+    apply_window_level_from_options(&tao_window, &window_opts);
+    let window = crate::tao::structs::Window {
+      #[allow(clippy::arc_with_non_send_sync)]
+      inner: Some(Arc::new(Mutex::new(tao_window))),
+    };
+
+    let mut builder = crate::wry::structs::WebViewBuilder::new()?;
+    if let Some(webview_opts) = webview_opts {
+      apply_webview_options(&mut builder, webview_opts);
+    }
+    if let Some(handler) = ipc_handler {
+      builder.with_ipc_handler(handler)?;
+    }
+    let webview = builder.build_on_window(&window, "webview".to_string(), None)?;
+
+    Ok(Self {
+      event_loop,
+      window,
+      webview,
+    })
+  }
+
+  /// Returns a handle to the window.
+  #[napi]
+  pub fn window(&self) -> crate::tao::structs::Window {
+    crate::tao::structs::Window {
+      inner: self.window.inner.clone(),
+    }
+  }
+
+  /// Returns a handle to the webview.
+  #[napi]
+  pub fn webview(&self) -> crate::wry::structs::WebView {
+    self.webview.clone_handle()
+  }
+
+  /// Returns a proxy for waking up the event loop.
+  #[napi]
+  pub fn create_proxy(&self) -> Result<crate::tao::structs::EventLoopProxy> {
+    self.event_loop.create_proxy()
+  }
+
+  /// Runs the event loop until the window is closed.
+  #[napi]
+  pub fn run(&mut self) -> Result<()> {
+    self.event_loop.run()
+  }
+
+  /// Runs a single iteration of the event loop.
+  #[napi]
+  pub fn run_iteration(&mut self) -> Result<bool> {
+    self.event_loop.run_iteration()
+  }
+}
+
+/// A shown splash window together with what it needs to redraw itself.
+struct SplashWindow {
+  window: crate::tao::structs::Window,
+  renderer: crate::tao::render::PixelRenderer,
+  rgba: Vec<u8>,
+}
+
+/// A splash window registered for auto-close, see [`tick_splashes`].
+type SplashSlot = std::rc::Rc<std::cell::RefCell<Option<SplashWindow>>>;
+
+thread_local! {
+  static SPLASH_DEADLINES: std::cell::RefCell<Vec<(std::time::Instant, SplashSlot)>> =
+    const { std::cell::RefCell::new(Vec::new()) };
+  /// Splash windows not yet drawn since they were shown, see [`draw_splashes_on_expose`].
+  static UNDRAWN_SPLASHES: std::cell::RefCell<Vec<SplashSlot>> =
+    const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Draws splash windows on their first `Resized`/`RedrawRequested` after being shown.
+///
+/// A frame presented before the window is mapped is not kept by every backend,
+/// so the image is presented again once the window is actually on screen.
+pub(crate) fn draw_splashes_on_expose(event: &tao::event::Event<'_, ()>) {
+  let window_id = match event {
+    tao::event::Event::RedrawRequested(window_id) => *window_id,
+    tao::event::Event::WindowEvent {
+      window_id,
+      event: tao::event::WindowEvent::Resized(_),
+      ..
+    } => *window_id,
+    _ => return,
+  };
+  UNDRAWN_SPLASHES.with(|splashes| {
+    splashes.borrow_mut().retain(|slot| {
+      let slot = slot.borrow();
+      let Some(splash) = slot.as_ref() else {
+        return false;
+      };
+      let Some(inner) = &splash.window.inner else {
+        return false;
+      };
+      if inner.lock().unwrap().id() != window_id {
+        return true;
+      }
+      if let Err(e) = splash.renderer.render_bytes(&splash.window, &splash.rgba) {
+        log_warn!("splash", "Failed to draw splash window: {}", e);
+      }
+      false
+    });
+  });
+}
+
+/// Closes splash windows whose duration has elapsed.
+///
+/// Called by the event loops when they become idle. Returns the next deadline, if any.
+pub(crate) fn tick_splashes() -> Option<std::time::Instant> {
+  SPLASH_DEADLINES.with(|deadlines| {
+    let mut deadlines = deadlines.borrow_mut();
+    let now = std::time::Instant::now();
+    deadlines.retain(|(deadline, slot)| {
+      if *deadline <= now {
+        close_splash_slot(slot);
+        false
+      } else {
+        slot.borrow().is_some()
+      }
+    });
+    deadlines.iter().map(|(deadline, _)| *deadline).min()
+  })
+}
+
+fn close_splash_slot(slot: &SplashSlot) {
+  if let Some(splash) = slot.borrow_mut().take() {
+    if let Some(inner) = &splash.window.inner {
+      inner.lock().unwrap().set_visible(false);
+    }
+    // Dropping the last handle releases the render state and destroys the window
+  }
+}
+
+/// A borderless, centered, always-on-top window showing an image while the app starts.
+#[napi]
+pub struct Splash {
+  slot: SplashSlot,
+}
+
+#[napi]
+impl Splash {
+  /// Shows a PNG (or JPEG, BMP, WEBP) image in a splash window.
+  ///
+  /// The window is undecorated, always on top and centered on the primary
+  /// monitor, and transparent where the screen supports it (on Linux/BSD this
+  /// needs a compositor; otherwise the image is shown over black), sized to the image in physical pixels. If `duration_ms` is set
+  /// the splash closes itself after that long, as long as the event loop is running;
+  /// otherwise call `close()`.
+  #[napi(factory)]
+  pub fn show(
+    event_loop: &crate::tao::structs::EventLoop,
+    png: Buffer,
+    duration_ms: Option<u32>,
+  ) -> Result<Self> {
+    let el = event_loop.inner.as_ref().ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Event loop already running or consumed".to_string(),
+      )
+    })?;
+
+    let image = crate::tao::functions::decode_image(png)?;
+    let transparent = crate::tao::platform::supports_rgba_visual();
+
+    let mut builder = tao::window::WindowBuilder::new()
+      .with_title("")
+      .with_inner_size(tao::dpi::PhysicalSize::new(image.width, image.height))
+      .with_decorations(false)
+      .with_resizable(false)
+      .with_transparent(transparent)
+      .with_visible(false);
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    ))]
+    {
+      builder = builder.with_rgba_visual(transparent);
+    }
+    if let Some(monitor) = el.primary_monitor() {
+      let size = monitor.size();
+      let position = monitor.position();
+      builder = builder.with_position(tao::dpi::PhysicalPosition::new(
+        position.x + (size.width as i32 - image.width as i32) / 2,
+        position.y + (size.height as i32 - image.height as i32) / 2,
+      ));
+    }
+
+    let tao_window = builder.build(el).map_err(|e| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to create splash window: {}", e),
+      )
+    })?;
+    crate::tao::structs::apply_window_level(&tao_window, WindowLevel::AlwaysOnTop);
+    let window = crate::tao::structs::Window {
+      #[allow(clippy::arc_with_non_send_sync)]
+      inner: Some(Arc::new(Mutex::new(tao_window))),
+    };
+
+    let mut renderer = crate::tao::render::PixelRenderer::new(image.width, image.height);
+    renderer.set_background_color(0, 0, 0, if transparent { 0 } else { 255 });
+    window.set_visible(true)?;
+    renderer.render_bytes(&window, &image.rgba)?;
+    window.request_redraw()?;
+
+    let slot: SplashSlot = std::rc::Rc::new(std::cell::RefCell::new(Some(SplashWindow {
+      window,
+      renderer,
+      rgba: image.rgba.to_vec(),
+    })));
+    UNDRAWN_SPLASHES.with(|splashes| splashes.borrow_mut().push(slot.clone()));
+    if let Some(duration_ms) = duration_ms {
+      let deadline =
+        std::time::Instant::now() + std::time::Duration::from_millis(duration_ms as u64);
+      SPLASH_DEADLINES.with(|deadlines| deadlines.borrow_mut().push((deadline, slot.clone())));
+    }
+
+    Ok(Self { slot })
+  }
+
+  /// Whether the splash window is still shown.
+  #[napi(getter)]
+  pub fn is_visible(&self) -> bool {
+    self.slot.borrow().is_some()
+  }
+
+  /// Closes the splash window.
+  #[napi]
+  pub fn close(&self) {
+    close_splash_slot(&self.slot);
+  }
+}
+
+/// A window, and optionally its renderer, recreated by [`apply_config`].
+#[napi]
+pub struct RestoredWindow {
+  window: crate::tao::structs::Window,
+  renderer: Option<crate::tao::render::PixelRenderer>,
+}
+
+#[napi]
+impl RestoredWindow {
+  /// Returns a handle to the window.
+  #[napi]
+  pub fn window(&self) -> crate::tao::structs::Window {
+    crate::tao::structs::Window {
+      inner: self.window.inner.clone(),
+    }
+  }
+
+  /// Returns the renderer, if the config had one.
+  #[napi]
+  pub fn renderer(&self) -> Option<crate::tao::render::PixelRenderer> {
+    self.renderer.clone()
+  }
+}
+
+/// Recreates a window and its renderer from a `Window.exportConfig` snapshot.
+///
+/// The window is built hidden, configured, and then shown if `visible` is set, so
+/// it does not flash at its default size. Settings a platform cannot apply are
+/// skipped as documented on `WindowConfig`.
+#[napi]
+pub fn apply_config(
+  event_loop: &crate::tao::structs::EventLoop,
+  config: crate::tao::structs::WindowConfig,
+) -> Result<RestoredWindow> {
+  let mut builder = crate::tao::structs::WindowBuilder::new()?;
+  builder.with_title(config.title)?;
+  builder.with_inner_size(
+    config.width.round().max(1.0) as u32,
+    config.height.round().max(1.0) as u32,
+  )?;
+  if let (Some(x), Some(y)) = (config.x, config.y) {
+    if !crate::tao::platform::is_wayland_backend() {
+      builder.with_position(x, y)?;
+    }
+  }
+  builder.with_resizable(config.resizable)?;
+  builder.with_decorated(config.decorated)?;
+  builder.with_transparent(config.transparent)?;
+  builder.with_always_on_top(config.level == WindowLevel::AlwaysOnTop)?;
+  builder.with_always_on_bottom(config.level == WindowLevel::AlwaysOnBottom)?;
+  builder.with_maximized(config.maximized)?;
+  if let Some(theme) = config.theme {
+    builder.with_theme(theme)?;
+  }
+  builder.with_visible(false)?;
+  let window = builder.build(event_loop)?;
+
+  if let Some(theme) = config.theme {
+    window.set_theme(theme)?;
+  }
+  if config.opacity < 1.0 {
+    window.set_opacity(config.opacity)?;
+  }
+  if config.aspect_ratio.is_some() {
+    window.set_aspect_ratio(config.aspect_ratio)?;
+  }
+  if let Some(mode) = config.fullscreen {
+    window.set_fullscreen(Some(mode), None, None)?;
+  }
+  window.set_visible(config.visible)?;
+
+  Ok(RestoredWindow {
+    window,
+    renderer: config
+      .renderer
+      .map(crate::tao::render::PixelRenderer::with_options),
+  })
+}
+
+/// Direction in which a [`SplitView`] lays out its panes.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitOrientation {
+  /// Panes side by side, separated by vertical splitters.
+  Horizontal,
+  /// Panes stacked top to bottom, separated by horizontal splitters.
+  Vertical,
+}
+
+#[napi(object)]
+pub struct SplitViewOptions {
+  /// Layout direction (default: Horizontal).
+  pub orientation: Option<SplitOrientation>,
+  /// Width of the draggable splitter in physical pixels (default: 6).
+  pub splitter_size: Option<u32>,
+  /// Minimum size of each pane along the split axis in physical pixels (default: 50).
+  pub min_pane_size: Option<u32>,
+}
+
+struct SplitViewState {
+  window: std::sync::Weak<Mutex<tao::window::Window>>,
+  window_id: tao::window::WindowId,
+  panes: Vec<crate::wry::structs::WebView>,
+  fractions: Vec<f64>,
+  orientation: SplitOrientation,
+  splitter_size: u32,
+  min_pane_size: u32,
+  size: (u32, u32),
+  hovered: Option<usize>,
+  dragging: Option<usize>,
+}
+
+thread_local! {
+  static SPLIT_VIEWS: std::cell::RefCell<Vec<std::rc::Weak<std::cell::RefCell<SplitViewState>>>> =
+    const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Computes `(offset, size)` of each pane along the split axis.
+fn split_layout(total: u32, fractions: &[f64], splitter_size: u32) -> Vec<(u32, u32)> {
+  let count = fractions.len() as u32;
+  if count == 0 {
+    return Vec::new();
+  }
+  let available = total.saturating_sub(splitter_size * (count - 1));
+  let mut layout = Vec::with_capacity(fractions.len());
+  let mut offset = 0;
+  let mut used = 0;
+  for (i, fraction) in fractions.iter().enumerate() {
+    let size = if i + 1 == fractions.len() {
+      available - used
+    } else {
+      ((available as f64 * fraction).round() as u32).min(available - used)
+    };
+    layout.push((offset, size));
+    used += size;
+    offset += size + splitter_size;
+  }
+  layout
+}
+
+/// Moves the splitter after pane `index` to `position`, keeping both panes at least `min_size`.
+fn drag_splitter(
+  fractions: &mut [f64],
+  index: usize,
+  position: f64,
+  total: u32,
+  splitter_size: u32,
+  min_size: u32,
+) {
+  if index + 1 >= fractions.len() {
+    return;
+  }
+  let layout = split_layout(total, fractions, splitter_size);
+  let start = layout[index].0;
+  let combined = layout[index].1 + layout[index + 1].1;
+  let min_size = min_size.min(combined / 2) as f64;
+
+  let first = (position - start as f64 - splitter_size as f64 / 2.0)
+    .clamp(min_size, combined as f64 - min_size)
+    .round();
+  let available = layout.iter().map(|(_, size)| *size).sum::<u32>().max(1) as f64;
+  fractions[index] = first / available;
+  fractions[index + 1] = (combined as f64 - first) / available;
+}
+
+impl SplitViewState {
+  fn axis(&self, x: f64, y: f64) -> f64 {
+    match self.orientation {
+      SplitOrientation::Horizontal => x,
+      SplitOrientation::Vertical => y,
+    }
+  }
+
+  fn total(&self) -> u32 {
+    match self.orientation {
+      SplitOrientation::Horizontal => self.size.0,
+      SplitOrientation::Vertical => self.size.1,
+    }
+  }
+
+  fn splitter_at(&self, position: f64) -> Option<usize> {
+    let layout = split_layout(self.total(), &self.fractions, self.splitter_size);
+    layout
+      .iter()
+      .take(layout.len().saturating_sub(1))
+      .position(|(offset, size)| {
+        let start = (offset + size) as f64;
+        position >= start && position < start + self.splitter_size as f64
+      })
+  }
+
+  fn relayout(&self) {
+    let layout = split_layout(self.total(), &self.fractions, self.splitter_size);
+    for (pane, (offset, size)) in self.panes.iter().zip(layout) {
+      let (x, y, width, height) = match self.orientation {
+        SplitOrientation::Horizontal => (offset, 0, size, self.size.1),
+        SplitOrientation::Vertical => (0, offset, self.size.0, size),
+      };
+      if let Some(inner) = &pane.inner {
+        let _ = inner.lock().unwrap().set_bounds(wry::Rect {
+          position: tao::dpi::PhysicalPosition::new(x as i32, y as i32).into(),
+          size: tao::dpi::PhysicalSize::new(width, height).into(),
+        });
+      }
+    }
+  }
+
+  fn set_cursor(&self, resize: bool) {
+    if let Some(window) = self.window.upgrade() {
+      let icon = match (resize, self.orientation) {
+        (false, _) => tao::window::CursorIcon::Default,
+        (true, SplitOrientation::Horizontal) => tao::window::CursorIcon::ColResize,
+        (true, SplitOrientation::Vertical) => tao::window::CursorIcon::RowResize,
+      };
+      window.lock().unwrap().set_cursor_icon(icon);
+    }
+  }
+
+  fn handle_event(&mut self, event: &tao::event::WindowEvent) {
+    match event {
+      tao::event::WindowEvent::Resized(size) => {
+        self.size = (size.width, size.height);
+        self.relayout();
+      }
+      tao::event::WindowEvent::CursorMoved { position, .. } => {
+        let position = self.axis(position.x, position.y);
+        if let Some(index) = self.dragging {
+          let total = self.total();
+          drag_splitter(
+            &mut self.fractions,
+            index,
+            position,
+            total,
+            self.splitter_size,
+            self.min_pane_size,
+          );
+          self.relayout();
+        } else {
+          let hovered = self.splitter_at(position);
+          if hovered.is_some() != self.hovered.is_some() {
+            self.set_cursor(hovered.is_some());
+          }
+          self.hovered = hovered;
+        }
+      }
+      tao::event::WindowEvent::CursorLeft { .. }
+        if self.dragging.is_none() && self.hovered.take().is_some() =>
+      {
+        self.set_cursor(false);
+      }
+      tao::event::WindowEvent::MouseInput {
+        state,
+        button: tao::event::MouseButton::Left,
+        ..
+      } => match state {
+        tao::event::ElementState::Pressed => self.dragging = self.hovered,
+        _ => self.dragging = None,
+      },
+      _ => {}
+    }
+  }
+}
+
+/// Forwards a window event to the split views of that window.
+pub(crate) fn split_views_handle_event(
+  window_id: tao::window::WindowId,
+  event: &tao::event::WindowEvent,
+) {
+  SPLIT_VIEWS.with(|views| {
+    views.borrow_mut().retain(|view| {
+      let Some(view) = view.upgrade() else {
+        return false;
+      };
+      let mut view = view.borrow_mut();
+      if view.window_id == window_id {
+        view.handle_event(event);
+      }
+      true
+    })
+  });
+}
+
+/// Tiles child webviews in one window, separated by draggable splitters.
+///
+/// Panes are re-laid out automatically when the window is resized, and the
+/// splitters can be dragged with the mouse; both are driven by the window events
+/// of a running event loop. Sizes are in physical pixels.
+///
+/// The splitters are the gaps between the webviews where the window itself is
+/// visible, so set a window background color to style them.
+///
+/// ## Platform-specific
+///
+/// - **Linux**: Child webviews are only supported on X11.
+#[napi]
+pub struct SplitView {
+  state: std::rc::Rc<std::cell::RefCell<SplitViewState>>,
+}
+
+#[napi]
+impl SplitView {
+  #[napi(constructor)]
+  pub fn new(
+    window: &crate::tao::structs::Window,
+    options: Option<SplitViewOptions>,
+  ) -> Result<Self> {
+    let inner = window.inner.as_ref().ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Window not initialized".to_string(),
+      )
+    })?;
+    let (window_id, size) = {
+      let window = inner.lock().unwrap();
+      let size = window.inner_size();
+      (window.id(), (size.width, size.height))
+    };
+    let options = options.unwrap_or(SplitViewOptions {
+      orientation: None,
+      splitter_size: None,
+      min_pane_size: None,
+    });
+
+    let state = std::rc::Rc::new(std::cell::RefCell::new(SplitViewState {
+      window: Arc::downgrade(inner),
+      window_id,
+      panes: Vec::new(),
+      fractions: Vec::new(),
+      orientation: options.orientation.unwrap_or(SplitOrientation::Horizontal),
+      splitter_size: options.splitter_size.unwrap_or(6),
+      min_pane_size: options.min_pane_size.unwrap_or(50),
+      size,
+      hovered: None,
+      dragging: None,
+    }));
+    SPLIT_VIEWS.with(|views| views.borrow_mut().push(std::rc::Rc::downgrade(&state)));
+
+    Ok(Self { state })
+  }
+
+  /// Builds a webview as a new pane at the end; all panes are resized equally.
+  #[napi]
+  pub fn add_pane(
+    &self,
+    window: &crate::tao::structs::Window,
+    builder: &mut crate::wry::structs::WebViewBuilder,
+  ) -> Result<crate::wry::structs::WebView> {
+    let mut state = self.state.borrow_mut();
+    let label = format!("pane-{}", state.panes.len());
+    let webview = builder.build_as_child(window, label)?;
+    state.panes.push(webview.clone_handle());
+    let count = state.panes.len();
+    state.fractions = vec![1.0 / count as f64; count];
+    state.relayout();
+    Ok(webview)
+  }
+
+  /// Gets the size of each pane as a fraction of the space available to panes.
+  #[napi(getter)]
+  pub fn ratios(&self) -> Vec<f64> {
+    self.state.borrow().fractions.clone()
+  }
+
+  /// Sets the relative size of each pane; values are normalized to sum to 1.
+  #[napi]
+  pub fn set_ratios(&self, ratios: Vec<f64>) -> Result<()> {
+    let mut state = self.state.borrow_mut();
+    let sum: f64 = ratios.iter().sum();
+    if ratios.len() != state.panes.len() || ratios.iter().any(|r| *r < 0.0) || sum <= 0.0 {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        format!(
+          "Expected {} non-negative ratios, got {:?}",
+          state.panes.len(),
+          ratios
+        ),
+      ));
+    }
+    state.fractions = ratios.iter().map(|r| r / sum).collect();
+    state.relayout();
+    Ok(())
+  }
+
+  /// Gets the layout direction.
+  #[napi(getter)]
+  pub fn orientation(&self) -> SplitOrientation {
+    self.state.borrow().orientation
+  }
+
+  /// Sets the layout direction.
+  #[napi]
+  pub fn set_orientation(&self, orientation: SplitOrientation) {
+    let mut state = self.state.borrow_mut();
+    state.orientation = orientation;
+    state.relayout();
+  }
+
+  /// Sets the minimum size of each pane along the split axis.
+  #[napi]
+  pub fn set_min_pane_size(&self, min_pane_size: u32) {
+    self.state.borrow_mut().min_pane_size = min_pane_size;
+  }
+}
+
 #[napi]
 pub fn get_webview_version() -> String {
   wry::webview_version().unwrap_or("unknown".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_split_layout() {
+    assert_eq!(
+      split_layout(806, &[0.5, 0.5], 6),
+      vec![(0, 400), (406, 400)]
+    );
+    // The last pane takes the rounding remainder
+    assert_eq!(
+      split_layout(100, &[1.0 / 3.0; 3], 2),
+      vec![(0, 32), (34, 32), (68, 32)]
+    );
+  }
+
+  #[test]
+  fn test_drag_splitter_clamps_to_min_size() {
+    let mut fractions = vec![0.5, 0.5];
+    drag_splitter(&mut fractions, 0, 203.0, 806, 6, 50);
+    assert_eq!(split_layout(806, &fractions, 6), vec![(0, 200), (206, 600)]);
+
+    drag_splitter(&mut fractions, 0, 10.0, 806, 6, 50);
+    assert_eq!(split_layout(806, &fractions, 6), vec![(0, 50), (56, 750)]);
+
+    drag_splitter(&mut fractions, 0, 900.0, 806, 6, 50);
+    assert_eq!(split_layout(806, &fractions, 6), vec![(0, 750), (756, 50)]);
+  }
+
+  #[test]
+  fn test_one_open_bridge_per_webview() {
+    let webview = Webview {
+      #[allow(clippy::arc_with_non_send_sync)]
+      inner: Arc::new(Mutex::new(None)),
+      ipc_listeners: Arc::new(Mutex::new(Vec::new())),
+      pending_actions: Arc::new(Mutex::new(Vec::new())),
+    };
+    let first = Bridge::new(&webview).unwrap();
+    assert!(Bridge::new(&webview).is_err());
+
+    let first_id = first.id;
+    drop(first);
+    assert!(!BRIDGES.lock().unwrap().contains_key(&first_id));
+    let second = Bridge::new(&webview).unwrap();
+    second.close();
+    assert!(Bridge::new(&webview).is_ok());
+  }
+
+  #[test]
+  fn test_chunk_str_small_payload_single_chunk() {
+    assert_eq!(chunk_str("{\"a\":1}", 64), vec!["{\"a\":1}"]);
+    assert_eq!(chunk_str("", 64), vec![""]);
+  }
+
+  #[test]
+  fn test_chunk_str_splits_and_reassembles() {
+    let payload = "x".repeat(10);
+    let chunks = chunk_str(&payload, 4);
+    assert_eq!(chunks, vec!["xxxx", "xxxx", "xx"]);
+    assert_eq!(chunks.concat(), payload);
+  }
+
+  #[test]
+  fn test_chunk_str_respects_char_boundaries() {
+    // 'é' is 2 bytes, a 3 byte limit must not split it
+    let payload = "éééé";
+    let chunks = chunk_str(payload, 3);
+    assert!(chunks.iter().all(|c| c.len() <= 3));
+    assert_eq!(chunks.concat(), payload);
+  }
+}
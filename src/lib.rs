@@ -7,12 +7,18 @@
 //! in Node.js applications. All methods, APIs, enums, and types are exported
 //! directly for Node.js composition.
 
+// Logging hook (must be declared first so its macros are visible below)
+#[macro_use]
+pub mod logging;
+
 // Wry bindings
 pub mod wry;
 
 // Tao bindings
 pub mod tao;
 
+pub use logging::{set_log_handler, set_log_level, LogLevel, LogRecord};
+
 // Re-export wry types
 pub use wry::enums::{
   BackgroundThrottlingPolicy, DragDropEvent, Error, NewWindowResponse, PageLoadEvent, ProxyConfig,
@@ -20,29 +26,46 @@ pub use wry::enums::{
 };
 pub use wry::functions::webview_version;
 pub use wry::structs::{
-  InitializationScript, NewWindowFeatures, NewWindowOpener, ProxyEndpoint, Rect,
-  RequestAsyncResponder, WebContext, WebView, WebViewAttributes, WebViewBuilder,
+  CustomProtocolHandler, CustomProtocolResponse, InitializationScript, IpcRequest,
+  IpcRequestHandler, NewWindowFeatures, NewWindowOpener, PageLoadHandler, PageLoadInfo,
+  ProxyEndpoint, Rect, RequestAsyncResponder, WebContext, WebView, WebViewAttributes,
+  WebViewBuilder,
 };
 pub use wry::types::{Result, WebViewId, RGBA};
 
 // Re-export tao types
 pub use tao::enums::{
-  CursorIcon, DeviceEvent, ElementState, Force, Key, KeyCode, KeyLocation, ModifiersState,
-  MouseButton, MouseButtonState, ProgressState, ResizeDirection, ScaleMode, StartCause,
-  TaoControlFlow, TaoFullscreenType, TaoTheme, TouchPhase, UserAttentionType, WindowEvent,
+  AppEventType, CursorGrabMode, CursorIcon, DeviceEvent, ElementState, Force, Key, KeyCode,
+  KeyLocation, ModifiersState, MouseButton, MouseButtonState, OverlayCorner, PixelFormat,
+  ProgressState, ResizeDirection, ScaleFilter, ScaleMode, ScrollUnit, StartCause, TaoControlFlow,
+  TaoFullscreenType, TaoTheme, TouchPhase, UserAttentionType, WindowEvent,
+};
+pub use tao::functions::{
+  available_monitors, build_info, decode_image, prefer_x11_backend, primary_monitor, tao_version,
 };
-pub use tao::functions::{available_monitors, primary_monitor, tao_version};
 pub use tao::structs::{
-  CursorPosition, EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget, GestureEvent,
-  HiDpiScaling, Icon, KeyboardEvent, MonitorInfo, MouseEvent, NotSupportedError, OsError, Position,
-  RawKeyEvent, Rectangle, ResizeDetails, ScaleFactorChangeDetails, Size, TaoProgressBar,
-  ThemeChangeDetails, Touch, VideoMode, Window, WindowAttributes, WindowBuilder, WindowDragOptions,
-  WindowJumpOptions, WindowOptions, WindowSizeConstraints,
+  AnyEventCallback, AppEvent, AppEventHandler, BuildInfo, CursorPosition, DecodedImage, EventLoop,
+  EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget, FullscreenApplied, GestureEvent,
+  HiDpiScaling, Icon, Insets, KeyboardEvent, MonitorInfo, MouseEvent, NotSupportedError, OsError,
+  PlatformErrorStatus, Position, RawKeyEvent, Rectangle, ResizeDetails, ScaleFactorChangeDetails,
+  Size, TaoProgressBar, ThemeChangeDetails, TimerCallback, Touch, VideoMode, VisualState,
+  WheelEvent, Window, WindowAttributes, WindowBuildResult, WindowBuilder, WindowConfig,
+  WindowDragOptions, WindowEventCallback, WindowEventData, WindowJumpOptions, WindowOptions,
+  WindowSizeConstraints,
 };
+pub use tao::tray::{TrayClickCallback, TrayIcon, TrayMenuItem, TrayMenuItemCallback};
 pub use tao::types::{AxisId, ButtonId, DeviceId, Result as TaoResult, WindowId, RGBA as TaoRGBA};
 
 // Re-export render types
-pub use tao::render::{render_pixels, PixelRenderer, RenderOptions};
+pub use tao::render::clock::FrameClock;
+pub use tao::render::compositor::Compositor;
+pub use tao::render::deterministic::render_offscreen_deterministic;
+pub use tao::render::thread::RenderThread;
+pub use tao::render::{
+  copy_rgba_to_clipboard, free_render_state, letterbox_rects, min_window_size_for_integer,
+  render_cache_size, render_pixels, PixelRenderer, RenderBackend, RenderError, RenderErrorCallback,
+  RenderOptions,
+};
 
 // High-level API adapter
 pub mod high_level;
@@ -0,0 +1,122 @@
+//! Logging hook
+//!
+//! This module routes the library's diagnostics through a settable callback
+//! instead of writing directly to stdout/stderr. Without a handler, records
+//! are written to stderr in debug builds and dropped in release builds.
+
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+/// Severity of a log record.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+  /// An operation failed.
+  Error,
+  /// Something unexpected happened but the operation continued.
+  Warn,
+  /// General information.
+  Info,
+  /// Diagnostic information useful while debugging.
+  Debug,
+  /// Very verbose, per-frame information.
+  Trace,
+}
+
+impl LogLevel {
+  fn as_str(&self) -> &'static str {
+    match self {
+      LogLevel::Error => "ERROR",
+      LogLevel::Warn => "WARN",
+      LogLevel::Info => "INFO",
+      LogLevel::Debug => "DEBUG",
+      LogLevel::Trace => "TRACE",
+    }
+  }
+}
+
+/// A single log record passed to the log handler.
+#[napi(object)]
+pub struct LogRecord {
+  /// The severity of the record.
+  pub level: LogLevel,
+  /// The component that emitted the record (e.g. "window", "render").
+  pub target: String,
+  /// The log message.
+  pub message: String,
+}
+
+/// Log handler callback type.
+pub type LogHandler = ThreadsafeFunction<LogRecord>;
+
+static LOG_HANDLER: LazyLock<Mutex<Option<LogHandler>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Maximum level that is emitted, stored as the `LogLevel` discriminant.
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Warn as u8);
+
+/// Sets the callback that receives log records.
+///
+/// Passing `null` removes the handler. Without a handler, records are written
+/// to stderr in debug builds and silently dropped in release builds.
+#[napi]
+pub fn set_log_handler(handler: Option<LogHandler>) {
+  *LOG_HANDLER.lock().unwrap() = handler;
+}
+
+/// Sets the maximum level of records that are emitted (default: Warn).
+#[napi]
+pub fn set_log_level(level: LogLevel) {
+  MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Returns true if a record at the given level would be emitted.
+pub(crate) fn enabled(level: LogLevel) -> bool {
+  (level as u8) <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Emits a log record. The message is only formatted if the level is enabled.
+pub(crate) fn log(level: LogLevel, target: &str, message: impl FnOnce() -> String) {
+  if !enabled(level) {
+    return;
+  }
+
+  let handler = LOG_HANDLER.lock().unwrap();
+  if let Some(handler) = handler.as_ref() {
+    let _ = handler.call(
+      Ok(LogRecord {
+        level,
+        target: target.to_string(),
+        message: message(),
+      }),
+      ThreadsafeFunctionCallMode::NonBlocking,
+    );
+  } else if cfg!(debug_assertions) {
+    eprintln!("[{}] {}: {}", level.as_str(), target, message());
+  }
+}
+
+macro_rules! log_error {
+  ($target:expr, $($arg:tt)*) => {
+    $crate::logging::log($crate::logging::LogLevel::Error, $target, || format!($($arg)*))
+  };
+}
+
+macro_rules! log_warn {
+  ($target:expr, $($arg:tt)*) => {
+    $crate::logging::log($crate::logging::LogLevel::Warn, $target, || format!($($arg)*))
+  };
+}
+
+macro_rules! log_debug {
+  ($target:expr, $($arg:tt)*) => {
+    $crate::logging::log($crate::logging::LogLevel::Debug, $target, || format!($($arg)*))
+  };
+}
+
+macro_rules! log_trace {
+  ($target:expr, $($arg:tt)*) => {
+    $crate::logging::log($crate::logging::LogLevel::Trace, $target, || format!($($arg)*))
+  };
+}
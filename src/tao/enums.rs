@@ -19,6 +19,60 @@ pub enum TaoControlFlow {
   ExitWithCode,
 }
 
+/// Type of an event delivered to an `EventLoop.runApp` handler.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppEventType {
+  /// The application has been resumed.
+  Resumed,
+  /// The application has been suspended.
+  Suspended,
+  /// The window is about to be closed.
+  CloseRequested,
+  /// The window has been destroyed.
+  Destroyed,
+  /// The window gained focus.
+  Focused,
+  /// The window lost focus.
+  Unfocused,
+  /// The window was moved; `position` holds the new outer position.
+  Moved,
+  /// The window was resized; `size` holds the new inner size.
+  Resized,
+  /// The scale factor changed; `scaleFactor` and `size` are set.
+  ScaleFactorChanged,
+  /// The system theme changed; `theme` is set.
+  ThemeChanged,
+  /// A key was pressed or released; `key`, `code`, `state` and `repeat` are set.
+  KeyboardInput,
+  /// Text was committed by an input method; `text` is set.
+  ReceivedText,
+  /// The cursor moved; `position` holds the cursor position.
+  CursorMoved,
+  /// The cursor entered the window.
+  CursorEntered,
+  /// The cursor left the window.
+  CursorLeft,
+  /// A mouse button was pressed or released; `button` and `state` are set.
+  MouseInput,
+  /// The mouse wheel or touchpad scrolled; `delta` is set.
+  MouseWheel,
+  /// A finger or pen touched, moved on or left the screen; `touch` is set.
+  Touch,
+  /// A file was dropped on the window; `path` is set.
+  DroppedFile,
+  /// A file is being dragged over the window; `path` is set.
+  HoveredFile,
+  /// A file drag left the window or was cancelled.
+  HoveredFileCancelled,
+  /// The window should be redrawn.
+  RedrawRequested,
+  /// All queued events have been processed and the loop is about to wait.
+  AboutToWait,
+  /// The event loop is being destroyed.
+  Exiting,
+}
+
 /// Window event type.
 #[napi]
 pub enum WindowEvent {
@@ -50,9 +104,20 @@ pub enum WindowEvent {
   Visible,
   /// The window became invisible.
   Invisible,
+  /// A key was pressed or released; `keyboard` holds the key.
+  KeyboardInput,
+  /// A mouse button was pressed or released; `mouse` holds the button and position.
+  MouseInput,
+  /// The cursor moved inside the window; `mouse` holds the position.
+  CursorMoved,
+  /// The mouse wheel or touchpad scrolled; `wheel` holds the delta.
+  MouseWheel,
 }
 
 /// Scale mode for rendering when window is resized.
+///
+/// Only controls geometry; nearest or bilinear sampling is picked separately
+/// with [`ScaleFilter`] and applies to every mode.
 #[napi]
 #[derive(Debug, Clone, Copy)]
 pub enum ScaleMode {
@@ -63,11 +128,56 @@ pub enum ScaleMode {
   /// Maintain aspect ratio and crop to fill the window.
   Fill,
   /// Integer scaling for pixel-perfect rendering.
+  ///
+  /// Needs a window at least as large as the buffer: below that the content is
+  /// shown at 1x and cropped. See `minWindowSizeForInteger`.
   Integer,
   /// No scaling - keep original size (centered).
   None,
 }
 
+/// Filter used to sample the buffer when it is scaled.
+///
+/// Independent of [`ScaleMode`], which only controls geometry.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaleFilter {
+  /// Nearest-neighbor sampling, keeps hard pixel edges (default).
+  Nearest,
+  /// Bilinear interpolation, smooths the image when scaling.
+  Bilinear,
+}
+
+/// Byte layout of the pixels in a buffer, as guessed by `debugDetectChannelOrder`.
+///
+/// The renderer expects `Rgba`. Red and blue swapped (BGRA) can't be told apart
+/// from RGBA by the data alone, so only the position of the alpha byte is guessed.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+  /// Alpha in the last byte of each pixel (RGBA or BGRA).
+  Rgba,
+  /// Alpha in the first byte of each pixel (ARGB or ABGR).
+  Argb,
+  /// No alpha: the last byte is padding left at 0 (RGBX or BGRX), so the pixels
+  /// render fully transparent.
+  Rgbx,
+}
+
+/// Corner of the window holding the debug overlay.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayCorner {
+  /// Top-left corner (default).
+  TopLeft,
+  /// Top-right corner.
+  TopRight,
+  /// Bottom-left corner.
+  BottomLeft,
+  /// Bottom-right corner.
+  BottomRight,
+}
+
 /// Mouse button event.
 #[napi]
 pub enum MouseButton {
@@ -317,6 +427,7 @@ pub enum Key {
 
 /// Modifier key state.
 #[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ModifiersState {
   /// The Shift key is pressed.
   Shift,
@@ -330,6 +441,7 @@ pub enum ModifiersState {
 
 /// Cursor icon.
 #[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CursorIcon {
   Default,
   Crosshair,
@@ -358,10 +470,19 @@ pub enum CursorIcon {
   AllScroll,
   ZoomIn,
   ZoomOut,
+  ContextMenu,
+  Cell,
+  VerticalText,
+  Alias,
+  Copy,
+  NoDrop,
+  Grab,
+  Grabbing,
 }
 
 /// Window theme.
 #[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TaoTheme {
   /// Light theme.
   Light,
@@ -371,6 +492,7 @@ pub enum TaoTheme {
 
 /// Fullscreen type.
 #[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TaoFullscreenType {
   /// Exclusive fullscreen.
   Exclusive,
@@ -378,8 +500,19 @@ pub enum TaoFullscreenType {
   Borderless,
 }
 
+/// Cursor grab mode.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorGrabMode {
+  /// The cursor moves freely.
+  None,
+  /// The cursor is kept inside the window.
+  Confined,
+}
+
 /// Window level.
 #[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WindowLevel {
   /// Normal window level.
   Normal,
@@ -455,6 +588,16 @@ pub enum StartCause {
   Init,
 }
 
+/// Unit a scroll device reported its delta in.
+#[napi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollUnit {
+  /// Lines, from a notched mouse wheel.
+  Line,
+  /// Pixels, from a precise touchpad.
+  Pixel,
+}
+
 /// Touch phase.
 #[napi]
 pub enum TouchPhase {
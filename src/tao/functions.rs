@@ -2,14 +2,68 @@
 //!
 //! This module contains all functions from the tao crate.
 
+use napi::bindgen_prelude::*;
 use napi_derive::napi;
 
-use crate::tao::structs::MonitorInfo;
+use crate::tao::structs::{BuildInfo, DecodedImage, MonitorInfo};
 
 /// Returns the current version of the tao crate.
 #[napi]
 pub fn tao_version() -> String {
-  "0.34.5".to_string()
+  env!("TAO_VERSION").to_string()
+}
+
+/// Forces the X11 backend on Linux, running through XWayland on Wayland sessions.
+///
+/// Works around Wayland protocol errors ("Error 71") of some compositors. Must be
+/// called before creating any `EventLoop` or `Application`: GTK picks its backend
+/// once, when the first event loop is created, and calling this later returns an
+/// error. Also fails when no X server is available. Does nothing on other platforms.
+#[napi]
+pub fn prefer_x11_backend() -> Result<()> {
+  crate::tao::platform::force_x11_backend()
+    .map_err(|message| napi::Error::new(napi::Status::GenericFailure, message))
+}
+
+/// Returns the library versions and capabilities compiled into this build.
+#[napi]
+pub fn build_info() -> BuildInfo {
+  let pixels_backend = if cfg!(target_os = "windows") {
+    "wgpu (DX12/Vulkan)"
+  } else if cfg!(target_os = "macos") {
+    "wgpu (Metal)"
+  } else {
+    "wgpu (Vulkan/GL)"
+  };
+
+  let mut features: Vec<String> = [
+    "clipboard",
+    "image-png",
+    "image-jpeg",
+    "image-bmp",
+    "image-webp",
+  ]
+  .iter()
+  .map(|f| f.to_string())
+  .collect();
+  // Debug builds always have the web inspector
+  if cfg!(any(debug_assertions, feature = "devtools")) {
+    features.push("devtools".to_string());
+  }
+  if cfg!(feature = "fullscreen") {
+    features.push("fullscreen".to_string());
+  }
+  if cfg!(debug_assertions) {
+    features.push("debug".to_string());
+  }
+
+  BuildInfo {
+    tao_version: tao_version(),
+    wry_version: env!("WRY_VERSION").to_string(),
+    pixels_backend: pixels_backend.to_string(),
+    features,
+    target_os: std::env::consts::OS.to_string(),
+  }
 }
 
 /// Returns the primary monitor information.
@@ -23,11 +77,49 @@ pub fn primary_monitor() -> MonitorInfo {
     },
     position: crate::tao::structs::Position { x: 0.0, y: 0.0 },
     scale_factor: 1.0,
+    is_primary: true,
   }
 }
 
 /// Returns a list of all available monitors.
+///
+/// Monitors can only be queried through an event loop, so this returns an empty
+/// list; use `Window.availableMonitors` for the connected monitors in a stable
+/// order.
 #[napi]
 pub fn available_monitors() -> Vec<MonitorInfo> {
-  vec![primary_monitor()]
+  Vec::new()
+}
+
+/// Decodes a PNG, JPEG, BMP or WEBP image into RGBA8 pixel data.
+///
+/// The result can be used directly for window icons or passed to the pixel renderer.
+#[napi]
+pub fn decode_image(bytes: Buffer) -> Result<DecodedImage> {
+  let (width, height, rgba) = decode_rgba(&bytes)?;
+  Ok(DecodedImage {
+    width,
+    height,
+    rgba: rgba.into(),
+  })
+}
+
+/// Decodes image bytes into `(width, height, rgba)`.
+pub(crate) fn decode_rgba(bytes: &[u8]) -> Result<(u32, u32, Vec<u8>)> {
+  let format = image::guess_format(bytes).map_err(|_| {
+    napi::Error::new(
+      napi::Status::GenericFailure,
+      "Unrecognized image format, expected PNG, JPEG, BMP or WEBP".to_string(),
+    )
+  })?;
+
+  let decoded = image::load_from_memory_with_format(bytes, format).map_err(|e| {
+    napi::Error::new(
+      napi::Status::GenericFailure,
+      format!("Failed to decode {:?} image: {}", format, e),
+    )
+  })?;
+
+  let rgba = decoded.into_rgba8();
+  Ok((rgba.width(), rgba.height(), rgba.into_raw()))
 }
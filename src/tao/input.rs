@@ -0,0 +1,150 @@
+//! Input helpers
+//!
+//! Click-count (double/triple-click) detection for mouse events. The timing and
+//! movement tolerance are configurable with `EventLoop.setDoubleClick`.
+//!
+//! Also converts line-based scroll deltas to pixels.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+/// Platform-typical double-click time in milliseconds
+#[cfg(target_os = "linux")]
+const DEFAULT_DOUBLE_CLICK_MS: u32 = 400;
+#[cfg(not(target_os = "linux"))]
+const DEFAULT_DOUBLE_CLICK_MS: u32 = 500;
+
+/// Platform-typical double-click movement tolerance in pixels
+#[cfg(target_os = "windows")]
+const DEFAULT_DOUBLE_CLICK_TOLERANCE: u32 = 4;
+#[cfg(not(target_os = "windows"))]
+const DEFAULT_DOUBLE_CLICK_TOLERANCE: u32 = 5;
+
+/// Pixels scrolled per line of a line-based (notched mouse wheel) scroll delta
+pub(crate) const PIXELS_PER_LINE: f64 = 40.0;
+
+/// Converts a scroll delta in lines to pixels
+pub(crate) fn lines_to_pixels(x: f64, y: f64) -> (f64, f64) {
+  (x * PIXELS_PER_LINE, y * PIXELS_PER_LINE)
+}
+
+static DOUBLE_CLICK_MS: AtomicU32 = AtomicU32::new(DEFAULT_DOUBLE_CLICK_MS);
+static DOUBLE_CLICK_TOLERANCE: AtomicU32 = AtomicU32::new(DEFAULT_DOUBLE_CLICK_TOLERANCE);
+
+/// Sets the maximum time between clicks and the maximum pointer movement for
+/// consecutive presses to count as a multi-click
+pub(crate) fn set_double_click(ms: u32, tolerance_px: u32) {
+  DOUBLE_CLICK_MS.store(ms, Ordering::Relaxed);
+  DOUBLE_CLICK_TOLERANCE.store(tolerance_px, Ordering::Relaxed);
+}
+
+/// Returns the current double-click time (ms) and movement tolerance (px)
+pub(crate) fn double_click() -> (u32, u32) {
+  (
+    DOUBLE_CLICK_MS.load(Ordering::Relaxed),
+    DOUBLE_CLICK_TOLERANCE.load(Ordering::Relaxed),
+  )
+}
+
+/// Tracks consecutive presses to compute `click_count`
+///
+/// Each press of the same button within the double-click time of the previous
+/// press, and within the tolerance of the first press' position, increments the
+/// count: 1 for a single click, 2 for a double-click, 3 for a triple-click and so
+/// on. Any other press starts over at 1.
+#[derive(Debug, Default)]
+pub(crate) struct ClickCounter {
+  last: Option<LastPress>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LastPress {
+  button: u16,
+  x: f64,
+  y: f64,
+  time: Instant,
+  count: u16,
+}
+
+impl ClickCounter {
+  /// Registers a button press and returns its click count
+  pub(crate) fn press(&mut self, button: u16, x: f64, y: f64, time: Instant) -> u16 {
+    let (ms, tolerance) = double_click();
+    self.press_with(
+      button,
+      x,
+      y,
+      time,
+      Duration::from_millis(ms as u64),
+      tolerance as f64,
+    )
+  }
+
+  fn press_with(
+    &mut self,
+    button: u16,
+    x: f64,
+    y: f64,
+    time: Instant,
+    max_interval: Duration,
+    tolerance: f64,
+  ) -> u16 {
+    let next = match self.last {
+      Some(last)
+        if last.button == button
+          && time.saturating_duration_since(last.time) <= max_interval
+          && (x - last.x).abs() <= tolerance
+          && (y - last.y).abs() <= tolerance =>
+      {
+        LastPress {
+          time,
+          count: last.count.saturating_add(1),
+          ..last
+        }
+      }
+      _ => LastPress {
+        button,
+        x,
+        y,
+        time,
+        count: 1,
+      },
+    };
+    self.last = Some(next);
+    next.count
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const INTERVAL: Duration = Duration::from_millis(500);
+
+  #[test]
+  fn test_click_count_escalates() {
+    let mut counter = ClickCounter::default();
+    let t = Instant::now();
+    assert_eq!(counter.press_with(0, 10.0, 10.0, t, INTERVAL, 4.0), 1);
+    let t = t + Duration::from_millis(200);
+    assert_eq!(counter.press_with(0, 11.0, 9.0, t, INTERVAL, 4.0), 2);
+    let t = t + Duration::from_millis(200);
+    assert_eq!(counter.press_with(0, 12.0, 12.0, t, INTERVAL, 4.0), 3);
+  }
+
+  #[test]
+  fn test_click_count_resets() {
+    let mut counter = ClickCounter::default();
+    let t = Instant::now();
+    assert_eq!(counter.press_with(0, 10.0, 10.0, t, INTERVAL, 4.0), 1);
+    // Too slow
+    let t = t + Duration::from_millis(600);
+    assert_eq!(counter.press_with(0, 10.0, 10.0, t, INTERVAL, 4.0), 1);
+    // Moved too far
+    let t = t + Duration::from_millis(100);
+    assert_eq!(counter.press_with(0, 20.0, 10.0, t, INTERVAL, 4.0), 1);
+    // Different button
+    let t = t + Duration::from_millis(100);
+    assert_eq!(counter.press_with(1, 20.0, 10.0, t, INTERVAL, 4.0), 1);
+  }
+}
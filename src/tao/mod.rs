@@ -4,9 +4,13 @@
 
 pub mod enums;
 pub mod functions;
+pub mod input;
 pub mod platform;
 pub mod render;
 pub mod structs;
+#[cfg(all(test, any(target_os = "linux", target_os = "windows")))]
+pub(crate) mod test_display;
+pub mod tray;
 pub mod types;
 
 // Re-export render module items for backward compatibility
@@ -1,9 +1,13 @@
 //! Platform detection and utilities
 //!
 //! This module provides utilities for detecting the current display server
-//! and platform-specific configurations.
+//! and platform-specific configurations. It also holds the Win32 (`win32`) and
+//! AppKit (`appkit`) calls the window helpers make.
 
 use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread::ThreadId;
 
 /// Display server type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -138,3 +142,491 @@ impl PlatformInfo {
 pub fn platform_info() -> PlatformInfo {
   PlatformInfo::detect()
 }
+
+/// Set once the first event loop has been created, which initializes GTK
+static BACKEND_INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// Thread that created the last event loop
+static EVENT_LOOP_THREAD: Mutex<Option<ThreadId>> = Mutex::new(None);
+
+/// Records that the windowing backend has been initialized, by the current thread
+///
+/// Called right before every event loop is created.
+pub(crate) fn mark_backend_initialized() {
+  BACKEND_INITIALIZED.store(true, Ordering::SeqCst);
+  *EVENT_LOOP_THREAD.lock().unwrap() = Some(std::thread::current().id());
+}
+
+/// Whether the current thread created the event loop; false before any is created
+pub(crate) fn is_event_loop_thread() -> bool {
+  *EVENT_LOOP_THREAD.lock().unwrap() == Some(std::thread::current().id())
+}
+
+/// Forces GTK (and winit-based renderers) onto the X11 backend
+///
+/// Sets `GDK_BACKEND=x11` and `WINIT_UNIX_BACKEND=x11`, so Wayland sessions run
+/// through XWayland. Must be called before the first event loop is created, as
+/// GTK reads the variables once when it is initialized. Does nothing outside
+/// Linux/BSD.
+///
+/// Returns an error if the backend is already initialized, or if no X server is
+/// available (`DISPLAY` unset).
+pub fn force_x11_backend() -> Result<(), String> {
+  if !cfg!(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )) {
+    return Ok(());
+  }
+  if BACKEND_INITIALIZED.load(Ordering::SeqCst) {
+    return Err(
+      "The windowing backend is already initialized; call this before creating any EventLoop or Application"
+        .to_string(),
+    );
+  }
+  if env::var_os("DISPLAY").is_none() {
+    return Err("No X server available for the X11 backend (DISPLAY is not set)".to_string());
+  }
+  env::set_var("GDK_BACKEND", "x11");
+  env::set_var("WINIT_UNIX_BACKEND", "x11");
+  Ok(())
+}
+
+/// Whether the default screen can show per-pixel transparency
+///
+/// On Linux/BSD this needs an RGBA visual and a running compositor; without
+/// them a transparent window is painted over with undefined contents. Must be
+/// called after an event loop has been created. Always true elsewhere.
+pub(crate) fn supports_rgba_visual() -> bool {
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    gtk::gdk::Screen::default()
+      .is_some_and(|screen| screen.rgba_visual().is_some() && screen.is_composited())
+  }
+  #[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )))]
+  {
+    true
+  }
+}
+
+/// Whether a GDK display runs on the Wayland backend
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn is_wayland_gdk_display(display: &gtk::gdk::Display) -> bool {
+  use gtk::prelude::*;
+
+  // GDK_IS_WAYLAND_DISPLAY, without linking against the optional Wayland backend
+  display.type_().name() == "GdkWaylandDisplay"
+}
+
+/// Whether a window is shown through GDK's Wayland backend
+///
+/// Unlike [`PlatformInfo::is_wayland`], which only looks at `WAYLAND_DISPLAY`,
+/// this is false for windows running through XWayland (e.g. with
+/// `GDK_BACKEND=x11`). Always false outside Linux/BSD.
+pub(crate) fn is_wayland_window(window: &tao::window::Window) -> bool {
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use gtk::prelude::*;
+    use tao::platform::unix::WindowExtUnix;
+
+    is_wayland_gdk_display(&window.gtk_window().display())
+  }
+  #[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )))]
+  {
+    let _ = window;
+    false
+  }
+}
+
+/// Whether GDK's default display uses the Wayland backend
+///
+/// For checks made before a window exists; see [`is_wayland_window`]. Must be
+/// called after an event loop has been created. Always false outside Linux/BSD.
+pub(crate) fn is_wayland_backend() -> bool {
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    gtk::gdk::Display::default().is_some_and(|display| is_wayland_gdk_display(&display))
+  }
+  #[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )))]
+  {
+    false
+  }
+}
+
+/// Win32 calls of the window helpers, on the `windows` crate
+///
+/// Windows are passed as the `isize` handles tao hands out.
+#[cfg(target_os = "windows")]
+pub(crate) mod win32 {
+  use std::ffi::c_void;
+
+  use windows::core::BOOL;
+  use windows::Win32::Foundation::{COLORREF, POINT};
+  use windows::Win32::Graphics::Dwm::{
+    DwmSetWindowAttribute, DWMSBT_AUTO, DWMSBT_TRANSIENTWINDOW, DWMWA_SYSTEMBACKDROP_TYPE,
+    DWMWA_USE_IMMERSIVE_DARK_MODE, DWMWINDOWATTRIBUTE,
+  };
+  use windows::Win32::Graphics::Gdi::{
+    CombineRgn, CreateRectRgn, DeleteObject, GetMonitorInfoW, MonitorFromWindow, ScreenToClient,
+    SetWindowRgn, HRGN, MONITORINFO, MONITOR_DEFAULTTONEAREST, RGN_OR,
+  };
+  use windows::Win32::UI::Shell::{GetWindowSubclass, RemoveWindowSubclass, SetWindowSubclass};
+  use windows::Win32::UI::WindowsAndMessaging::{
+    GetCursorPos, GetWindowLongW, IsZoomed, SetLayeredWindowAttributes, SetWindowDisplayAffinity,
+    SetWindowLongW, SetWindowPos, ShowWindow, GWL_EXSTYLE, GWL_STYLE, HWND_TOPMOST, LWA_ALPHA,
+    SWP_FRAMECHANGED, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, SW_MAXIMIZE,
+    SW_RESTORE, WDA_EXCLUDEFROMCAPTURE, WDA_NONE,
+  };
+
+  pub(crate) use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+  pub(crate) use windows::Win32::UI::Shell::DefSubclassProc;
+
+  /// Window procedure of a subclass, see [`set_subclass`]
+  pub(crate) type SubclassProc =
+    unsafe extern "system" fn(HWND, u32, WPARAM, LPARAM, usize, usize) -> LRESULT;
+
+  fn hwnd(window: isize) -> HWND {
+    HWND(window as *mut c_void)
+  }
+
+  /// Returns the tao handle of a window
+  pub(crate) fn handle(window: HWND) -> isize {
+    window.0 as isize
+  }
+
+  /// Sets the immersive dark mode attribute that makes the titlebar dark.
+  pub(crate) fn set_dark_titlebar(window: isize, dark: bool) {
+    let value = BOOL::from(dark);
+    let set = |attribute: DWMWINDOWATTRIBUTE| unsafe {
+      DwmSetWindowAttribute(
+        hwnd(window),
+        attribute,
+        &value as *const BOOL as *const c_void,
+        std::mem::size_of::<BOOL>() as u32,
+      )
+    };
+    // Windows 10 builds before 20H1 use the undocumented attribute 19
+    if set(DWMWA_USE_IMMERSIVE_DARK_MODE).is_err() {
+      let _ = set(DWMWINDOWATTRIBUTE(19));
+    }
+  }
+
+  /// Sets the acrylic system backdrop, or the default one; returns whether
+  /// the system accepted it (Windows 11 22H2+).
+  pub(crate) fn set_acrylic_backdrop(window: isize, acrylic: bool) -> bool {
+    let value = if acrylic {
+      DWMSBT_TRANSIENTWINDOW
+    } else {
+      DWMSBT_AUTO
+    };
+    unsafe {
+      DwmSetWindowAttribute(
+        hwnd(window),
+        DWMWA_SYSTEMBACKDROP_TYPE,
+        &value as *const _ as *const c_void,
+        std::mem::size_of_val(&value) as u32,
+      )
+    }
+    .is_ok()
+  }
+
+  /// Excludes a window from screen capture, or includes it again; returns
+  /// whether the system accepted it (Windows 10 2004+).
+  pub(crate) fn set_capture_excluded(window: isize, excluded: bool) -> bool {
+    let affinity = if excluded {
+      WDA_EXCLUDEFROMCAPTURE
+    } else {
+      WDA_NONE
+    };
+    unsafe { SetWindowDisplayAffinity(hwnd(window), affinity) }.is_ok()
+  }
+
+  /// Returns the style (`GWL_STYLE`) of a window
+  pub(crate) fn style(window: isize) -> i32 {
+    unsafe { GetWindowLongW(hwnd(window), GWL_STYLE) }
+  }
+
+  pub(crate) fn set_style(window: isize, style: i32) {
+    unsafe { SetWindowLongW(hwnd(window), GWL_STYLE, style) };
+  }
+
+  /// Returns the extended style (`GWL_EXSTYLE`) of a window
+  pub(crate) fn ex_style(window: isize) -> i32 {
+    unsafe { GetWindowLongW(hwnd(window), GWL_EXSTYLE) }
+  }
+
+  pub(crate) fn set_ex_style(window: isize, style: i32) {
+    unsafe { SetWindowLongW(hwnd(window), GWL_EXSTYLE, style) };
+  }
+
+  /// Sets the opacity of a layered (`WS_EX_LAYERED`) window
+  pub(crate) fn set_layered_alpha(window: isize, alpha: u8) {
+    let _ = unsafe { SetLayeredWindowAttributes(hwnd(window), COLORREF(0), alpha, LWA_ALPHA) };
+  }
+
+  /// Moves a window to the top of the topmost windows, without activating it
+  pub(crate) fn raise_topmost(window: isize) {
+    let _ = unsafe {
+      SetWindowPos(
+        hwnd(window),
+        Some(HWND_TOPMOST),
+        0,
+        0,
+        0,
+        0,
+        SWP_NOSIZE | SWP_NOMOVE | SWP_NOACTIVATE,
+      )
+    };
+  }
+
+  /// Makes a window recalculate its frame after a style change
+  pub(crate) fn refresh_frame(window: isize) {
+    let _ = unsafe {
+      SetWindowPos(
+        hwnd(window),
+        None,
+        0,
+        0,
+        0,
+        0,
+        SWP_NOSIZE | SWP_NOMOVE | SWP_NOZORDER | SWP_FRAMECHANGED,
+      )
+    };
+  }
+
+  /// Maximizes a window, or restores it when it is maximized
+  pub(crate) fn toggle_maximized(window: isize) {
+    unsafe {
+      let command = if IsZoomed(hwnd(window)).as_bool() {
+        SW_RESTORE
+      } else {
+        SW_MAXIMIZE
+      };
+      let _ = ShowWindow(hwnd(window), command);
+    }
+  }
+
+  /// Converts a point from screen to client coordinates
+  pub(crate) fn screen_to_client(window: isize, (x, y): (i32, i32)) -> Option<(i32, i32)> {
+    let mut point = POINT { x, y };
+    unsafe { ScreenToClient(hwnd(window), &mut point) }
+      .as_bool()
+      .then_some((point.x, point.y))
+  }
+
+  /// Returns the screen point a mouse message carries in its `LPARAM`
+  pub(crate) fn message_point(lparam: LPARAM) -> (i32, i32) {
+    (
+      (lparam.0 & 0xFFFF) as i16 as i32,
+      ((lparam.0 >> 16) & 0xFFFF) as i16 as i32,
+    )
+  }
+
+  /// Returns the cursor position in the client coordinates of a window
+  pub(crate) fn cursor_position(window: isize) -> Option<(i32, i32)> {
+    let mut point = POINT::default();
+    unsafe { GetCursorPos(&mut point) }.ok()?;
+    screen_to_client(window, (point.x, point.y))
+  }
+
+  /// Returns the work area of the monitor a window is on, as left, top, right
+  /// and bottom edges
+  pub(crate) fn monitor_work_area(window: isize) -> Option<(i32, i32, i32, i32)> {
+    unsafe {
+      let monitor = MonitorFromWindow(hwnd(window), MONITOR_DEFAULTTONEAREST);
+      if monitor.is_invalid() {
+        return None;
+      }
+      let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+      };
+      if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+        return None;
+      }
+      let work = info.rcWork;
+      Some((work.left, work.top, work.right, work.bottom))
+    }
+  }
+
+  /// Sets the window region to the union of one pixel high runs `(x0, x1, y)`,
+  /// or removes it with `None`
+  pub(crate) fn set_window_region(window: isize, runs: Option<&[(i32, i32, i32)]>) {
+    unsafe {
+      let region = match runs {
+        Some(runs) => {
+          let region = CreateRectRgn(0, 0, 0, 0);
+          for &(x0, x1, y) in runs {
+            let run = CreateRectRgn(x0, y, x1, y + 1);
+            let _ = CombineRgn(region.into(), region.into(), run.into(), RGN_OR);
+            let _ = DeleteObject(run.into());
+          }
+          region
+        }
+        None => HRGN::default(),
+      };
+      // On success the system owns the region
+      if SetWindowRgn(hwnd(window), region.into(), true) == 0 && !region.is_invalid() {
+        let _ = DeleteObject(region.into());
+      }
+    }
+  }
+
+  /// Installs a subclass on a window; `data` is handed to every call of `proc_`
+  pub(crate) fn set_subclass(window: isize, proc_: SubclassProc, id: usize, data: usize) -> bool {
+    unsafe { SetWindowSubclass(hwnd(window), Some(proc_), id, data) }.as_bool()
+  }
+
+  /// Returns the data of a subclass, or `None` when it is not installed
+  pub(crate) fn subclass_data(window: isize, proc_: SubclassProc, id: usize) -> Option<usize> {
+    let mut data = 0usize;
+    unsafe {
+      GetWindowSubclass(
+        hwnd(window),
+        Some(proc_),
+        id,
+        (&mut data as *mut usize).into(),
+      )
+    }
+    .as_bool()
+    .then_some(data)
+  }
+
+  pub(crate) fn remove_subclass(window: isize, proc_: SubclassProc, id: usize) {
+    let _ = unsafe { RemoveWindowSubclass(hwnd(window), Some(proc_), id) };
+  }
+}
+
+/// AppKit calls of the window helpers, on objc2
+///
+/// Windows and views are passed as the raw pointers tao hands out.
+#[cfg(target_os = "macos")]
+pub(crate) mod appkit {
+  use std::ffi::c_void;
+
+  use objc2::runtime::AnyObject;
+  use objc2::{class, msg_send};
+  use objc2_foundation::{NSRect, NSSize};
+
+  /// A rect as `(x, y, width, height)`, with a bottom-left origin
+  pub(crate) type Rect = (f64, f64, f64, f64);
+
+  fn rect(rect: NSRect) -> Rect {
+    (
+      rect.origin.x,
+      rect.origin.y,
+      rect.size.width,
+      rect.size.height,
+    )
+  }
+
+  fn object(pointer: *mut c_void) -> *mut AnyObject {
+    pointer.cast()
+  }
+
+  /// Sets the presentation options of the whole application
+  pub(crate) fn set_presentation_options(options: usize) {
+    unsafe {
+      let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+      let () = msg_send![app, setPresentationOptions: options];
+    }
+  }
+
+  /// Sets the alpha value of an `NSWindow`
+  pub(crate) fn set_alpha_value(ns_window: *mut c_void, alpha: f64) {
+    unsafe {
+      let () = msg_send![object(ns_window), setAlphaValue: alpha];
+    }
+  }
+
+  /// Sets the content resize increments of an `NSWindow`
+  pub(crate) fn set_content_resize_increments(ns_window: *mut c_void, width: f64, height: f64) {
+    unsafe {
+      let () = msg_send![
+        object(ns_window),
+        setContentResizeIncrements: NSSize::new(width, height)
+      ];
+    }
+  }
+
+  /// Returns the frame of an `NSView`, in the coordinates of its superview
+  pub(crate) fn view_frame(ns_view: *mut c_void) -> Rect {
+    let frame: NSRect = unsafe { msg_send![object(ns_view), frame] };
+    rect(frame)
+  }
+
+  /// Returns the part of an `NSWindow` not covered by its titlebar and
+  /// toolbars, in window coordinates
+  pub(crate) fn content_layout_rect(ns_window: *mut c_void) -> Rect {
+    let layout: NSRect = unsafe { msg_send![object(ns_window), contentLayoutRect] };
+    rect(layout)
+  }
+
+  /// Returns the height of the primary screen and the visible frame of the
+  /// screen an `NSWindow` is on, or `None` when it is offscreen
+  pub(crate) fn screen_visible_frame(ns_window: *mut c_void) -> Option<(f64, Rect)> {
+    unsafe {
+      let screen: *mut AnyObject = msg_send![object(ns_window), screen];
+      let screens: *mut AnyObject = msg_send![class!(NSScreen), screens];
+      if screen.is_null() || screens.is_null() {
+        return None;
+      }
+      let primary: *mut AnyObject = msg_send![screens, objectAtIndex: 0usize];
+      let primary_frame: NSRect = msg_send![primary, frame];
+      let visible: NSRect = msg_send![screen, visibleFrame];
+      Some((primary_frame.size.height, rect(visible)))
+    }
+  }
+
+  /// Sets the private `_WKMediaMutedState` of a `WKWebView`
+  pub(crate) fn set_page_muted(wk_webview: *mut c_void, state: usize) {
+    unsafe {
+      let () = msg_send![object(wk_webview), _setPageMuted: state];
+    }
+  }
+}
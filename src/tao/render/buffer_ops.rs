@@ -3,10 +3,12 @@
 //! This module provides functions for copying and scaling pixel buffers
 //! using various algorithms (nearest-neighbor scaling, centered copy, fill mode).
 
-// Debug logging macro
+use crate::tao::enums::PixelFormat;
+
+// Debug logging macro - routed through the logging hook, see `set_log_level`
 macro_rules! debug_log {
     ($($arg:tt)*) => {
-        eprintln!("[PixelRenderer] {}", format!($($arg)*));
+        log_trace!("render", $($arg)*)
     };
 }
 
@@ -143,6 +145,130 @@ pub fn copy_buffer_fill(
   }
 }
 
+/// Linearly blends two equally sized RGBA buffers
+///
+/// `t` of 0.0 yields `from`, 1.0 yields `to`; values outside that range are clamped.
+pub fn blend_buffers(from: &[u8], to: &[u8], t: f64) -> Vec<u8> {
+  // 8-bit weight keeps the blend integer-only and exact at both ends
+  let weight = (t.clamp(0.0, 1.0) * 256.0).round() as u32;
+  from
+    .iter()
+    .zip(to)
+    .map(|(&a, &b)| ((a as u32 * (256 - weight) + b as u32 * weight + 128) >> 8) as u8)
+    .collect()
+}
+
+/// Copies a `width` x `height` rectangle at `x`, `y` out of an RGBA buffer
+///
+/// The rectangle must lie within the buffer.
+pub fn crop_buffer(
+  buffer: &[u8],
+  buffer_width: u32,
+  x: u32,
+  y: u32,
+  width: u32,
+  height: u32,
+) -> Vec<u8> {
+  let row_len = width as usize * 4;
+  let mut cropped = Vec::with_capacity(row_len * height as usize);
+  for row in y..y + height {
+    let start = (row as usize * buffer_width as usize + x as usize) * 4;
+    cropped.extend_from_slice(&buffer[start..start + row_len]);
+  }
+  cropped
+}
+
+/// Parameters for blending an image over a frame
+pub struct BlendImageParams {
+  pub image_width: u32,
+  pub image_height: u32,
+  /// Offset of the image in the frame; may be negative
+  pub x: i32,
+  pub y: i32,
+  /// Multiplies the image alpha, clamped to 0.0–1.0
+  pub opacity: f64,
+}
+
+/// Alpha-blends an RGBA image over a frame at an offset
+///
+/// The parts of the image outside the frame are skipped. The frame alpha is kept
+/// as is.
+pub fn blend_image_over(
+  frame: &mut [u8],
+  frame_width: u32,
+  frame_height: u32,
+  image: &[u8],
+  params: BlendImageParams,
+) {
+  let BlendImageParams {
+    image_width,
+    image_height,
+    x,
+    y,
+    opacity,
+  } = params;
+  let opacity = (opacity.clamp(0.0, 1.0) * 255.0).round() as u32;
+  if opacity == 0 {
+    return;
+  }
+  let x_start = x.max(0);
+  let y_start = y.max(0);
+  let x_end = (x + image_width as i32).min(frame_width as i32);
+  let y_end = (y + image_height as i32).min(frame_height as i32);
+  if x_start >= x_end || y_start >= y_end {
+    return;
+  }
+
+  let row_len = (x_end - x_start) as usize * 4;
+  for frame_y in y_start..y_end {
+    let frame_idx = (frame_y as usize * frame_width as usize + x_start as usize) * 4;
+    let image_idx = ((frame_y - y) as usize * image_width as usize + (x_start - x) as usize) * 4;
+    let dst = &mut frame[frame_idx..frame_idx + row_len];
+    let src = &image[image_idx..image_idx + row_len];
+    for (dst, src) in dst.chunks_exact_mut(4).zip(src.chunks_exact(4)) {
+      let alpha = (src[3] as u32 * opacity + 127) / 255;
+      for channel in 0..3 {
+        dst[channel] =
+          ((src[channel] as u32 * alpha + dst[channel] as u32 * (255 - alpha) + 127) / 255) as u8;
+      }
+    }
+  }
+}
+
+/// Maximum number of pixels sampled by [`detect_channel_order`]
+const CHANNEL_ORDER_SAMPLES: usize = 4096;
+
+/// Guesses the byte layout of a buffer from a sample of its pixels
+///
+/// Returns `None` when the sample is inconclusive, e.g. for translucent or
+/// single-color content. Opaque buffers are the easy case: the alpha byte is the
+/// one that is 255 everywhere. A last byte that is 0 on colored pixels is padding.
+pub fn detect_channel_order(buffer: &[u8]) -> Option<PixelFormat> {
+  let pixels = buffer.len() / 4;
+  if pixels < 16 {
+    return None;
+  }
+  let step = pixels.div_ceil(CHANNEL_ORDER_SAMPLES);
+  let (mut samples, mut first_opaque, mut last_opaque, mut padded) = (0, 0, 0, 0);
+  for pixel in buffer.chunks_exact(4).step_by(step) {
+    samples += 1;
+    first_opaque += (pixel[0] == 255) as usize;
+    last_opaque += (pixel[3] == 255) as usize;
+    padded += (pixel[3] == 0 && pixel[..3].iter().any(|&c| c != 0)) as usize;
+  }
+  // 90% of the sample must agree
+  let most = |count: usize| count * 10 >= samples * 9;
+  if most(last_opaque) {
+    Some(PixelFormat::Rgba)
+  } else if most(first_opaque) {
+    Some(PixelFormat::Argb)
+  } else if most(padded) {
+    Some(PixelFormat::Rgbx)
+  } else {
+    None
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -210,10 +336,8 @@ mod tests {
 
     copy_buffer_centered(&mut frame, &buffer, 8, 8, 4, 4);
 
-    // Buffer is larger, so it uses saturating_sub - offset should be 0
-    // and only first 4 rows/cols should be copied
-    // Actually with saturating_sub, 4 - 8 = 0, so offset is 0
-    // But it should only copy min(8, 4) = 4 rows
+    // The frame must be buffer-sized; any other frame is left untouched
+    assert!(frame.iter().all(|&b| b == 0));
   }
 
   // ============================================================================
@@ -229,7 +353,7 @@ mod tests {
     copy_buffer_fill(&mut frame, &buffer, 4, 4, 4, 4);
 
     // Should be a direct copy when aspect ratios match
-    // Note: Due to the scaling math, it may not be exact
+    assert_eq!(frame, buffer);
   }
 
   #[test]
@@ -253,11 +377,11 @@ mod tests {
       }
     }
 
-    let mut frame = vec![0u8; 4 * 4 * 4];
+    let mut frame = vec![0u8; 8 * 4 * 4]; // Frame is buffer-sized
     copy_buffer_fill(&mut frame, &buffer, 8, 4, 4, 4);
 
-    // Should crop to center 4x4, so we should see both colors
-    // The center 4 columns would be columns 2,3,4,5
+    // The full buffer is copied; the crop happens when pixels scales it
+    assert_eq!(frame, buffer);
   }
 
   #[test]
@@ -281,10 +405,11 @@ mod tests {
       }
     }
 
-    let mut frame = vec![0u8; 4 * 4 * 4];
+    let mut frame = vec![0u8; 4 * 8 * 4]; // Frame is buffer-sized
     copy_buffer_fill(&mut frame, &buffer, 4, 8, 4, 4);
 
-    // Should crop to center 4 rows
+    // The full buffer is copied; the crop happens when pixels scales it
+    assert_eq!(frame, buffer);
   }
 
   // ============================================================================
@@ -310,7 +435,7 @@ mod tests {
 
     copy_buffer_scaled(&mut frame, &buffer, params);
 
-    // Should copy the buffer (though coordinates may transform)
+    assert_eq!(frame, buffer);
   }
 
   #[test]
@@ -332,7 +457,8 @@ mod tests {
 
     copy_buffer_scaled(&mut frame, &buffer, params);
 
-    // Every other pixel should be sampled
+    // The pixels crate samples it down when rendering
+    assert_eq!(frame, buffer);
   }
 
   #[test]
@@ -354,7 +480,8 @@ mod tests {
 
     copy_buffer_scaled(&mut frame, &buffer, params);
 
-    // With offset, the image should be offset in buffer coordinates
+    // The offset is in window coordinates and applied by the pixels crate
+    assert_eq!(frame, buffer);
   }
 
   // ============================================================================
@@ -369,7 +496,12 @@ mod tests {
 
     copy_buffer_centered(&mut frame, &buffer, 1, 1, 4, 4);
 
-    // Should place pixel at center (1,1) or (2,2)
+    // The frame must be buffer-sized; any other frame is left untouched
+    assert!(frame.iter().all(|&b| b == 0));
+
+    let mut frame = vec![0u8; 4];
+    copy_buffer_centered(&mut frame, &buffer, 1, 1, 4, 4);
+    assert_eq!(frame, buffer);
   }
 
   #[test]
@@ -400,7 +532,7 @@ mod tests {
 
       copy_buffer_fill(&mut frame, &buffer, buf_w, buf_h, win_w, win_h);
 
-      // Just verify it doesn't panic
+      assert_eq!(frame, buffer, "{}x{} to {}x{}", buf_w, buf_h, win_w, win_h);
     }
   }
 
@@ -431,7 +563,94 @@ mod tests {
 
       copy_buffer_scaled(&mut frame, &buffer, params);
 
-      // Verify it doesn't panic
+      assert_eq!(frame, buffer, "{}x{} window", win_w, win_h);
     }
   }
+
+  // ============================================================================
+  // blend_buffers Tests
+  // ============================================================================
+
+  #[test]
+  fn test_blend_black_to_white_half_is_gray() {
+    let black = [0, 0, 0, 255].repeat(4);
+    let white = [255, 255, 255, 255].repeat(4);
+    let blended = blend_buffers(&black, &white, 0.5);
+    assert_eq!(blended, [128, 128, 128, 255].repeat(4));
+  }
+
+  #[test]
+  fn test_blend_endpoints_are_exact() {
+    let from = create_test_buffer(3, 2);
+    let to: Vec<u8> = from.iter().map(|v| 255 - v).collect();
+    assert_eq!(blend_buffers(&from, &to, 0.0), from);
+    assert_eq!(blend_buffers(&from, &to, 1.0), to);
+    assert_eq!(blend_buffers(&from, &to, 2.0), to);
+  }
+
+  #[test]
+  fn test_blend_image_over_clips_and_blends() {
+    let mut frame = vec![0u8; 4 * 4 * 4];
+    // Opaque white 2x2 image hanging off the top-left corner
+    let image = vec![255u8; 2 * 2 * 4];
+    let params = BlendImageParams {
+      image_width: 2,
+      image_height: 2,
+      x: -1,
+      y: -1,
+      opacity: 1.0,
+    };
+    blend_image_over(&mut frame, 4, 4, &image, params);
+    assert_eq!(&frame[0..4], &[255, 255, 255, 0]);
+    assert_eq!(&frame[4..8], &[0, 0, 0, 0]);
+
+    // Half opacity over black
+    let mut frame = vec![0u8; 4];
+    let params = BlendImageParams {
+      image_width: 1,
+      image_height: 1,
+      x: 0,
+      y: 0,
+      opacity: 0.5,
+    };
+    blend_image_over(&mut frame, 1, 1, &[200, 100, 50, 255], params);
+    assert_eq!(&frame[0..3], &[100, 50, 25]);
+  }
+
+  #[test]
+  fn test_crop_buffer() {
+    let buffer = create_test_buffer(4, 3);
+    let cropped = crop_buffer(&buffer, 4, 1, 1, 2, 2);
+    assert_eq!(cropped.len(), 2 * 2 * 4);
+    // Top-left of the crop is pixel (1, 1): R = x, G = y
+    assert_eq!(&cropped[0..4], &[1, 1, 128, 255]);
+    // Bottom-right of the crop is pixel (2, 2)
+    assert_eq!(&cropped[12..16], &[2, 2, 128, 255]);
+  }
+
+  #[test]
+  fn test_detect_channel_order() {
+    let rgba = create_test_buffer(8, 8);
+    assert_eq!(detect_channel_order(&rgba), Some(PixelFormat::Rgba));
+
+    // The same pixels with alpha moved to the front
+    let argb: Vec<u8> = rgba
+      .chunks_exact(4)
+      .flat_map(|p| [p[3], p[0], p[1], p[2]])
+      .collect();
+    assert_eq!(detect_channel_order(&argb), Some(PixelFormat::Argb));
+
+    // BGRX from a capture API: colors with the last byte left at 0
+    let bgrx: Vec<u8> = rgba
+      .chunks_exact(4)
+      .flat_map(|p| [p[2], p[1], p[0], 0])
+      .collect();
+    assert_eq!(detect_channel_order(&bgrx), Some(PixelFormat::Rgbx));
+
+    // Fully transparent and translucent content is inconclusive
+    assert_eq!(detect_channel_order(&[0; 8 * 8 * 4]), None);
+    assert_eq!(detect_channel_order(&[10, 20, 30, 128].repeat(64)), None);
+    // Too small to tell
+    assert_eq!(detect_channel_order(&[0, 0, 0, 255]), None);
+  }
 }
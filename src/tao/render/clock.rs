@@ -0,0 +1,154 @@
+//! Frame clock
+//!
+//! Frame timing and pacing for render-only use, where `PixelRenderer` is driven
+//! on the caller's own cadence instead of from event loop callbacks.
+
+use napi_derive::napi;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Number of recent frame deltas averaged by [`FrameClock::fps`]
+const FPS_WINDOW: usize = 60;
+
+/// Time before a deadline at which `wait_until_next` stops sleeping and spins
+///
+/// OS sleeps commonly overshoot by about a millisecond.
+const SPIN_THRESHOLD: Duration = Duration::from_millis(1);
+
+/// Measures frame times and paces a render loop without an event loop
+#[napi]
+pub struct FrameClock {
+  last_tick: Option<Instant>,
+  deltas: VecDeque<f64>,
+}
+
+#[napi]
+impl FrameClock {
+  /// Creates a new frame clock; the first `tick` starts timing
+  #[napi(constructor)]
+  #[allow(clippy::new_without_default)]
+  pub fn new() -> Self {
+    Self {
+      last_tick: None,
+      deltas: VecDeque::with_capacity(FPS_WINDOW),
+    }
+  }
+
+  /// Marks the start of a frame and returns the milliseconds since the last tick
+  ///
+  /// Returns 0 on the first tick.
+  #[napi]
+  pub fn tick(&mut self) -> f64 {
+    self.tick_at(Instant::now())
+  }
+
+  /// Returns the average frames per second over the last 60 ticks
+  ///
+  /// Returns 0 until two ticks have been made.
+  #[napi]
+  pub fn fps(&self) -> f64 {
+    if self.deltas.is_empty() {
+      return 0.0;
+    }
+    let average = self.deltas.iter().sum::<f64>() / self.deltas.len() as f64;
+    if average > 0.0 {
+      1000.0 / average
+    } else {
+      0.0
+    }
+  }
+
+  /// Sleeps for the remainder of the frame budget at `target_fps`
+  ///
+  /// The budget is measured from the last `tick`, so call `tick` at the start of
+  /// each frame and this at the end. Returns the milliseconds waited, 0 when the
+  /// frame is already over budget or `target_fps` is not positive.
+  #[napi]
+  pub fn wait_until_next(&self, target_fps: f64) -> f64 {
+    let (Some(last_tick), true) = (self.last_tick, target_fps > 0.0) else {
+      return 0.0;
+    };
+    let deadline = last_tick + Duration::from_secs_f64(1.0 / target_fps);
+    let start = Instant::now();
+    if start >= deadline {
+      return 0.0;
+    }
+
+    let remaining = deadline - start;
+    if remaining > SPIN_THRESHOLD {
+      std::thread::sleep(remaining - SPIN_THRESHOLD);
+    }
+    while Instant::now() < deadline {
+      std::thread::yield_now();
+    }
+    start.elapsed().as_secs_f64() * 1000.0
+  }
+
+  /// Resets the clock; the next `tick` starts timing again
+  #[napi]
+  pub fn reset(&mut self) {
+    self.last_tick = None;
+    self.deltas.clear();
+  }
+
+  fn tick_at(&mut self, now: Instant) -> f64 {
+    let delta = self
+      .last_tick
+      .map(|last| now.saturating_duration_since(last).as_secs_f64() * 1000.0);
+    self.last_tick = Some(now);
+
+    let Some(delta) = delta else {
+      return 0.0;
+    };
+    if self.deltas.len() == FPS_WINDOW {
+      self.deltas.pop_front();
+    }
+    self.deltas.push_back(delta);
+    delta
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_tick_deltas_and_fps() {
+    let mut clock = FrameClock::new();
+    let start = Instant::now();
+    assert_eq!(clock.tick_at(start), 0.0);
+    assert_eq!(clock.fps(), 0.0);
+
+    for frame in 1..=10 {
+      let delta = clock.tick_at(start + Duration::from_millis(20 * frame));
+      assert!((delta - 20.0).abs() < 1e-6);
+    }
+    assert!((clock.fps() - 50.0).abs() < 1e-6);
+  }
+
+  #[test]
+  fn test_fps_uses_recent_frames() {
+    let mut clock = FrameClock::new();
+    let mut now = Instant::now();
+    clock.tick_at(now);
+    for _ in 0..FPS_WINDOW {
+      now += Duration::from_millis(100);
+      clock.tick_at(now);
+    }
+    for _ in 0..FPS_WINDOW {
+      now += Duration::from_millis(10);
+      clock.tick_at(now);
+    }
+    assert!((clock.fps() - 100.0).abs() < 1e-6);
+  }
+
+  #[test]
+  fn test_wait_until_next_paces_frame() {
+    let mut clock = FrameClock::new();
+    assert_eq!(clock.wait_until_next(60.0), 0.0);
+    clock.tick();
+    let waited = clock.wait_until_next(100.0);
+    assert!(waited > 0.0 && waited < 15.0, "waited {}", waited);
+    assert_eq!(clock.wait_until_next(0.0), 0.0);
+  }
+}
@@ -0,0 +1,306 @@
+//! Retained layer compositor
+//!
+//! Keeps a stack of named RGBA layers and composes them into a single frame for
+//! `PixelRenderer`, so overlays made of independently changing sprites only send
+//! the images that changed instead of a full composite every frame.
+
+use crate::tao::render::buffer_ops::{blend_image_over, BlendImageParams};
+use crate::tao::render::{PixelRenderer, RenderOptions};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// A named image in a [`Compositor`]
+struct Layer {
+  name: String,
+  pixels: Vec<u8>,
+  width: u32,
+  height: u32,
+  x: i32,
+  y: i32,
+  opacity: f64,
+}
+
+/// Composes named image layers and presents them to a window
+///
+/// Layers are drawn in the order they were added, each alpha-blended over the
+/// ones below at its position and opacity, on top of the background color. The
+/// composite has the renderer's buffer size and is displayed with its scale mode.
+///
+/// `present` only recomposes and re-renders when a layer changed, a different
+/// window is targeted or the window was resized since the last present, so it can
+/// be called every frame. The per-window surface of the render cache is reused.
+#[napi]
+pub struct Compositor {
+  renderer: PixelRenderer,
+  layers: Vec<Layer>,
+  frame: Vec<u8>,
+  dirty: bool,
+  /// Window key and inner size of the last present
+  presented: Option<(u64, u32, u32)>,
+}
+
+#[napi]
+impl Compositor {
+  /// Creates a compositor for a `width` x `height` composite
+  #[napi(constructor)]
+  pub fn new(width: u32, height: u32) -> Self {
+    Self::from_renderer(PixelRenderer::new(width, height))
+  }
+
+  /// Creates a compositor with render options; the buffer size is the composite size
+  #[napi(factory)]
+  pub fn with_options(options: RenderOptions) -> Self {
+    Self::from_renderer(PixelRenderer::with_options(options))
+  }
+
+  /// Adds a layer on top of the others
+  ///
+  /// `pixels` is an RGBA image of `width` x `height`, placed with its top-left
+  /// corner at `x`, `y` in the composite.
+  #[napi]
+  pub fn add_layer(
+    &mut self,
+    name: String,
+    pixels: Buffer,
+    width: u32,
+    height: u32,
+    x: i32,
+    y: i32,
+  ) -> Result<()> {
+    if self.layers.iter().any(|layer| layer.name == name) {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Layer '{}' already exists", name),
+      ));
+    }
+    validate_layer_pixels(&pixels, width, height)?;
+    self.layers.push(Layer {
+      name,
+      pixels: pixels.to_vec(),
+      width,
+      height,
+      x,
+      y,
+      opacity: 1.0,
+    });
+    self.dirty = true;
+    Ok(())
+  }
+
+  /// Replaces the image of a layer; it must keep the layer's size
+  #[napi]
+  pub fn update_layer(&mut self, name: String, pixels: Buffer) -> Result<()> {
+    let layer = self.layer_mut(&name)?;
+    validate_layer_pixels(&pixels, layer.width, layer.height)?;
+    layer.pixels.copy_from_slice(&pixels);
+    self.dirty = true;
+    Ok(())
+  }
+
+  /// Removes a layer; returns whether it existed
+  #[napi]
+  pub fn remove_layer(&mut self, name: String) -> bool {
+    let count = self.layers.len();
+    self.layers.retain(|layer| layer.name != name);
+    let removed = self.layers.len() != count;
+    self.dirty |= removed;
+    removed
+  }
+
+  /// Moves a layer's top-left corner to `x`, `y`
+  #[napi]
+  pub fn move_layer(&mut self, name: String, x: i32, y: i32) -> Result<()> {
+    let layer = self.layer_mut(&name)?;
+    if (layer.x, layer.y) != (x, y) {
+      layer.x = x;
+      layer.y = y;
+      self.dirty = true;
+    }
+    Ok(())
+  }
+
+  /// Sets a layer's opacity, from 0.0 (hidden) to 1.0 (default)
+  #[napi]
+  pub fn set_layer_opacity(&mut self, name: String, opacity: f64) -> Result<()> {
+    let layer = self.layer_mut(&name)?;
+    let opacity = opacity.clamp(0.0, 1.0);
+    if layer.opacity != opacity {
+      layer.opacity = opacity;
+      self.dirty = true;
+    }
+    Ok(())
+  }
+
+  /// Gets the layer names, from bottom to top
+  #[napi(getter)]
+  pub fn layer_names(&self) -> Vec<String> {
+    self.layers.iter().map(|layer| layer.name.clone()).collect()
+  }
+
+  /// Presents the composite to a window
+  ///
+  /// Returns `false` without rendering when nothing changed since the last
+  /// present to the same window.
+  #[napi]
+  pub fn present(&mut self, window: &crate::tao::structs::Window) -> Result<bool> {
+    let target = window.inner.as_ref().and_then(|inner| {
+      let window = inner.lock().ok()?;
+      let size = window.inner_size();
+      Some((
+        crate::tao::render::render_state_key(&window),
+        size.width,
+        size.height,
+      ))
+    });
+    if !self.dirty && target.is_some() && target == self.presented {
+      return Ok(false);
+    }
+
+    self.compose();
+    self.renderer.render_bytes(window, &self.frame)?;
+    self.presented = target;
+    Ok(true)
+  }
+
+  fn from_renderer(renderer: PixelRenderer) -> Self {
+    let frame = vec![0; (renderer.buffer_width * renderer.buffer_height * 4) as usize];
+    Self {
+      renderer,
+      layers: Vec::new(),
+      frame,
+      dirty: true,
+      presented: None,
+    }
+  }
+
+  fn layer_mut(&mut self, name: &str) -> Result<&mut Layer> {
+    self
+      .layers
+      .iter_mut()
+      .find(|layer| layer.name == name)
+      .ok_or_else(|| {
+        napi::Error::new(
+          napi::Status::GenericFailure,
+          format!("Layer '{}' does not exist", name),
+        )
+      })
+  }
+
+  /// Redraws the composite frame from the background and layers if it changed
+  fn compose(&mut self) {
+    if !self.dirty {
+      return;
+    }
+    for pixel in self.frame.chunks_exact_mut(4) {
+      pixel.copy_from_slice(&self.renderer.bg_color);
+    }
+    for layer in &self.layers {
+      blend_image_over(
+        &mut self.frame,
+        self.renderer.buffer_width,
+        self.renderer.buffer_height,
+        &layer.pixels,
+        BlendImageParams {
+          image_width: layer.width,
+          image_height: layer.height,
+          x: layer.x,
+          y: layer.y,
+          opacity: layer.opacity,
+        },
+      );
+    }
+    self.dirty = false;
+  }
+}
+
+fn validate_layer_pixels(pixels: &[u8], width: u32, height: u32) -> Result<()> {
+  let expected_len = (width * height * 4) as usize;
+  if pixels.len() != expected_len {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!(
+        "Layer size mismatch: got {} bytes, expected {} bytes for {}x{}",
+        pixels.len(),
+        expected_len,
+        width,
+        height
+      ),
+    ));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn solid(width: u32, height: u32, rgba: [u8; 4]) -> Vec<u8> {
+    rgba.repeat((width * height) as usize)
+  }
+
+  fn add(compositor: &mut Compositor, name: &str, pixels: Vec<u8>, size: u32, x: i32, y: i32) {
+    compositor.layers.push(Layer {
+      name: name.to_string(),
+      pixels,
+      width: size,
+      height: size,
+      x,
+      y,
+      opacity: 1.0,
+    });
+    compositor.dirty = true;
+  }
+
+  #[test]
+  fn test_compose_stacks_layers_in_order() {
+    let mut compositor = Compositor::new(4, 4);
+    add(
+      &mut compositor,
+      "bottom",
+      solid(2, 2, [255, 0, 0, 255]),
+      2,
+      0,
+      0,
+    );
+    add(
+      &mut compositor,
+      "top",
+      solid(2, 2, [0, 0, 255, 255]),
+      2,
+      1,
+      1,
+    );
+    compositor.compose();
+
+    let pixel = |x: usize, y: usize| &compositor.frame[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4];
+    assert_eq!(pixel(0, 0), &[255, 0, 0, 255]);
+    assert_eq!(pixel(1, 1), &[0, 0, 255, 255]);
+    assert_eq!(pixel(3, 3), &[0, 0, 0, 255]);
+  }
+
+  #[test]
+  fn test_unchanged_layers_stay_clean() {
+    let mut compositor = Compositor::new(2, 2);
+    add(
+      &mut compositor,
+      "sprite",
+      solid(1, 1, [9, 9, 9, 255]),
+      1,
+      0,
+      0,
+    );
+    compositor.compose();
+    assert!(!compositor.dirty);
+
+    compositor.move_layer("sprite".to_string(), 0, 0).unwrap();
+    compositor
+      .set_layer_opacity("sprite".to_string(), 1.0)
+      .unwrap();
+    assert!(!compositor.dirty);
+
+    compositor.move_layer("sprite".to_string(), 1, 1).unwrap();
+    assert!(compositor.dirty);
+    assert!(compositor.remove_layer("sprite".to_string()));
+    assert!(!compositor.remove_layer("sprite".to_string()));
+  }
+}
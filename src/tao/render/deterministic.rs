@@ -0,0 +1,352 @@
+//! Deterministic software renderer
+//!
+//! Renders a pixel buffer into an offscreen RGBA frame using only integer and
+//! 16.16 fixed-point arithmetic, so the output is byte-for-byte identical on
+//! every platform. Intended for golden-image regression tests of the scaling
+//! and compositing logic; no GPU or window is involved.
+
+use crate::tao::enums::ScaleMode;
+use crate::tao::render::scaling::align_layout;
+use crate::tao::render::RenderOptions;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// Number of fractional bits used by the fixed-point sampling steps
+const FIXED_SHIFT: u32 = 16;
+
+/// Calculates scaled dimensions using integer arithmetic only
+///
+/// Returns a tuple of (offset_x, offset_y, scaled_width, scaled_height), with the
+/// same meaning as [`super::scaling::calculate_scaled_dimensions`]. For `Fill`
+/// the scaled size may exceed the output and the offsets are 0.
+pub fn calculate_scaled_dimensions_fixed(
+  buffer_width: u32,
+  buffer_height: u32,
+  output_width: u32,
+  output_height: u32,
+  scale_mode: ScaleMode,
+) -> (u32, u32, u32, u32) {
+  if buffer_width == 0 || buffer_height == 0 {
+    return (0, 0, 0, 0);
+  }
+
+  let (bw, bh) = (buffer_width as u64, buffer_height as u64);
+  let (ow, oh) = (output_width as u64, output_height as u64);
+  // Width-limited when ow / bw <= oh / bh
+  let width_limited = ow * bh <= oh * bw;
+
+  let (scaled_width, scaled_height) = match scale_mode {
+    ScaleMode::Stretch => (ow, oh),
+    ScaleMode::None => (bw, bh),
+    ScaleMode::Fit => {
+      if width_limited {
+        (ow, (bh * ow / bw).min(oh))
+      } else {
+        ((bw * oh / bh).min(ow), oh)
+      }
+    }
+    ScaleMode::Fill => {
+      if width_limited {
+        (bw * oh / bh, oh)
+      } else {
+        (ow, bh * ow / bw)
+      }
+    }
+    ScaleMode::Integer => {
+      let scale = (ow / bw).min(oh / bh).max(1);
+      (bw * scale, bh * scale)
+    }
+  };
+
+  let offset_x = ow.saturating_sub(scaled_width) / 2;
+  let offset_y = oh.saturating_sub(scaled_height) / 2;
+  (
+    offset_x as u32,
+    offset_y as u32,
+    scaled_width as u32,
+    scaled_height as u32,
+  )
+}
+
+/// Renders a buffer into a new `output_width * output_height * 4` RGBA frame
+///
+/// Letterbox areas are filled with the background color, then the buffer is
+/// sampled with nearest-neighbor filtering using 16.16 fixed-point steps.
+pub fn render_deterministic(
+  buffer: &[u8],
+  options: &RenderOptions,
+  output_width: u32,
+  output_height: u32,
+) -> Vec<u8> {
+  let buffer_width = options.buffer_width;
+  let buffer_height = options.buffer_height;
+  let scale_mode = options.scale_mode.unwrap_or(ScaleMode::Fit);
+  let bg_color = options
+    .background_color
+    .as_ref()
+    .filter(|c| c.len() >= 4)
+    .map(|c| [c[0], c[1], c[2], c[3]])
+    .unwrap_or([0, 0, 0, 255]);
+  let alignment = options.offset_alignment.unwrap_or(1);
+
+  let mut frame = vec![0u8; output_width as usize * output_height as usize * 4];
+  for pixel in frame.chunks_exact_mut(4) {
+    pixel.copy_from_slice(&bg_color);
+  }
+
+  let (offset_x, offset_y, scaled_width, scaled_height) = align_layout(
+    calculate_scaled_dimensions_fixed(
+      buffer_width,
+      buffer_height,
+      output_width,
+      output_height,
+      scale_mode,
+    ),
+    alignment,
+  );
+  if scaled_width == 0 || scaled_height == 0 {
+    return frame;
+  }
+
+  // Portion of the scaled image that is cut off on the left/top (Fill and None)
+  let crop_x = scaled_width.saturating_sub(output_width) / 2;
+  let crop_y = scaled_height.saturating_sub(output_height) / 2;
+  let visible_width = scaled_width.min(output_width.saturating_sub(offset_x));
+  let visible_height = scaled_height.min(output_height.saturating_sub(offset_y));
+
+  let step_x = ((buffer_width as u64) << FIXED_SHIFT) / scaled_width as u64;
+  let step_y = ((buffer_height as u64) << FIXED_SHIFT) / scaled_height as u64;
+
+  for y in 0..visible_height {
+    let src_y = ((((crop_y + y) as u64 * step_y) >> FIXED_SHIFT) as u32).min(buffer_height - 1);
+    let dst_y = offset_y + y;
+    for x in 0..visible_width {
+      let src_x = ((((crop_x + x) as u64 * step_x) >> FIXED_SHIFT) as u32).min(buffer_width - 1);
+      let dst_x = offset_x + x;
+
+      let src_idx = (src_y as usize * buffer_width as usize + src_x as usize) * 4;
+      let dst_idx = (dst_y as usize * output_width as usize + dst_x as usize) * 4;
+
+      if src_idx + 4 <= buffer.len() && dst_idx + 4 <= frame.len() {
+        frame[dst_idx..dst_idx + 4].copy_from_slice(&buffer[src_idx..src_idx + 4]);
+      }
+    }
+  }
+
+  frame
+}
+
+/// Renders a pixel buffer offscreen with a fully deterministic CPU renderer
+///
+/// Produces identical bytes on every platform for the same input, which makes
+/// the result suitable for golden-image tests. The output is an RGBA buffer of
+/// `output_width * output_height * 4` bytes.
+///
+/// Uses nearest-neighbor sampling with fixed-point math, so results can differ
+/// by a pixel from the on-screen [`super::PixelRenderer`] at scaled edges.
+/// `options.filter` is ignored.
+#[napi]
+pub fn render_offscreen_deterministic(
+  buffer: Buffer,
+  options: RenderOptions,
+  output_width: u32,
+  output_height: u32,
+) -> napi::Result<Buffer> {
+  let buffer = super::buffer_prefix(
+    &buffer,
+    options.buffer_width,
+    options.buffer_height,
+    options.allow_oversized_buffer.unwrap_or(false),
+  )?;
+  Ok(render_deterministic(buffer, &options, output_width, output_height).into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// FNV-1a 64-bit hash, stable across platforms and Rust versions
+  fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in bytes {
+      hash ^= b as u64;
+      hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+  }
+
+  /// Creates a test pattern where every pixel is distinct
+  fn pattern(width: u32, height: u32) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+      for x in 0..width {
+        buffer.push((x * 7 + y * 3) as u8);
+        buffer.push(((x * 13) ^ (y * 5)) as u8);
+        buffer.push((x + y * width) as u8);
+        buffer.push(255);
+      }
+    }
+    buffer
+  }
+
+  fn render_hash(
+    buffer_size: (u32, u32),
+    output_size: (u32, u32),
+    scale_mode: ScaleMode,
+    offset_alignment: u32,
+  ) -> u64 {
+    let options = RenderOptions {
+      buffer_width: buffer_size.0,
+      buffer_height: buffer_size.1,
+      scale_mode: Some(scale_mode),
+      background_color: Some(vec![16, 32, 48, 255]),
+      offset_alignment: Some(offset_alignment),
+      filter: None,
+      verify_first_frame: None,
+      wait_for_present: None,
+      interpolate: None,
+      allow_oversized_buffer: None,
+      redraw_on_resize: None,
+      use_cache: None,
+    };
+    let buffer = pattern(buffer_size.0, buffer_size.1);
+    let frame = render_deterministic(&buffer, &options, output_size.0, output_size.1);
+    assert_eq!(frame.len(), (output_size.0 * output_size.1 * 4) as usize);
+    fnv1a(&frame)
+  }
+
+  #[test]
+  fn test_fixed_dimensions_match_float() {
+    // Integer math must agree with the float implementation on common sizes
+    use crate::tao::render::scaling::calculate_scaled_dimensions;
+    let cases = [
+      (1920, 1080, 800, 600),
+      (800, 600, 1920, 1080),
+      (256, 224, 1024, 768),
+      (320, 240, 640, 480),
+    ];
+    for mode in [ScaleMode::Fit, ScaleMode::Integer, ScaleMode::None] {
+      for (bw, bh, ww, wh) in cases {
+        assert_eq!(
+          calculate_scaled_dimensions_fixed(bw, bh, ww, wh, mode),
+          calculate_scaled_dimensions(bw, bh, ww, wh, mode),
+          "{:?} {}x{} -> {}x{}",
+          mode,
+          bw,
+          bh,
+          ww,
+          wh
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn test_identity_render_is_copy() {
+    let options = RenderOptions {
+      buffer_width: 8,
+      buffer_height: 6,
+      ..RenderOptions::default()
+    };
+    let buffer = pattern(8, 6);
+    assert_eq!(render_deterministic(&buffer, &options, 8, 6), buffer);
+  }
+
+  #[test]
+  fn test_none_offsets_are_aligned_like_the_window_mapping() {
+    use crate::tao::render::scaling::window_to_buffer;
+    // 4x4 centered in 11x11 starts at 3, snapped to 2 with an alignment of 2
+    let options = RenderOptions {
+      buffer_width: 4,
+      buffer_height: 4,
+      scale_mode: Some(ScaleMode::None),
+      background_color: Some(vec![0, 0, 0, 255]),
+      offset_alignment: Some(2),
+      ..RenderOptions::default()
+    };
+    let buffer = vec![255; 4 * 4 * 4];
+    let frame = render_deterministic(&buffer, &options, 11, 11);
+    let pixel = |x: usize, y: usize| &frame[(y * 11 + x) * 4..(y * 11 + x) * 4 + 4];
+    assert_eq!(pixel(2, 2), [255, 255, 255, 255]);
+    assert_eq!(pixel(6, 6), [0, 0, 0, 255]);
+    assert_eq!(
+      window_to_buffer(2, 2, 4, 4, 11, 11, ScaleMode::None, 2),
+      Some((0, 0))
+    );
+    assert_eq!(
+      window_to_buffer(6, 6, 4, 4, 11, 11, ScaleMode::None, 2),
+      None
+    );
+  }
+
+  #[test]
+  fn test_golden_hashes() {
+    // Update these only when a rendering change is intentional
+    let cases = [
+      (
+        (64, 48),
+        (200, 100),
+        ScaleMode::Fit,
+        1,
+        0xdde5_802a_9c45_45b5u64,
+      ),
+      (
+        (64, 48),
+        (100, 200),
+        ScaleMode::Fit,
+        1,
+        0xac61_ec87_200e_d0d6,
+      ),
+      (
+        (64, 48),
+        (200, 100),
+        ScaleMode::Fill,
+        1,
+        0xfbd5_eec8_53ec_c365,
+      ),
+      (
+        (64, 48),
+        (200, 100),
+        ScaleMode::Stretch,
+        1,
+        0xef4d_fec6_e2ab_6857,
+      ),
+      (
+        (32, 28),
+        (131, 97),
+        ScaleMode::Integer,
+        1,
+        0xfe8c_9cce_be99_f1a6,
+      ),
+      (
+        (32, 28),
+        (131, 97),
+        ScaleMode::Integer,
+        2,
+        0x6781_f2f8_f4bb_2efa,
+      ),
+      (
+        (64, 48),
+        (50, 30),
+        ScaleMode::None,
+        1,
+        0xb178_9a00_e607_a8e7,
+      ),
+      (
+        (64, 48),
+        (90, 70),
+        ScaleMode::None,
+        1,
+        0xa81a_7c44_5cd7_5d05,
+      ),
+    ];
+    for (buffer_size, output_size, mode, alignment, expected) in cases {
+      let hash = render_hash(buffer_size, output_size, mode, alignment);
+      assert_eq!(
+        hash, expected,
+        "{:?} {:?} -> {:?} (alignment {})",
+        mode, buffer_size, output_size, alignment
+      );
+    }
+  }
+}
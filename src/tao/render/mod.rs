@@ -3,22 +3,20 @@
 //! Provides a minimal API for rendering RGBA pixel buffers to Tao windows.
 //! Uses the pixels crate which supports multiple backends (X11, DXGI, Cocoa).
 
-use crate::tao::enums::ScaleMode;
-use crate::tao::render::scaling::calculate_scaled_dimensions;
+use crate::tao::enums::{OverlayCorner, PixelFormat, ScaleFilter, ScaleMode};
+use crate::tao::render::scaling::{
+  calculate_scaled_dimensions_aligned, covers_window, letterbox_bars, window_to_buffer,
+};
+use crate::tao::structs::{alpha_runs, apply_input_shape, refresh_input_shape, ShapeRun};
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use napi_derive::napi;
-use std::cell::RefCell;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
-// Debug logging macro - set to false to disable debug output
-const DEBUG_ENABLED: bool = false;
-
-#[allow(unused_macros)]
+// Debug logging macro - routed through the logging hook, see `set_log_level`
 macro_rules! debug_log {
     ($($arg:tt)*) => {
-        if DEBUG_ENABLED {
-            eprintln!("[PixelRenderer] {}", format!($($arg)*));
-        }
+        log_trace!("render", $($arg)*)
     };
 }
 
@@ -27,13 +25,479 @@ struct RenderState {
   pixels: pixels::Pixels<'static>,
   last_window_width: u32,
   last_window_height: u32,
+  /// Thread that created the surface; the surface must only be used from it
+  thread: std::thread::ThreadId,
+  /// Last fully shown source buffer, kept for `render_interpolated`
+  previous_buffer: Option<Vec<u8>>,
+  /// Times the presented frames for the debug overlay
+  overlay_clock: clock::FrameClock,
+  /// Last presented frame, kept for `redraw_on_resize`
+  last_frame: Option<LastFrame>,
+  /// sRGB-encoded copy of the frame uploaded to linear surfaces, see [`present_frame`]
+  encoded_frame: Vec<u8>,
+}
+
+/// A frame presented to a window with `redraw_on_resize` enabled
+struct LastFrame {
+  /// The renderer that presented the frame, with its scale mode, filter and colors
+  renderer: PixelRenderer,
+  buffer: Vec<u8>,
+}
+
+/// Render state of a window, locked on its own so windows present in parallel
+type SharedRenderState = Arc<Mutex<RenderState>>;
+
+/// States keyed by window ID, each locked on its own
+///
+/// Generic over the state so the bookkeeping does not need a GPU to be tested.
+struct RenderCache<T> {
+  states: Mutex<std::collections::HashMap<u64, Arc<Mutex<T>>>>,
+}
+
+impl<T> RenderCache<T> {
+  fn new() -> Self {
+    Self {
+      states: Mutex::new(std::collections::HashMap::new()),
+    }
+  }
+
+  fn get(&self, key: u64) -> Option<Arc<Mutex<T>>> {
+    self.states.lock().ok()?.get(&key).cloned()
+  }
+
+  /// Caches the state of a window, replacing any previous one
+  fn insert(&self, key: u64, state: T) -> Arc<Mutex<T>> {
+    let state = Arc::new(Mutex::new(state));
+    let mut states = self.states.lock().unwrap();
+    if states.insert(key, state.clone()).is_none() {
+      warn_on_cache_growth(states.len());
+    }
+    state
+  }
+
+  /// Removes the state of a window; returns whether it had one
+  fn remove(&self, key: u64) -> bool {
+    let Ok(mut states) = self.states.lock() else {
+      return false;
+    };
+    // The state, and its surface, is released after the cache is unlocked
+    let removed = states.remove(&key);
+    drop(states);
+    removed.is_some()
+  }
+
+  fn len(&self) -> usize {
+    self.states.lock().map(|states| states.len()).unwrap_or(0)
+  }
 }
 
 /// Global cache for rendering state to avoid resource exhaustion errors.
 /// The key is the window ID. Works on all platforms (X11, DXGI, Cocoa).
-static RENDER_STATE: std::sync::LazyLock<
-  Mutex<RefCell<std::collections::HashMap<u64, RenderState>>>,
-> = std::sync::LazyLock::new(|| Mutex::new(RefCell::new(std::collections::HashMap::new())));
+///
+/// The map is only locked to look states up: presenting locks the window's own
+/// state, see [`render_state`].
+static RENDER_STATE: std::sync::LazyLock<RenderCache<RenderState>> =
+  std::sync::LazyLock::new(RenderCache::new);
+
+/// An image decoded by [`PixelRenderer::render_from_file`]
+struct DecodedFile {
+  modified: Option<std::time::SystemTime>,
+  len: u64,
+  width: u32,
+  height: u32,
+  rgba: std::sync::Arc<Vec<u8>>,
+  last_used: std::time::Instant,
+}
+
+/// Total size of the decoded images kept in [`DECODED_FILES`]
+const DECODED_FILES_MAX_BYTES: usize = 256 * 1024 * 1024;
+
+/// Decoded images keyed by path, re-decoded when the file's mtime or size changes
+///
+/// The least recently rendered images are evicted once the cache exceeds
+/// [`DECODED_FILES_MAX_BYTES`].
+static DECODED_FILES: std::sync::LazyLock<
+  Mutex<std::collections::HashMap<std::path::PathBuf, DecodedFile>>,
+> = std::sync::LazyLock::new(|| Mutex::new(std::collections::HashMap::new()));
+
+/// Evicts the least recently used images until they take at most `max_bytes`
+///
+/// `keep` (the image just decoded) is never evicted, even if it alone is larger.
+fn evict_decoded_files(
+  files: &mut std::collections::HashMap<std::path::PathBuf, DecodedFile>,
+  keep: &std::path::Path,
+  max_bytes: usize,
+) {
+  let mut total: usize = files.values().map(|file| file.rgba.len()).sum();
+  while total > max_bytes {
+    let Some(oldest) = files
+      .iter()
+      .filter(|(path, _)| path.as_path() != keep)
+      .min_by_key(|(_, file)| file.last_used)
+      .map(|(path, _)| path.clone())
+    else {
+      break;
+    };
+    if let Some(file) = files.remove(&oldest) {
+      debug_log!("evicting decoded {}", oldest.display());
+      total -= file.rgba.len();
+    }
+  }
+}
+
+/// Returns the key of a window in the render state cache
+pub(crate) fn render_state_key(window: &tao::window::Window) -> u64 {
+  window_id_key(window.id())
+}
+
+/// Converts a window id to the numeric id exposed to JS (see `Window.id`)
+pub(crate) fn window_id_key(window_id: tao::window::WindowId) -> u64 {
+  unsafe {
+    let mut id_val: u64 = 0;
+    std::ptr::copy_nonoverlapping(
+      &window_id as *const _ as *const u8,
+      &mut id_val as *mut _ as *mut u8,
+      std::mem::size_of_val(&window_id).min(8),
+    );
+    id_val
+  }
+}
+
+/// Gets the cached render state of a window, without keeping the cache locked
+fn render_state(key: u64) -> Option<SharedRenderState> {
+  RENDER_STATE.get(key)
+}
+
+/// Locks the render state of a window, waiting for a frame in progress
+fn lock_render_state(
+  state: &SharedRenderState,
+) -> napi::Result<std::sync::MutexGuard<'_, RenderState>> {
+  state.lock().map_err(|_| {
+    napi::Error::new(
+      napi::Status::GenericFailure,
+      "Failed to lock render state".to_string(),
+    )
+  })
+}
+
+/// Locks a window to use its native handle
+fn lock_window(
+  window: &Mutex<tao::window::Window>,
+) -> napi::Result<std::sync::MutexGuard<'_, tao::window::Window>> {
+  window.lock().map_err(|_| {
+    napi::Error::new(
+      napi::Status::GenericFailure,
+      "Failed to lock window".to_string(),
+    )
+  })
+}
+
+/// Caches the render state of a window, replacing any previous one
+fn insert_render_state(key: u64, state: RenderState) -> SharedRenderState {
+  let state = RENDER_STATE.insert(key, state);
+  mark_alpha_hittest_stale(key);
+  state
+}
+
+/// Removes the cached render state of a window, releasing its surface
+///
+/// Must not be used for a window presented by another thread, see
+/// [`release_render_state`]. Returns whether the window had one.
+pub(crate) fn remove_render_state(key: u64) -> bool {
+  let removed = RENDER_STATE.remove(key);
+  if removed {
+    debug_log!("removed render state for window {}", key);
+  }
+  removed
+}
+
+/// Releases the render surface of a window
+///
+/// The surface of a window with a render thread is released by that thread
+/// between two frames. Returns whether the window had one.
+pub(crate) fn release_render_state(key: u64) -> bool {
+  thread::release_threaded_surface(key).unwrap_or_else(|| remove_render_state(key))
+}
+
+/// Number of cached surfaces from which the growth of the render cache is logged
+const RENDER_CACHE_WARN_THRESHOLD: usize = 64;
+
+/// Warns each time the render cache reaches another multiple of
+/// [`RENDER_CACHE_WARN_THRESHOLD`] windows, a sign of windows never released
+///
+/// Entries are not evicted: each belongs to a window that may still be rendered.
+fn warn_on_cache_growth(len: usize) {
+  if len >= RENDER_CACHE_WARN_THRESHOLD && len.is_multiple_of(RENDER_CACHE_WARN_THRESHOLD) {
+    log_warn!(
+      "render",
+      "{} windows hold a cached render surface; close windows that are done or call freeRenderState",
+      len
+    );
+  }
+}
+
+/// Set once a rendered buffer gave a conclusive channel order guess
+static CHANNEL_ORDER_CHECKED: std::sync::atomic::AtomicBool =
+  std::sync::atomic::AtomicBool::new(false);
+
+/// Warns once per process when rendered buffers don't look like RGBA
+///
+/// Buffers are sampled until one is conclusive, so blank first frames don't use
+/// up the check.
+fn warn_on_channel_order_mismatch(buffer: &[u8]) {
+  use std::sync::atomic::Ordering;
+  if CHANNEL_ORDER_CHECKED.load(Ordering::Relaxed) {
+    return;
+  }
+  let Some(format) = buffer_ops::detect_channel_order(buffer) else {
+    return;
+  };
+  if CHANNEL_ORDER_CHECKED.swap(true, Ordering::Relaxed) {
+    return;
+  }
+  match format {
+    PixelFormat::Rgba => {}
+    PixelFormat::Argb => log_warn!(
+      "render",
+      "Rendered buffer looks like ARGB (alpha first); the renderer expects RGBA, so colors will be wrong"
+    ),
+    PixelFormat::Rgbx => log_warn!(
+      "render",
+      "Rendered buffer has its alpha byte at 0 on colored pixels, like RGBX/BGRX padding; \
+       set alpha to 255 or the frame renders transparent"
+    ),
+  }
+}
+
+/// Releases the render state of a window once its native window is destroyed
+pub(crate) fn release_destroyed_window(
+  window_id: tao::window::WindowId,
+  event: &tao::event::WindowEvent,
+) {
+  if matches!(event, tao::event::WindowEvent::Destroyed) {
+    let key = window_id_key(window_id);
+    release_render_state(key);
+    remove_alpha_hittest(key);
+  }
+}
+
+/// Minimum time between two click-through region updates of a window
+const ALPHA_HITTEST_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Click-through settings of a window, see [`PixelRenderer::enable_alpha_hittest`]
+struct AlphaHittest {
+  threshold: u8,
+  updated_at: Option<std::time::Instant>,
+  /// Region last applied to the window
+  runs: Vec<ShapeRun>,
+  /// Set when the window's surface was recreated, to apply the region again
+  /// even if it did not change
+  stale: bool,
+}
+
+/// Windows with alpha-based click-through, keyed like the render state cache.
+/// Kept apart from it so the setting survives surface recreation.
+static ALPHA_HITTESTS: std::sync::LazyLock<Mutex<std::collections::HashMap<u64, AlphaHittest>>> =
+  std::sync::LazyLock::new(|| Mutex::new(std::collections::HashMap::new()));
+
+/// Forgets the click-through settings of a closed window
+pub(crate) fn remove_alpha_hittest(key: u64) {
+  ALPHA_HITTESTS.lock().unwrap().remove(&key);
+}
+
+/// Makes the next click-through update of a window apply its region
+fn mark_alpha_hittest_stale(key: u64) {
+  if let Some(hittest) = ALPHA_HITTESTS.lock().unwrap().get_mut(&key) {
+    hittest.stale = true;
+  }
+}
+
+/// Gets whether the click-through region of a window is due for an update
+fn alpha_hittest_due(hittest: &AlphaHittest) -> bool {
+  hittest.stale
+    || hittest
+      .updated_at
+      .is_none_or(|updated_at| updated_at.elapsed() >= ALPHA_HITTEST_INTERVAL)
+}
+
+/// Updates the click-through region of a window from its last rendered frame
+///
+/// Does nothing if alpha hit testing is off for the window or the region was
+/// updated less than [`ALPHA_HITTEST_INTERVAL`] ago. After the surface was
+/// recreated, the region is applied even if it did not change. Must be called
+/// on the thread owning the window, see [`update_alpha_hittest_after_frame`].
+fn update_alpha_hittest(window_id: u64, window: &tao::window::Window) {
+  let mut hittests = ALPHA_HITTESTS.lock().unwrap();
+  let Some(hittest) = hittests.get_mut(&window_id) else {
+    return;
+  };
+  if !alpha_hittest_due(hittest) {
+    return;
+  }
+
+  let runs = {
+    let Some(state) = render_state(window_id) else {
+      return;
+    };
+    // Busy with a frame of a render thread, which asks again after it
+    let Ok(state) = state.try_lock() else {
+      return;
+    };
+    let (width, height) = (state.last_window_width, state.last_window_height);
+    let frame = state.pixels.frame();
+    if frame.len() != (width * height * 4) as usize {
+      return;
+    }
+    alpha_runs(frame, width, height, 4, hittest.threshold)
+  };
+  hittest.updated_at = Some(std::time::Instant::now());
+  if hittest.stale || runs != hittest.runs {
+    apply_input_shape(window, Some(&runs));
+    hittest.runs = runs;
+    hittest.stale = false;
+  } else {
+    refresh_input_shape(window);
+  }
+}
+
+/// Updates the click-through region of a window after a presented frame
+///
+/// A render thread leaves the update to the event loop thread.
+fn update_alpha_hittest_after_frame(
+  window_id: u64,
+  window: &Arc<Mutex<tao::window::Window>>,
+  on_render_thread: bool,
+) {
+  if !on_render_thread {
+    if let Ok(window) = window.lock() {
+      update_alpha_hittest(window_id, &window);
+    }
+    return;
+  }
+  let due = ALPHA_HITTESTS
+    .lock()
+    .unwrap()
+    .get(&window_id)
+    .is_some_and(alpha_hittest_due);
+  if due {
+    thread::request_alpha_hittest(window_id, window);
+  }
+}
+
+/// Presents the last frame of a resized window again at its new size
+///
+/// Called by the event loops for every window event; does nothing unless the
+/// window's last frame was rendered with `redraw_on_resize` on this thread.
+pub(crate) fn redraw_after_resize(
+  window_id: tao::window::WindowId,
+  event: &tao::event::WindowEvent,
+) {
+  let tao::event::WindowEvent::Resized(size) = event else {
+    return;
+  };
+  // Minimized windows report a zero size
+  if size.width == 0 || size.height == 0 {
+    return;
+  }
+  let Some(state) = render_state(window_id_key(window_id)) else {
+    return;
+  };
+  // A state locked elsewhere belongs to a render thread
+  let Ok(mut state) = state.try_lock() else {
+    return;
+  };
+  if state.thread != std::thread::current().id() {
+    return;
+  }
+  let Some(last) = state.last_frame.take() else {
+    return;
+  };
+
+  if state.last_window_width != size.width || state.last_window_height != size.height {
+    let resized = state
+      .pixels
+      .resize_surface(size.width, size.height)
+      .and_then(|_| state.pixels.resize_buffer(size.width, size.height));
+    if let Err(e) = resized {
+      // The next render recreates the surface
+      debug_log!("redraw on resize: resize failed: {:?}", e);
+      state.last_frame = Some(last);
+      return;
+    }
+    state.last_window_width = size.width;
+    state.last_window_height = size.height;
+  }
+  if let Err(e) = last
+    .renderer
+    .render_with_state(&mut state, &last.buffer, size.width, size.height)
+  {
+    debug_log!("redraw on resize: render failed: {:?}", e);
+  }
+  state.last_frame = Some(last);
+}
+
+/// Keeps a copy of a source buffer as the previous frame of a window
+fn store_previous_buffer(window_id: u64, buffer: &[u8]) {
+  if let Some(state) = render_state(window_id) {
+    if let Ok(mut state) = state.lock() {
+      match &mut state.previous_buffer {
+        Some(previous) if previous.len() == buffer.len() => previous.copy_from_slice(buffer),
+        previous => *previous = Some(buffer.to_vec()),
+      }
+    }
+  }
+}
+
+/// Checks the size of an RGBA buffer and returns the bytes to render
+///
+/// With `allow_oversized`, a longer buffer is accepted and cut to the expected
+/// length; otherwise the length must match exactly.
+pub(crate) fn buffer_prefix(
+  buffer: &[u8],
+  width: u32,
+  height: u32,
+  allow_oversized: bool,
+) -> napi::Result<&[u8]> {
+  let expected_len = width as usize * height as usize * 4;
+  if buffer.len() == expected_len || (allow_oversized && buffer.len() > expected_len) {
+    return Ok(&buffer[..expected_len]);
+  }
+  Err(napi::Error::new(
+    napi::Status::GenericFailure,
+    format!(
+      "Buffer size mismatch: got {} bytes, expected {} bytes for {}x{}",
+      buffer.len(),
+      expected_len,
+      width,
+      height
+    ),
+  ))
+}
+
+/// Returns the number of windows with cached render state
+///
+/// Intended for debugging resource leaks: entries are removed when the owning
+/// `Window` is closed, destroyed or dropped (garbage-collected in JS), and by
+/// `freeRenderState`. A warning is logged every 64 cached windows.
+#[napi]
+pub fn render_cache_size() -> u32 {
+  RENDER_STATE.len() as u32
+}
+
+/// Releases the cached render surface of a window
+///
+/// Surfaces are released on their own when a window is closed, destroyed or
+/// garbage collected. This frees the GPU resources of a window that stays
+/// around without being rendered to, e.g. a hidden preview kept for reuse; the
+/// next render creates a new surface. Returns whether the window had one.
+#[napi]
+pub fn free_render_state(window: &crate::tao::structs::Window) -> bool {
+  let Some(inner) = &window.inner else {
+    return false;
+  };
+  let key = render_state_key(&inner.lock().unwrap());
+  release_render_state(key)
+}
 
 /// Render options for pixel buffer display
 #[napi(object)]
@@ -47,6 +511,38 @@ pub struct RenderOptions {
   pub scale_mode: Option<ScaleMode>,
   /// Background color for letterboxing [R, G, B, A] (default: [0, 0, 0, 255])
   pub background_color: Option<Vec<u8>>,
+  /// Alignment grid for letterbox offsets in pixels, e.g. 2 for even offsets (default: 1)
+  pub offset_alignment: Option<u32>,
+  /// Sampling filter applied with any scale mode (default: Nearest)
+  ///
+  /// `Integer` with `Bilinear` is allowed, but usually undesirable since integer
+  /// scaling is meant to keep pixels sharp.
+  pub filter: Option<ScaleFilter>,
+  /// Validate the render surface with a test pattern when it is created (default: false)
+  ///
+  /// See [`PixelRenderer::set_verify_first_frame`].
+  pub verify_first_frame: Option<bool>,
+  /// Block in render until the GPU has finished and presented the frame (default: false)
+  ///
+  /// See [`PixelRenderer::set_wait_for_present`].
+  pub wait_for_present: Option<bool>,
+  /// Keep each rendered buffer for `render_interpolated` (default: false)
+  ///
+  /// See [`PixelRenderer::set_interpolate`].
+  pub interpolate: Option<bool>,
+  /// Accept buffers longer than `buffer_width * buffer_height * 4` bytes and use
+  /// only that prefix, e.g. from sources with trailing padding (default: false)
+  pub allow_oversized_buffer: Option<bool>,
+  /// Present the last frame again, scaled to the new size, as soon as the window
+  /// is resized (default: false)
+  ///
+  /// See [`PixelRenderer::set_redraw_on_resize`].
+  pub redraw_on_resize: Option<bool>,
+  /// Keep each window's surface in the global render cache between renders
+  /// (default: true)
+  ///
+  /// See [`PixelRenderer::set_use_cache`].
+  pub use_cache: Option<bool>,
 }
 
 impl Default for RenderOptions {
@@ -56,21 +552,74 @@ impl Default for RenderOptions {
       buffer_height: 600,
       scale_mode: Some(ScaleMode::Fit),
       background_color: Some(vec![0, 0, 0, 255]),
+      offset_alignment: Some(1),
+      filter: Some(ScaleFilter::Nearest),
+      verify_first_frame: Some(false),
+      wait_for_present: Some(false),
+      interpolate: Some(false),
+      allow_oversized_buffer: Some(false),
+      redraw_on_resize: Some(false),
+      use_cache: Some(true),
     }
   }
 }
 
+/// A render failure reported to a [`PixelRenderer::set_on_error`] callback
+#[napi(object)]
+pub struct RenderError {
+  /// Description of the failure
+  pub message: String,
+  /// Whether the failure is a transient surface loss the renderer can recover from
+  pub recoverable: bool,
+  /// Whether the frame was rendered after recreating the surface
+  pub recovered: bool,
+}
+
+/// The graphics backend rendering a window, see [`PixelRenderer::active_backend`]
+#[napi(object)]
+pub struct RenderBackend {
+  /// Graphics API of the adapter: "vulkan", "metal", "dx12" or "gl"
+  pub api: String,
+  /// Name of the adapter, e.g. the GPU model or "llvmpipe"
+  pub adapter: String,
+  /// Whether the adapter renders on the CPU, e.g. llvmpipe, WARP or the fallback
+  /// adapter picked by `verifyFirstFrame`
+  pub software: bool,
+  /// Texture format of the window surface, e.g. "Bgra8UnormSrgb"
+  pub surface_format: String,
+}
+
+/// Callback receiving the render failures of a [`PixelRenderer`]
+pub type RenderErrorCallback = ThreadsafeFunction<RenderError>;
+
 /// Simple pixel renderer for Tao windows
 ///
 /// NOTE: This renderer uses a global cache to avoid resource exhaustion errors
 /// that occur when creating too many contexts/surfaces on each render call.
 /// Resources are cached per-window and reused across all PixelRenderer instances.
+///
+/// Each window must be rendered from a single thread: the surface is bound to the
+/// thread that first rendered to the window, and rendering from another thread
+/// returns an error.
 #[napi]
+#[derive(Clone)]
 pub struct PixelRenderer {
   buffer_width: u32,
   buffer_height: u32,
   scale_mode: ScaleMode,
   bg_color: [u8; 4],
+  offset_alignment: u32,
+  filter: ScaleFilter,
+  verify_first_frame: bool,
+  wait_for_present: bool,
+  interpolate: bool,
+  allow_oversized_buffer: bool,
+  redraw_on_resize: bool,
+  use_cache: bool,
+  debug_overlay: bool,
+  overlay_corner: OverlayCorner,
+  overlay_color: [u8; 4],
+  on_error: Option<Arc<RenderErrorCallback>>,
 }
 
 #[napi]
@@ -83,6 +632,18 @@ impl PixelRenderer {
       buffer_height,
       scale_mode: ScaleMode::Fit,
       bg_color: [0, 0, 0, 255],
+      offset_alignment: 1,
+      filter: ScaleFilter::Nearest,
+      verify_first_frame: false,
+      wait_for_present: false,
+      interpolate: false,
+      allow_oversized_buffer: false,
+      redraw_on_resize: false,
+      use_cache: true,
+      debug_overlay: false,
+      overlay_corner: OverlayCorner::TopLeft,
+      overlay_color: [255, 255, 255, 255],
+      on_error: None,
     }
   }
 
@@ -106,6 +667,39 @@ impl PixelRenderer {
       buffer_height: options.buffer_height,
       scale_mode: options.scale_mode.unwrap_or(ScaleMode::Fit),
       bg_color,
+      offset_alignment: options.offset_alignment.unwrap_or(1),
+      filter: options.filter.unwrap_or(ScaleFilter::Nearest),
+      verify_first_frame: options.verify_first_frame.unwrap_or(false),
+      wait_for_present: options.wait_for_present.unwrap_or(false),
+      interpolate: options.interpolate.unwrap_or(false),
+      allow_oversized_buffer: options.allow_oversized_buffer.unwrap_or(false),
+      redraw_on_resize: options.redraw_on_resize.unwrap_or(false),
+      use_cache: options.use_cache.unwrap_or(true),
+      debug_overlay: false,
+      overlay_corner: OverlayCorner::TopLeft,
+      overlay_color: [255, 255, 255, 255],
+      on_error: None,
+    }
+  }
+
+  /// Gets the options this renderer would be recreated with by `withOptions`
+  ///
+  /// The debug overlay and the `onError` callback are not part of the options.
+  #[napi]
+  pub fn options(&self) -> RenderOptions {
+    RenderOptions {
+      buffer_width: self.buffer_width,
+      buffer_height: self.buffer_height,
+      scale_mode: Some(self.scale_mode),
+      background_color: Some(self.bg_color.to_vec()),
+      offset_alignment: Some(self.offset_alignment),
+      filter: Some(self.filter),
+      verify_first_frame: Some(self.verify_first_frame),
+      wait_for_present: Some(self.wait_for_present),
+      interpolate: Some(self.interpolate),
+      allow_oversized_buffer: Some(self.allow_oversized_buffer),
+      redraw_on_resize: Some(self.redraw_on_resize),
+      use_cache: Some(self.use_cache),
     }
   }
 
@@ -121,8 +715,165 @@ impl PixelRenderer {
     self.bg_color = [r, g, b, a];
   }
 
+  /// Sets the sampling filter used when scaling
+  ///
+  /// Applies to every scale mode. `Integer` with `Bilinear` is allowed, but usually
+  /// undesirable since integer scaling is meant to keep pixels sharp.
+  #[napi]
+  pub fn set_filter(&mut self, filter: ScaleFilter) {
+    self.filter = filter;
+  }
+
+  /// Enables validation of the render surface when it is created
+  ///
+  /// Some virtual GPUs accept a surface format they cannot actually render to,
+  /// which shows up as a black or garbage window. With verification enabled, a
+  /// test pattern is rendered offscreen in the surface format and read back right
+  /// after the surface is created. If the pattern does not survive, the surface is
+  /// recreated with a non-sRGB format, and finally on the software (fallback)
+  /// adapter; if every attempt fails, render returns an error.
+  ///
+  /// Only applies to surfaces created after this call (the first render of a
+  /// window, or a recreation after a failed resize).
+  ///
+  /// # Cost
+  /// Each verification is one extra render pass, a GPU to CPU readback of two
+  /// pixels and a blocking wait for the GPU, typically a few milliseconds. Every
+  /// fallback attempt also creates a new adapter and device. Nothing is added to
+  /// later frames.
+  #[napi]
+  pub fn set_verify_first_frame(&mut self, verify: bool) {
+    self.verify_first_frame = verify;
+  }
+
+  /// Makes `render` block until the GPU has finished the frame and presented it
+  ///
+  /// Switches the surface to the vsync'd FIFO present mode, and after presenting
+  /// waits for the GPU to finish its work. `render` then returns the present
+  /// timestamp in milliseconds since the Unix epoch.
+  ///
+  /// This is not a wait for vblank: wgpu cannot observe the actual scan-out, so
+  /// the timestamp marks the point the frame was handed to the compositor, which
+  /// shows it at a later vblank. FIFO only paces rendering to the refresh rate
+  /// through the swap chain, once its images are all in use.
+  #[napi]
+  pub fn set_wait_for_present(&mut self, wait: bool) {
+    self.wait_for_present = wait;
+  }
+
+  /// Makes `render` keep each buffer as the start point for `render_interpolated`
+  ///
+  /// Needed when mixing `render` and `render_interpolated`; `render_interpolated`
+  /// keeps its own fully shown frames regardless. Keeping a copy of the source
+  /// buffer doubles the buffer memory per window.
+  #[napi]
+  pub fn set_interpolate(&mut self, interpolate: bool) {
+    self.interpolate = interpolate;
+  }
+
+  /// Accepts buffers longer than expected, using only the first
+  /// `buffer_width * buffer_height * 4` bytes
+  ///
+  /// Off by default: any size mismatch is an error. Buffers that are too short
+  /// are always rejected.
+  #[napi]
+  pub fn set_allow_oversized_buffer(&mut self, allow: bool) {
+    self.allow_oversized_buffer = allow;
+  }
+
+  /// Presents the last frame again as soon as the window is resized
+  ///
+  /// After a resize the surface shows stale or undefined content until the next
+  /// render, which flickers while the user drags the window edge. With this
+  /// enabled, each window keeps a copy of its last presented frame, and the event
+  /// loop re-scales and presents it right after every resize, with the settings of
+  /// the renderer that presented it. Costs one copy of the frame per window.
+  ///
+  /// Only applies to windows rendered on the event loop thread, and to frames
+  /// rendered after this call; a frame rendered with this disabled drops the copy.
+  #[napi]
+  pub fn set_redraw_on_resize(&mut self, redraw: bool) {
+    self.redraw_on_resize = redraw;
+  }
+
+  /// Sets whether window surfaces are kept in the global render cache
+  ///
+  /// Creating a surface takes tens of milliseconds, so by default each window's
+  /// surface is cached on its first render and reused until the window is
+  /// destroyed or `freeRenderState` is called. For a one-off render before the
+  /// window is closed, disable the cache: each render then creates the surface,
+  /// presents and drops it again, and nothing is left behind. A cached surface of
+  /// the window is released first.
+  ///
+  /// Without the cache there is no previous frame for `renderInterpolated` or
+  /// `redrawOnResize`, `prewarm` only presents, and render threads cannot be
+  /// spawned. On X11 each surface is a new connection to the X server, which
+  /// allows a limited number of clients (typically 256), so rendering frequently
+  /// without the cache can exhaust them while the old connections close.
+  #[napi]
+  pub fn set_use_cache(&mut self, use_cache: bool) {
+    self.use_cache = use_cache;
+  }
+
+  /// Draws a diagnostics overlay into every presented frame
+  ///
+  /// Shows the frame rate, the time between the last two frames in milliseconds
+  /// and the graphics API of the window, e.g. "60 FPS 16.7 MS VULKAN", in a small
+  /// built-in bitmap font on a dark box. The overlay is drawn after scaling, so
+  /// its size does not depend on the buffer size or scale mode. Timings are kept
+  /// per window and cover every render call, whichever renderer made it.
+  ///
+  /// Meant for development; disabled by default.
+  #[napi]
+  pub fn set_debug_overlay(&mut self, enabled: bool) {
+    self.debug_overlay = enabled;
+  }
+
+  /// Sets the corner of the debug overlay (default: top-left)
+  #[napi]
+  pub fn set_debug_overlay_corner(&mut self, corner: OverlayCorner) {
+    self.overlay_corner = corner;
+  }
+
+  /// Sets the text color of the debug overlay (default: opaque white)
+  #[napi]
+  pub fn set_debug_overlay_color(&mut self, r: u8, g: u8, b: u8, a: u8) {
+    self.overlay_color = [r, g, b, a];
+  }
+
+  /// Sets a callback for render failures and enables automatic surface recovery
+  ///
+  /// While a callback is set, a render that fails because the surface was lost,
+  /// became outdated or timed out (e.g. during a fullscreen transition or a GPU
+  /// reset) recreates the window's surface and retries the frame once. Those
+  /// failures no longer throw: the callback receives them with `recoverable` set,
+  /// and `recovered` tells whether the retry rendered the frame. A frame that is
+  /// still not shown is dropped, and the next render tries again.
+  ///
+  /// Any other failure (out of GPU memory, no adapter, invalid buffer size, ...)
+  /// is passed to the callback with `recoverable` unset and still throws.
+  ///
+  /// Pass `null` to remove the callback; render failures then throw again.
+  #[napi]
+  pub fn set_on_error(&mut self, callback: Option<RenderErrorCallback>) {
+    self.on_error = callback.map(Arc::new);
+  }
+
+  /// Sets the alignment grid for letterbox offsets
+  ///
+  /// Offsets are rounded down to a multiple of `alignment` so scaled content lands
+  /// on consistent pixel boundaries. Use 2 for even offsets in `ScaleMode::Integer`
+  /// to avoid half-pixel shimmer, or 1 to disable snapping.
+  #[napi]
+  pub fn set_offset_alignment(&mut self, alignment: u32) {
+    self.offset_alignment = alignment;
+  }
+
   /// Renders a pixel buffer to the given window
   ///
+  /// Returns the present timestamp when `wait_for_present` is enabled, otherwise
+  /// `undefined`.
+  ///
   /// # Arguments
   /// * `window` - The Tao window to render to
   /// * `buffer` - RGBA pixel buffer (must be buffer_width * buffer_height * 4 bytes)
@@ -132,99 +883,839 @@ impl PixelRenderer {
   /// that occur when creating new contexts/surfaces on each render call.
   /// Resources are cached per-window and reused across all PixelRenderer instances.
   #[napi]
-  pub fn render(&self, window: &crate::tao::structs::Window, buffer: Buffer) -> napi::Result<()> {
+  pub fn render(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+  ) -> napi::Result<Option<f64>> {
+    let buffer = self.validate_buffer(&buffer)?;
+    let presented = self.render_bytes(window, buffer)?;
+    if self.interpolate {
+      if let Some(inner) = &window.inner {
+        let window_id = render_state_key(&inner.lock().unwrap());
+        store_previous_buffer(window_id, buffer);
+      }
+    }
+    Ok(presented)
+  }
+
+  /// Renders a `Uint8ClampedArray` or `Uint8Array` view to the given window
+  ///
+  /// Behaves like `render`, but borrows the typed array's memory for the duration of
+  /// the call instead of requiring a `Buffer`, so an `ImageData.data` array or a view
+  /// into a larger `ArrayBuffer` can be rendered without copying.
+  ///
+  /// # Lifetime
+  /// The view is only borrowed while this call runs: it must not be detached or
+  /// transferred (e.g. posted to a worker) until `renderView` returns. The renderer
+  /// keeps no reference to it afterwards.
+  #[napi]
+  pub fn render_view(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Either<Uint8ClampedSlice<'_>, Uint8ArraySlice<'_>>,
+  ) -> napi::Result<Option<f64>> {
+    let bytes: &[u8] = match &buffer {
+      Either::A(clamped) => clamped,
+      Either::B(array) => array,
+    };
+    let bytes = self.validate_buffer(bytes)?;
+    let presented = self.render_bytes(window, bytes)?;
+    if self.interpolate {
+      if let Some(inner) = &window.inner {
+        let window_id = render_state_key(&inner.lock().unwrap());
+        store_previous_buffer(window_id, bytes);
+      }
+    }
+    Ok(presented)
+  }
+
+  /// Creates and caches the render surface of a window ahead of the first frame
+  ///
+  /// The first render to a window creates its GPU surface, which can take tens of
+  /// milliseconds and causes a hitch. Calling this right after the window is
+  /// created, e.g. behind a loading screen, moves that cost out of the first real
+  /// frame. With `present` set, a frame cleared to the background color is also
+  /// shown. Does nothing if the window already has a surface.
+  ///
+  /// Must be called on the thread that will render to the window: the surface is
+  /// bound to the thread that created it. Without the render cache nothing is
+  /// kept, so only the `present` frame is shown.
+  #[napi]
+  pub fn prewarm(
+    &self,
+    window: &crate::tao::structs::Window,
+    present: Option<bool>,
+  ) -> napi::Result<()> {
+    if present.unwrap_or(false) {
+      let cleared = self
+        .bg_color
+        .repeat((self.buffer_width * self.buffer_height) as usize);
+      return self.render_bytes(window, &cleared).map(|_| ());
+    }
+    if !self.use_cache {
+      return Ok(());
+    }
+
     let window_arc = window.inner.as_ref().ok_or_else(|| {
       napi::Error::new(
         napi::Status::GenericFailure,
         "Window not initialized".to_string(),
       )
     })?;
-
     let window_guard = window_arc.lock().map_err(|_| {
       napi::Error::new(
         napi::Status::GenericFailure,
         "Failed to lock window".to_string(),
       )
     })?;
+    self.ensure_render_state(&window_guard)
+  }
 
-    // Get the window ID for caching
-    let window_id = window_guard.id();
-    let window_id_u64 = unsafe {
-      let mut id_val: u64 = 0;
-      std::ptr::copy_nonoverlapping(
-        &window_id as *const _ as *const u8,
-        &mut id_val as *mut _ as *mut u8,
-        std::mem::size_of_val(&window_id).min(8),
-      );
-      id_val
-    };
-
-    let window_size = window_guard.inner_size();
-    let window_width = window_size.width;
-    let window_height = window_size.height;
-
-    // Validate buffer size
-    let expected_len = (self.buffer_width * self.buffer_height * 4) as usize;
-    if buffer.len() != expected_len {
+  /// Keeps a window from shrinking below the size its buffer needs at 1x
+  ///
+  /// Sets the window's minimum inner size to `minWindowSizeForInteger` of the
+  /// buffer size, so the `Integer` scale mode never has to crop the content. The
+  /// minimum is converted to logical pixels with the current scale factor: call
+  /// this again after a `ScaleFactorChanged` event. Use `Window.setMinSizeLogical`
+  /// to remove it.
+  ///
+  /// Throws if the scale mode is not `Integer`.
+  #[napi]
+  pub fn apply_integer_min_size(&self, window: &crate::tao::structs::Window) -> napi::Result<()> {
+    if !matches!(self.scale_mode, ScaleMode::Integer) {
       return Err(napi::Error::new(
-        napi::Status::GenericFailure,
+        napi::Status::InvalidArg,
         format!(
-          "Buffer size mismatch: got {} bytes, expected {} bytes for {}x{}",
-          buffer.len(),
-          expected_len,
-          self.buffer_width,
-          self.buffer_height
+          "Integer minimum size needs the Integer scale mode, not {:?}",
+          self.scale_mode
         ),
       ));
     }
-
+    let Some(inner) = &window.inner else {
+      return Ok(());
+    };
+    let window = inner.lock().map_err(|_| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Failed to lock window".to_string(),
+      )
+    })?;
+    let size = min_window_size_for_integer(self.buffer_width, self.buffer_height);
+    let scale_factor = window.scale_factor();
+    crate::tao::structs::apply_min_size(
+      &window,
+      Some((size.width / scale_factor, size.height / scale_factor)),
+    );
+    Ok(())
+  }
+
+  /// Presents frames to a window from a dedicated thread
+  ///
+  /// Returns a [`RenderThread`](thread::RenderThread) owning the window's surface:
+  /// frames passed to its `submitFrame` are copied and presented on that thread
+  /// with this renderer's settings, as they were when the thread was spawned. JS
+  /// only pays for the copy, so many windows can present at high frame rates
+  /// without queueing up on the main thread.
+  ///
+  /// Must be called on the event loop thread. Window handles are only used on
+  /// that thread, so it creates the surface for the render thread, here and again
+  /// when the surface is lost, and updates the click-through region of
+  /// `enableAlphaHittest`; the window size a frame is presented at is the one it
+  /// last saw in a `Resized` event. A surface the window already had is released,
+  /// so no surface is ever used by two threads. Rendering the window in any other
+  /// way throws until the render thread is stopped. A window has at most one
+  /// render thread.
+  ///
+  /// Only supported on Windows, Linux and the BSDs, which present to a window
+  /// from any thread. Throws on macOS, where the surface is the view's Metal
+  /// layer and AppKit only allows it to be used on the main thread, and if the
+  /// render cache is disabled with `setUseCache`.
+  #[napi]
+  pub fn spawn_render_thread(
+    &self,
+    window: &crate::tao::structs::Window,
+  ) -> napi::Result<thread::RenderThread> {
+    if !self.use_cache {
+      return Err(napi::Error::new(
+        napi::Status::InvalidArg,
+        "Render threads need the render cache, see setUseCache".to_string(),
+      ));
+    }
+    let window_arc = window.inner.as_ref().ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Window not initialized".to_string(),
+      )
+    })?;
+    thread::RenderThread::spawn(self, window_arc.clone())
+  }
+
+  /// Gets the graphics backend cached for a window
+  ///
+  /// Returns `null` until the window has been rendered to (or prewarmed), and
+  /// after its surface was released. The result reflects the adapter and surface
+  /// format actually chosen, including the fallbacks of `verifyFirstFrame`, while
+  /// `buildInfo().pixelsBackend` only lists the APIs compiled in.
+  #[napi]
+  pub fn active_backend(&self, window: &crate::tao::structs::Window) -> Option<RenderBackend> {
+    let window_id = render_state_key(&*window.inner.as_ref()?.lock().ok()?);
+    let state = render_state(window_id)?;
+    let state = state.lock().ok()?;
+    let pixels = &state.pixels;
+    let info = pixels.adapter().get_info();
+    Some(RenderBackend {
+      api: info.backend.to_str().to_string(),
+      adapter: info.name,
+      software: info.device_type == pixels::wgpu::DeviceType::Cpu,
+      surface_format: format!("{:?}", pixels.render_texture_format()),
+    })
+  }
+
+  /// Measures the time from submitting a frame to the GPU finishing it, in milliseconds
+  ///
+  /// Presents the window's current frame again and times it from submission until
+  /// the GPU signals the queue's work as done, a fence on the submission. Waiting
+  /// for a free surface texture, which FIFO (vsync'd) presentation blocks on, is
+  /// included.
+  ///
+  /// This is an estimate of the backend's own latency: the compositor and the
+  /// wait for scan-out come on top, often one or more refresh intervals. Measure a
+  /// few times and take the median; the first frames after creating a surface are
+  /// slower.
+  ///
+  /// Throws if the window has no surface yet (render or prewarm it first), or when
+  /// called from another thread than the one rendering the window.
+  #[napi]
+  pub fn measure_present_latency(&self, window: &crate::tao::structs::Window) -> napi::Result<f64> {
+    let window_arc = window.inner.as_ref().ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Window not initialized".to_string(),
+      )
+    })?;
+    let window_id = render_state_key(&*window_arc.lock().map_err(|_| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Failed to lock window".to_string(),
+      )
+    })?);
+
+    let state = render_state(window_id).ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Window has no render surface yet; render or prewarm it first".to_string(),
+      )
+    })?;
+    let mut state = lock_render_state(&state)?;
+    if state.thread != std::thread::current().id() {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        "Window render surface was created on another thread".to_string(),
+      ));
+    }
+
+    let done = Arc::new(Mutex::new(None));
+    let start = std::time::Instant::now();
+    present_frame(&mut state).map_err(|e| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to render: {:?}", e),
+      )
+    })?;
+    let signaled = done.clone();
+    state.pixels.queue().on_submitted_work_done(move || {
+      *signaled.lock().unwrap() = Some(std::time::Instant::now());
+    });
+    state.pixels.device().poll(pixels::wgpu::Maintain::Wait);
+
+    let end = done.lock().unwrap().unwrap_or_else(std::time::Instant::now);
+    Ok(end.duration_since(start).as_secs_f64() * 1000.0)
+  }
+
+  /// Fills the whole window with a color
+  ///
+  /// Like every rendered frame, the color is adjusted to the window's surface
+  /// format (see `render`), so the displayed pixels match the requested RGBA
+  /// values on sRGB and linear surfaces alike. Channel order (RGBA vs. BGRA
+  /// surfaces) is handled by the GPU and needs no conversion.
+  ///
+  /// Creates the window's surface if it does not have one yet.
+  #[napi]
+  pub fn clear(
+    &self,
+    window: &crate::tao::structs::Window,
+    r: u8,
+    g: u8,
+    b: u8,
+    a: u8,
+  ) -> napi::Result<()> {
+    self.prewarm(window, None)?;
+
+    // A 1x1 buffer stretched with nearest filtering covers every pixel
+    let renderer = PixelRenderer {
+      buffer_width: 1,
+      buffer_height: 1,
+      scale_mode: ScaleMode::Stretch,
+      filter: ScaleFilter::Nearest,
+      ..self.clone()
+    };
+    renderer.render_bytes(window, &[r, g, b, a]).map(|_| ())
+  }
+
+  /// Makes clicks pass through the transparent parts of what is rendered
+  ///
+  /// From the next render on, pixels of the displayed frame with an alpha below
+  /// `threshold` stop receiving pointer input, so a shaped overlay only captures
+  /// clicks over its visible content. The region follows the rendered frames but
+  /// is recomputed at most every 100 ms, so it can briefly lag behind animations.
+  ///
+  /// The setting belongs to the window, not to this renderer, and lasts until
+  /// [`PixelRenderer::disable_alpha_hittest`] or the window is closed.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Sets the GTK input shape; the window looks unchanged.
+  /// - **Windows**: Hit tests over pixels below the threshold report the window
+  ///   as transparent; the window looks unchanged. Clicks reach other processes
+  ///   once the cursor has rested there until the next region update.
+  /// - **macOS**: No-op. Transparent windows already let clicks pass through
+  ///   fully transparent pixels.
+  #[napi]
+  pub fn enable_alpha_hittest(&self, window: &crate::tao::structs::Window, threshold: u8) {
+    let Some(inner) = &window.inner else {
+      return;
+    };
+    let key = render_state_key(&inner.lock().unwrap());
+    ALPHA_HITTESTS.lock().unwrap().insert(
+      key,
+      AlphaHittest {
+        threshold,
+        updated_at: None,
+        runs: Vec::new(),
+        stale: false,
+      },
+    );
+  }
+
+  /// Turns off alpha-based click-through, so the whole window receives input again
+  #[napi]
+  pub fn disable_alpha_hittest(&self, window: &crate::tao::structs::Window) {
+    if let Some(inner) = &window.inner {
+      let window = inner.lock().unwrap();
+      if ALPHA_HITTESTS
+        .lock()
+        .unwrap()
+        .remove(&render_state_key(&window))
+        .is_some()
+      {
+        apply_input_shape(&window, None);
+      }
+    }
+  }
+
+  /// Renders a blend between the previous frame and a new buffer
+  ///
+  /// Smooths low frame rate sources, e.g. a 30 fps source on a 60 Hz display:
+  /// render each new buffer once at `t = 0.5` and once at `t = 1`. `t` is the blend
+  /// factor from the previous frame (0.0) to `buffer` (1.0).
+  ///
+  /// A render with `t >= 1` makes `buffer` the previous frame for the next call.
+  /// Without a previous frame of the same size, `buffer` is rendered as is.
+  ///
+  /// # Memory
+  /// The previous frame is a copy of the source buffer kept per window, which
+  /// doubles the buffer memory per window.
+  #[napi]
+  pub fn render_interpolated(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+    t: f64,
+  ) -> napi::Result<Option<f64>> {
+    let buffer = self.validate_buffer(&buffer)?;
+    let window_id = window
+      .inner
+      .as_ref()
+      .and_then(|inner| inner.lock().ok().map(|window| render_state_key(&window)));
+
+    let blended = window_id.and_then(|window_id| {
+      let state = render_state(window_id)?;
+      let state = state.lock().ok()?;
+      let previous = state.previous_buffer.as_ref()?;
+      (previous.len() == buffer.len()).then(|| buffer_ops::blend_buffers(previous, buffer, t))
+    });
+
+    let presented = self.render_bytes(window, blended.as_deref().unwrap_or(buffer))?;
+    if t >= 1.0 || blended.is_none() {
+      if let Some(window_id) = window_id {
+        store_previous_buffer(window_id, buffer);
+      }
+    }
+    Ok(presented)
+  }
+
+  /// Renders a pixel buffer through a fixed-size internal frame
+  ///
+  /// The buffer is first composed into an `internal_width` x `internal_height` frame
+  /// using the current scale mode and background color, and that frame is then
+  /// scaled to the window with the same mode. This decouples the layout resolution
+  /// from the physical window size, like a game's render target.
+  ///
+  /// # Arguments
+  /// * `window` - The Tao window to render to
+  /// * `buffer` - RGBA pixel buffer (must be buffer_width * buffer_height * 4 bytes)
+  /// * `internal_width` - Width of the intermediate frame in pixels
+  /// * `internal_height` - Height of the intermediate frame in pixels
+  #[napi]
+  pub fn render_scaled_output(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+    internal_width: u32,
+    internal_height: u32,
+  ) -> napi::Result<Option<f64>> {
+    let buffer = self.validate_buffer(&buffer)?;
+    if internal_width == 0 || internal_height == 0 {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        format!(
+          "Invalid internal resolution: {}x{}",
+          internal_width, internal_height
+        ),
+      ));
+    }
+
+    let options = self.options();
+    let internal_frame =
+      deterministic::render_deterministic(buffer, &options, internal_width, internal_height);
+
+    let output = PixelRenderer {
+      buffer_width: internal_width,
+      buffer_height: internal_height,
+      ..self.clone()
+    };
+    output.render_bytes(window, &internal_frame)
+  }
+
+  /// Renders a sub-rectangle of a larger buffer, e.g. one frame of a sprite sheet
+  ///
+  /// Only `src_rect` of the `buffer_width` x `buffer_height` RGBA `buffer` is shown,
+  /// scaled and positioned in the window as a buffer of the rectangle's size with
+  /// this renderer's scale mode, background color and filter. The renderer's own
+  /// buffer size is ignored.
+  ///
+  /// `src_rect` is in buffer pixels (fractions are truncated) and must lie within
+  /// the buffer.
+  #[napi]
+  pub fn render_sub(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+    buffer_width: u32,
+    buffer_height: u32,
+    src_rect: crate::tao::structs::Rectangle,
+  ) -> napi::Result<Option<f64>> {
+    let expected_len = (buffer_width * buffer_height * 4) as usize;
+    if buffer.len() != expected_len {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        format!(
+          "Buffer size mismatch: got {} bytes, expected {} bytes for {}x{}",
+          buffer.len(),
+          expected_len,
+          buffer_width,
+          buffer_height
+        ),
+      ));
+    }
+
+    let (x, y) = (src_rect.origin.x, src_rect.origin.y);
+    let (width, height) = (src_rect.size.width, src_rect.size.height);
+    if x < 0.0
+      || y < 0.0
+      || width < 1.0
+      || height < 1.0
+      || x + width > buffer_width as f64
+      || y + height > buffer_height as f64
+    {
+      return Err(napi::Error::new(
+        napi::Status::InvalidArg,
+        format!(
+          "Source rectangle {}x{} at ({}, {}) is empty or outside the {}x{} buffer",
+          width, height, x, y, buffer_width, buffer_height
+        ),
+      ));
+    }
+
+    let (x, y, width, height) = (x as u32, y as u32, width as u32, height as u32);
+    let sub = buffer_ops::crop_buffer(&buffer, buffer_width, x, y, width, height);
+    let renderer = PixelRenderer {
+      buffer_width: width,
+      buffer_height: height,
+      ..self.clone()
+    };
+    renderer.render_bytes(window, &sub)
+  }
+
+  /// Renders an image file (PNG, JPEG, BMP or WEBP) to the given window
+  ///
+  /// The file is decoded once and cached by path; it is only decoded again when its
+  /// modification time or size changes, so static content can be redrawn without
+  /// passing buffers from JS. The image's own dimensions are used as the buffer size,
+  /// with this renderer's scale mode, background color and offset alignment.
+  #[napi]
+  pub fn render_from_file(
+    &self,
+    window: &crate::tao::structs::Window,
+    path: String,
+  ) -> napi::Result<Option<f64>> {
+    let path = std::path::PathBuf::from(path);
+    let metadata = std::fs::metadata(&path).map_err(|e| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to read {}: {}", path.display(), e),
+      )
+    })?;
+    let modified = metadata.modified().ok();
+    let len = metadata.len();
+
+    let cached = {
+      let mut files = DECODED_FILES.lock().unwrap();
+      files
+        .get_mut(&path)
+        .filter(|file| file.modified == modified && file.len == len)
+        .map(|file| {
+          file.last_used = std::time::Instant::now();
+          (file.width, file.height, file.rgba.clone())
+        })
+    };
+
+    let (width, height, rgba) = match cached {
+      Some(image) => image,
+      None => {
+        debug_log!("decoding {}", path.display());
+        let bytes = std::fs::read(&path).map_err(|e| {
+          napi::Error::new(
+            napi::Status::GenericFailure,
+            format!("Failed to read {}: {}", path.display(), e),
+          )
+        })?;
+        let (width, height, rgba) = crate::tao::functions::decode_rgba(&bytes)?;
+        let rgba = std::sync::Arc::new(rgba);
+        let mut files = DECODED_FILES.lock().unwrap();
+        files.insert(
+          path.clone(),
+          DecodedFile {
+            modified,
+            len,
+            width,
+            height,
+            rgba: rgba.clone(),
+            last_used: std::time::Instant::now(),
+          },
+        );
+        evict_decoded_files(&mut files, &path, DECODED_FILES_MAX_BYTES);
+        (width, height, rgba)
+      }
+    };
+
+    let renderer = PixelRenderer {
+      buffer_width: width,
+      buffer_height: height,
+      ..self.clone()
+    };
+    renderer.render_bytes(window, &rgba)
+  }
+
+  /// Renders a pixel buffer to the given window and returns the frame shown
+  ///
+  /// The returned buffer is the RGBA frame after scaling and letterboxing, at the
+  /// window's inner size in physical pixels (`Window.innerSize`), e.g. for feeding
+  /// an encoder while the window shows a preview. It is copied from the CPU-side
+  /// frame that was uploaded for display, so no GPU readback is involved; the copy
+  /// costs one memcpy of the window-sized frame.
+  #[napi]
+  pub fn render_and_capture(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: Buffer,
+  ) -> napi::Result<Buffer> {
+    self.render_bytes(window, &buffer)?;
+
+    let window_id = window
+      .inner
+      .as_ref()
+      .and_then(|inner| inner.lock().ok().map(|window| render_state_key(&window)));
+    let state = window_id.and_then(render_state).ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Frame not available after render".to_string(),
+      )
+    })?;
+    let frame = lock_render_state(&state)?.pixels.frame().to_vec();
+    Ok(frame.into())
+  }
+
+  /// Reads the color displayed at a point of the last rendered frame
+  ///
+  /// `x` and `y` are physical window coordinates, e.g. from a `CursorMoved`
+  /// event. Points in the letterbox area return the background color; other
+  /// points return the RGBA value of the cached frame, i.e. the source pixel after
+  /// scaling and filtering.
+  ///
+  /// Uses this renderer's buffer size, scale mode and alignment to locate the
+  /// content, so call it on the renderer that drew the frame.
+  #[napi]
+  pub fn sample_pixel(
+    &self,
+    window: &crate::tao::structs::Window,
+    x: f64,
+    y: f64,
+  ) -> napi::Result<Vec<u8>> {
+    let window_arc = window.inner.as_ref().ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Window not initialized".to_string(),
+      )
+    })?;
+    let window_id = {
+      let window_guard = window_arc.lock().map_err(|_| {
+        napi::Error::new(
+          napi::Status::GenericFailure,
+          "Failed to lock window".to_string(),
+        )
+      })?;
+      render_state_key(&window_guard)
+    };
+
+    let state = render_state(window_id).ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Window has not been rendered yet".to_string(),
+      )
+    })?;
+    let state = lock_render_state(&state)?;
+
+    let (frame_width, frame_height) = (state.last_window_width, state.last_window_height);
+    if x < 0.0 || y < 0.0 || x >= frame_width as f64 || y >= frame_height as f64 {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        format!(
+          "Point ({}, {}) is outside the rendered frame of {}x{}",
+          x, y, frame_width, frame_height
+        ),
+      ));
+    }
+    let (x, y) = (x as u32, y as u32);
+
+    let mapped = window_to_buffer(
+      x,
+      y,
+      self.buffer_width,
+      self.buffer_height,
+      frame_width,
+      frame_height,
+      self.scale_mode,
+      self.offset_alignment,
+    );
+    if mapped.is_none() {
+      return Ok(self.bg_color.to_vec());
+    }
+
+    let idx = (y as usize * frame_width as usize + x as usize) * 4;
+    Ok(
+      state
+        .pixels
+        .frame()
+        .get(idx..idx + 4)
+        .map(|pixel| pixel.to_vec())
+        .unwrap_or_else(|| self.bg_color.to_vec()),
+    )
+  }
+
+  /// Checks that a buffer matches the configured buffer dimensions
+  fn validate_buffer<'a>(&self, buffer: &'a [u8]) -> napi::Result<&'a [u8]> {
+    buffer_prefix(
+      buffer,
+      self.buffer_width,
+      self.buffer_height,
+      self.allow_oversized_buffer,
+    )
+  }
+
+  /// Renders raw RGBA bytes to the given window
+  pub(crate) fn render_bytes(
+    &self,
+    window: &crate::tao::structs::Window,
+    buffer: &[u8],
+  ) -> napi::Result<Option<f64>> {
+    warn_on_channel_order_mismatch(buffer);
+    let window_arc = window.inner.as_ref().ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Window not initialized".to_string(),
+      )
+    })?;
+    self.render_window(window_arc, buffer)
+  }
+
+  /// Renders raw RGBA bytes to a tao window
+  ///
+  /// Locks the window to read its size and, if needed, to create its surface.
+  fn render_window(
+    &self,
+    window: &Arc<Mutex<tao::window::Window>>,
+    buffer: &[u8],
+  ) -> napi::Result<Option<f64>> {
+    // Get the window ID for caching
+    let (window_id_u64, window_size) = {
+      let window_guard = lock_window(window)?;
+      (render_state_key(&window_guard), window_guard.inner_size())
+    };
+    let window_width = window_size.width;
+    let window_height = window_size.height;
+
+    // Validate buffer size
+    let buffer = self.validate_buffer(buffer)?;
+
     // Render using cached pixels instance
-    self.render_cached(
-      window_id_u64,
-      &window_guard,
-      &buffer,
+    self.render_cached(window_id_u64, window, buffer, window_width, window_height)
+  }
+
+  /// Renders raw RGBA bytes on the render thread of a window
+  ///
+  /// Never locks the window: the size is the one the event loop thread sampled
+  /// when the frame was queued, and the surface is created by that thread.
+  pub(crate) fn render_threaded(
+    &self,
+    window_id: u64,
+    window: &Arc<Mutex<tao::window::Window>>,
+    buffer: &[u8],
+    window_width: u32,
+    window_height: u32,
+  ) -> napi::Result<Option<f64>> {
+    let buffer = self.validate_buffer(buffer)?;
+    self.render_cached(window_id, window, buffer, window_width, window_height)
+  }
+
+  /// Render using cached pixels instance, recovering from a lost surface if enabled
+  ///
+  /// On a render thread, the surface is recreated by the event loop thread and
+  /// the frame is dropped.
+  fn render_cached(
+    &self,
+    window_id: u64,
+    window: &Arc<Mutex<tao::window::Window>>,
+    buffer: &[u8],
+    window_width: u32,
+    window_height: u32,
+  ) -> napi::Result<Option<f64>> {
+    let on_render_thread = thread::check_render_thread(window_id)?;
+    let error = match self.render_cached_once(
+      window_id,
+      window,
+      on_render_thread,
+      buffer,
       window_width,
       window_height,
-    )
+    )? {
+      Ok(presented) => {
+        update_alpha_hittest_after_frame(window_id, window, on_render_thread);
+        return Ok(presented);
+      }
+      Err(error) => error,
+    };
+    let message = format!("Failed to render: {:?}", error);
+    let Some(on_error) = &self.on_error else {
+      return Err(napi::Error::new(napi::Status::GenericFailure, message));
+    };
+
+    if !is_recoverable(&error) {
+      let report = RenderError {
+        message: message.clone(),
+        recoverable: false,
+        recovered: false,
+      };
+      let _ = on_error.call(Ok(report), ThreadsafeFunctionCallMode::NonBlocking);
+      return Err(napi::Error::new(napi::Status::GenericFailure, message));
+    }
+
+    debug_log!("{}, recreating the surface", message);
+    remove_render_state(window_id);
+    let retry = if on_render_thread {
+      thread::request_surface(window_id, window, self)?;
+      None
+    } else {
+      self
+        .render_cached_once(
+          window_id,
+          window,
+          false,
+          buffer,
+          window_width,
+          window_height,
+        )
+        .ok()
+        .and_then(|presented| presented.ok())
+    };
+    let recovered = retry.is_some();
+    if recovered {
+      update_alpha_hittest_after_frame(window_id, window, false);
+    }
+    let report = RenderError {
+      message,
+      recoverable: true,
+      recovered,
+    };
+    let _ = on_error.call(Ok(report), ThreadsafeFunctionCallMode::NonBlocking);
+    Ok(retry.flatten())
   }
 
-  /// Render using cached pixels instance (platform-agnostic)
-  fn render_cached(
+  /// Renders once with the cached pixels instance (platform-agnostic)
+  ///
+  /// The outer error is a setup failure, the inner one a failed present.
+  fn render_cached_once(
     &self,
     window_id: u64,
-    window: &tao::window::Window,
+    window: &Arc<Mutex<tao::window::Window>>,
+    on_render_thread: bool,
     buffer: &[u8],
     window_width: u32,
     window_height: u32,
-  ) -> napi::Result<()> {
-    // Get or create the rendering state from the global cache using entry API
-    let cache = RENDER_STATE.lock().map_err(|_| {
-      napi::Error::new(
-        napi::Status::GenericFailure,
-        "Failed to lock render state cache".to_string(),
-      )
-    })?;
+  ) -> napi::Result<std::result::Result<Option<f64>, pixels::Error>> {
+    if !self.use_cache {
+      return self.render_uncached(window_id, window, buffer, window_width, window_height);
+    }
 
-    // Use entry API for single lookup - more efficient than contains_key + get_mut
-    let mut cache_ref = cache.borrow_mut();
-    let state = cache_ref.entry(window_id).or_insert_with(|| {
-      // Create new pixels instance with window dimensions
-      let surface_texture = pixels::SurfaceTexture::new(window_width, window_height, window);
-      let new_pixels = pixels::Pixels::new(window_width, window_height, surface_texture)
-        .expect("Failed to create pixels instance");
-
-      // SAFETY: Extending lifetime to 'static is safe because:
-      // 1. The pixels instance is only used while the window is alive
-      // 2. The window ID is unique and won't be reused
-      // 3. We clean up when the window is closed
-      let static_pixels: pixels::Pixels<'static> = unsafe { std::mem::transmute(new_pixels) };
-
-      RenderState {
-        pixels: static_pixels,
-        last_window_width: window_width,
-        last_window_height: window_height,
+    // Get or create the rendering state; the cache is not locked while presenting
+    let shared = match render_state(window_id) {
+      Some(shared) => shared,
+      None if on_render_thread => {
+        // Window handles are only used on the event loop thread, which creates
+        // the surface; frames are dropped until then
+        thread::request_surface(window_id, window, self)?;
+        return Ok(Ok(None));
       }
-    });
+      None => insert_render_state(
+        window_id,
+        self.new_render_state(&*lock_window(window)?, window_width, window_height)?,
+      ),
+    };
+    let mut state = lock_render_state(&shared)?;
+
+    // The GPU surface is bound to the thread that created it
+    let current_thread = std::thread::current().id();
+    if state.thread != current_thread {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        format!(
+          "Window render surface was created on thread {:?} but render was called from thread {:?}. \
+           Each window must always be rendered from the same thread.",
+          state.thread, current_thread
+        ),
+      ));
+    }
 
     // Handle window resize if needed
     let needs_resize =
@@ -245,44 +1736,22 @@ impl PixelRenderer {
           "  resize_surface failed: {:?}, recreating pixels instance",
           e
         );
-        // If resize fails, fall back to recreating
-        // Drop the current borrow of the hashmap
-        drop(cache_ref);
-
-        // Get mutable access to cache and recreate
-        let mut cache_mut = cache.borrow_mut();
-        cache_mut.remove(&window_id);
-
-        let surface_texture = pixels::SurfaceTexture::new(window_width, window_height, window);
-        let new_pixels = pixels::Pixels::new(window_width, window_height, surface_texture)
-          .map_err(|e| {
-            napi::Error::new(
-              napi::Status::GenericFailure,
-              format!("Failed to create pixels: {:?}", e),
-            )
-          })?;
-
-        let static_pixels: pixels::Pixels<'static> = unsafe { std::mem::transmute(new_pixels) };
-
-        cache_mut.insert(
+        // If resize fails, fall back to recreating; the old surface goes first
+        drop(state);
+        drop(shared);
+        remove_render_state(window_id);
+        if on_render_thread {
+          thread::request_surface(window_id, window, self)?;
+          return Ok(Ok(None));
+        }
+        let shared = insert_render_state(
           window_id,
-          RenderState {
-            pixels: static_pixels,
-            last_window_width: window_width,
-            last_window_height: window_height,
-          },
+          self.new_render_state(&*lock_window(window)?, window_width, window_height)?,
         );
-
-        // Get the newly inserted state
-        let state = cache_mut.get_mut(&window_id).ok_or_else(|| {
-          napi::Error::new(
-            napi::Status::GenericFailure,
-            "Render state not available after recreation".to_string(),
-          )
-        })?;
+        let mut state = lock_render_state(&shared)?;
 
         // Continue with rendering using the new state
-        return self.render_with_state(state, buffer, window_width, window_height);
+        return Ok(self.render_with_state(&mut state, buffer, window_width, window_height));
       } else {
         // Also resize the pixel buffer to match window dimensions
         if let Err(e) = state.pixels.resize_buffer(window_width, window_height) {
@@ -300,7 +1769,157 @@ impl PixelRenderer {
       }
     }
 
-    self.render_with_state(state, buffer, window_width, window_height)
+    Ok(self.render_with_state(&mut state, buffer, window_width, window_height))
+  }
+
+  /// Renders once with a surface created for this call only, see [`PixelRenderer::set_use_cache`]
+  fn render_uncached(
+    &self,
+    window_id: u64,
+    window: &Mutex<tao::window::Window>,
+    buffer: &[u8],
+    window_width: u32,
+    window_height: u32,
+  ) -> napi::Result<std::result::Result<Option<f64>, pixels::Error>> {
+    // A window can only have one surface configured at a time
+    release_render_state(window_id);
+    let mut state = self.new_render_state(&*lock_window(window)?, window_width, window_height)?;
+    Ok(self.render_with_state(&mut state, buffer, window_width, window_height))
+  }
+
+  /// Creates and caches the render surface of a window if it has none
+  fn ensure_render_state(&self, window: &tao::window::Window) -> napi::Result<()> {
+    let key = render_state_key(window);
+    thread::check_render_thread(key)?;
+    if render_state(key).is_none() {
+      let size = window.inner_size();
+      insert_render_state(key, self.new_render_state(window, size.width, size.height)?);
+      debug_log!(
+        "prewarmed surface for {}x{} window",
+        size.width,
+        size.height
+      );
+    }
+    Ok(())
+  }
+
+  /// Keeps a copy of a presented frame for [`redraw_after_resize`]
+  fn keep_last_frame(&self, state: &mut RenderState, buffer: &[u8]) {
+    // The copy does not store frames itself when it is presented again
+    let renderer = PixelRenderer {
+      redraw_on_resize: false,
+      ..self.clone()
+    };
+    match &mut state.last_frame {
+      Some(last) if last.buffer.len() == buffer.len() => {
+        last.buffer.copy_from_slice(buffer);
+        last.renderer = renderer;
+      }
+      last => {
+        *last = Some(LastFrame {
+          renderer,
+          buffer: buffer.to_vec(),
+        })
+      }
+    }
+  }
+
+  /// Creates the render state of a window with a new pixels instance
+  fn new_render_state(
+    &self,
+    window: &tao::window::Window,
+    window_width: u32,
+    window_height: u32,
+  ) -> napi::Result<RenderState> {
+    self.new_render_state_for(
+      window,
+      window_width,
+      window_height,
+      std::thread::current().id(),
+    )
+  }
+
+  /// Creates the render state of a window for the thread that will present to it
+  ///
+  /// Only a render thread gets a surface created on another thread, see
+  /// [`thread::SURFACE_ON_RENDER_THREAD`].
+  fn new_render_state_for(
+    &self,
+    window: &tao::window::Window,
+    window_width: u32,
+    window_height: u32,
+    thread: std::thread::ThreadId,
+  ) -> napi::Result<RenderState> {
+    // Create new pixels instance with window dimensions
+    let new_pixels = self.create_pixels(window, window_width, window_height)?;
+    Ok(render_state_from_pixels(
+      new_pixels,
+      window_width,
+      window_height,
+      thread,
+    ))
+  }
+
+  /// Creates a pixels instance for a window, verifying it if enabled
+  fn create_pixels<'win>(
+    &self,
+    window: &'win tao::window::Window,
+    window_width: u32,
+    window_height: u32,
+  ) -> napi::Result<pixels::Pixels<'win>> {
+    let build = |attempt: SurfaceAttempt| {
+      let surface_texture = pixels::SurfaceTexture::new(window_width, window_height, window);
+      let builder = pixels::PixelsBuilder::new(window_width, window_height, surface_texture);
+      match attempt {
+        SurfaceAttempt::Default => builder.build(),
+        SurfaceAttempt::Format(format) => builder.surface_texture_format(format).build(),
+        SurfaceAttempt::SoftwareAdapter => builder
+          .request_adapter_options(pixels::wgpu::RequestAdapterOptions {
+            power_preference: pixels::wgpu::PowerPreference::default(),
+            force_fallback_adapter: true,
+            compatible_surface: None,
+          })
+          .build(),
+      }
+    };
+
+    if !self.verify_first_frame {
+      return build(SurfaceAttempt::Default).map_err(|e| {
+        napi::Error::new(
+          napi::Status::GenericFailure,
+          format!("Failed to create pixels: {:?}", e),
+        )
+      });
+    }
+
+    let attempts = [
+      SurfaceAttempt::Default,
+      SurfaceAttempt::Format(pixels::wgpu::TextureFormat::Bgra8Unorm),
+      SurfaceAttempt::Format(pixels::wgpu::TextureFormat::Rgba8Unorm),
+      SurfaceAttempt::SoftwareAdapter,
+    ];
+    let mut failures = Vec::new();
+    for attempt in attempts {
+      match build(attempt) {
+        Ok(mut pixels) => {
+          if verify_surface(&mut pixels) {
+            debug_log!("surface verified with {:?}", attempt);
+            return Ok(pixels);
+          }
+          debug_log!("surface verification failed with {:?}", attempt);
+          failures.push(format!("{:?}: test pattern mismatch", attempt));
+        }
+        Err(e) => failures.push(format!("{:?}: {:?}", attempt, e)),
+      }
+    }
+
+    Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!(
+        "No working render surface configuration found ({})",
+        failures.join("; ")
+      ),
+    ))
   }
 
   /// Render using an already acquired state
@@ -310,14 +1929,15 @@ impl PixelRenderer {
     buffer: &[u8],
     window_width: u32,
     window_height: u32,
-  ) -> napi::Result<()> {
+  ) -> std::result::Result<Option<f64>, pixels::Error> {
     // Apply scaling if needed
-    let (offset_x, offset_y, scaled_width, scaled_height) = calculate_scaled_dimensions(
+    let (offset_x, offset_y, scaled_width, scaled_height) = calculate_scaled_dimensions_aligned(
       self.buffer_width,
       self.buffer_height,
       window_width,
       window_height,
       self.scale_mode,
+      self.offset_alignment,
     );
 
     debug_log!(
@@ -344,9 +1964,15 @@ impl PixelRenderer {
       window_width * window_height * 4
     );
 
-    // Clear with background color first
-    for pixel in frame.chunks_exact_mut(4) {
-      pixel.copy_from_slice(&self.bg_color);
+    // Clear with background color first, unless the content overwrites every pixel
+    if !covers_window(
+      (offset_x, offset_y, scaled_width, scaled_height),
+      window_width,
+      window_height,
+    ) {
+      for pixel in frame.chunks_exact_mut(4) {
+        pixel.copy_from_slice(&self.bg_color);
+      }
     }
 
     // Copy source buffer with scaling
@@ -355,13 +1981,14 @@ impl PixelRenderer {
     match self.scale_mode {
       ScaleMode::Stretch => {
         // Stretch mode: scale entire buffer to fill window
-        scale_buffer_nearest_neighbor(
+        scale_buffer_stretch(
           frame,
           buffer,
           self.buffer_width,
           self.buffer_height,
           window_width,
           window_height,
+          self.filter,
         );
       }
       ScaleMode::None => {
@@ -384,6 +2011,7 @@ impl PixelRenderer {
           self.buffer_height,
           window_width,
           window_height,
+          self.filter,
         );
       }
       _ => {
@@ -400,21 +2028,328 @@ impl PixelRenderer {
             offset_y,
             scaled_width,
             scaled_height,
+            filter: self.filter,
           },
         );
       }
     }
 
+    // Timed on every frame so the rate is right as soon as the overlay is enabled
+    let frame_ms = state.overlay_clock.tick();
+    if self.debug_overlay {
+      let text = overlay::overlay_text(
+        state.overlay_clock.fps(),
+        frame_ms,
+        state.pixels.adapter().get_info().backend.to_str(),
+      );
+      overlay::draw_overlay(
+        state.pixels.frame_mut(),
+        window_width,
+        window_height,
+        &text,
+        self.overlay_corner,
+        self.overlay_color,
+      );
+    }
+
+    if self.wait_for_present && state.pixels.present_mode() != pixels::wgpu::PresentMode::Fifo {
+      state
+        .pixels
+        .set_present_mode(pixels::wgpu::PresentMode::Fifo);
+    }
+
     // Render
-    state.pixels.render().map_err(|e| {
-      napi::Error::new(
-        napi::Status::GenericFailure,
-        format!("Failed to render: {:?}", e),
-      )
-    })?;
+    present_frame(state)?;
+    if self.redraw_on_resize {
+      self.keep_last_frame(state, buffer);
+    } else {
+      state.last_frame = None;
+    }
+
+    if !self.wait_for_present {
+      return Ok(None);
+    }
+    state.pixels.device().poll(pixels::wgpu::Maintain::Wait);
+    let presented_at = std::time::SystemTime::now()
+      .duration_since(std::time::UNIX_EPOCH)
+      .map(|d| d.as_secs_f64() * 1000.0)
+      .unwrap_or(0.0);
+    Ok(Some(presented_at))
+  }
+}
+
+/// Wraps a new pixels instance of a window into its render state
+fn render_state_from_pixels(
+  pixels: pixels::Pixels<'_>,
+  window_width: u32,
+  window_height: u32,
+  thread: std::thread::ThreadId,
+) -> RenderState {
+  // SAFETY: Extending lifetime to 'static is safe because:
+  // 1. The pixels instance is only used while the window is alive
+  // 2. The window ID is unique and won't be reused
+  // 3. We clean up when the window is closed
+  let static_pixels: pixels::Pixels<'static> = unsafe { std::mem::transmute(pixels) };
+
+  RenderState {
+    pixels: static_pixels,
+    last_window_width: window_width,
+    last_window_height: window_height,
+    thread,
+    previous_buffer: None,
+    overlay_clock: clock::FrameClock::new(),
+    last_frame: None,
+    encoded_frame: Vec::new(),
+  }
+}
+
+/// Returns whether a render failure is a transient surface loss
+///
+/// A lost, outdated or timed out surface is fixed by recreating it; running out of
+/// memory or failing to create the surface or device is not.
+fn is_recoverable(error: &pixels::Error) -> bool {
+  matches!(
+    error,
+    pixels::Error::Surface(
+      pixels::wgpu::SurfaceError::Lost
+        | pixels::wgpu::SurfaceError::Outdated
+        | pixels::wgpu::SurfaceError::Timeout
+    )
+  )
+}
+
+/// Encodes a linear channel value in `0.0..=1.0` with the sRGB transfer function
+fn srgb_encode(linear: f64) -> f64 {
+  if linear <= 0.0031308 {
+    linear * 12.92
+  } else {
+    1.055 * linear.powf(1.0 / 2.4) - 0.055
+  }
+}
 
+/// [`srgb_encode`] of every 8-bit channel value
+static SRGB_ENCODE_TABLE: std::sync::LazyLock<[u8; 256]> = std::sync::LazyLock::new(|| {
+  std::array::from_fn(|value| {
+    (srgb_encode(value as f64 / 255.0) * 255.0)
+      .round()
+      .clamp(0.0, 255.0) as u8
+  })
+});
+
+/// Encodes the color channels of an RGBA frame for a linear surface, see [`present_frame`]
+fn encode_frame_for_linear_surface(frame: &[u8], encoded: &mut Vec<u8>) {
+  let table = &*SRGB_ENCODE_TABLE;
+  encoded.clear();
+  encoded.extend(frame.chunks_exact(4).flat_map(|p| {
+    [
+      table[p[0] as usize],
+      table[p[1] as usize],
+      table[p[2] as usize],
+      p[3],
+    ]
+  }));
+}
+
+/// Presents the current frame of a render state
+///
+/// Frames are sampled from an sRGB texture, i.e. decoded to linear values. An sRGB
+/// surface encodes them again, so the frame is shown as is. A linear (non-sRGB)
+/// surface, e.g. one picked by `verify_first_frame`, stores the decoded values and
+/// would show mid-tones too dark, so for those an sRGB-encoded copy of the frame is
+/// uploaded instead. This is the only gamma conversion, for content, background
+/// and `clear` alike, and the CPU-side frame (`render_and_capture`,
+/// `sample_pixel`) keeps the requested colors. Alpha is never converted.
+fn present_frame(state: &mut RenderState) -> std::result::Result<(), pixels::Error> {
+  use pixels::wgpu;
+
+  if state.pixels.render_texture_format().is_srgb() {
+    return state.pixels.render();
+  }
+  encode_frame_for_linear_surface(state.pixels.frame(), &mut state.encoded_frame);
+  let encoded = &state.encoded_frame;
+  state.pixels.render_with(|encoder, target, context| {
+    // Queued after the upload of the plain frame, so this one is sampled
+    context.queue.write_texture(
+      wgpu::ImageCopyTexture {
+        texture: &context.texture,
+        mip_level: 0,
+        origin: wgpu::Origin3d::ZERO,
+        aspect: wgpu::TextureAspect::All,
+      },
+      encoded,
+      wgpu::ImageDataLayout {
+        offset: 0,
+        bytes_per_row: Some(context.texture_extent.width * 4),
+        rows_per_image: Some(context.texture_extent.height),
+      },
+      context.texture_extent,
+    );
+    context.scaling_renderer.render(encoder, target);
     Ok(())
+  })
+}
+
+/// Surface configurations tried in order when `verify_first_frame` is enabled
+#[derive(Debug, Clone, Copy)]
+enum SurfaceAttempt {
+  /// The format pixels picks from the surface capabilities
+  Default,
+  /// An explicit surface format
+  Format(pixels::wgpu::TextureFormat),
+  /// The software adapter (e.g. llvmpipe or WARP)
+  SoftwareAdapter,
+}
+
+/// Renders a test pattern offscreen in the surface format and checks two pixels
+///
+/// The left half of the frame is red and the right half blue. Formats other than
+/// 8-bit RGBA/BGRA cannot be decoded here and are assumed to work.
+fn verify_surface(pixels: &mut pixels::Pixels<'_>) -> bool {
+  use pixels::wgpu;
+
+  let context = pixels.context();
+  let (width, height) = (context.texture_extent.width, context.texture_extent.height);
+  if width < 2 || height == 0 {
+    return true;
+  }
+
+  let frame = pixels.frame_mut();
+  for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
+    let color = if (i as u32 % width) < width / 2 {
+      [255, 0, 0, 255]
+    } else {
+      [0, 0, 255, 255]
+    };
+    pixel.copy_from_slice(&color);
+  }
+
+  let context = pixels.context();
+  context.queue.write_texture(
+    wgpu::ImageCopyTexture {
+      texture: &context.texture,
+      mip_level: 0,
+      origin: wgpu::Origin3d::ZERO,
+      aspect: wgpu::TextureAspect::All,
+    },
+    pixels.frame(),
+    wgpu::ImageDataLayout {
+      offset: 0,
+      bytes_per_row: Some(width * 4),
+      rows_per_image: Some(height),
+    },
+    context.texture_extent,
+  );
+
+  let points = [(width / 4, height / 2), (width * 3 / 4, height / 2)];
+  match read_back_surface(pixels, &points) {
+    Ok(Some(colors)) => {
+      let is_red = |p: [u8; 4]| p[0] > 200 && p[1] < 50 && p[2] < 50;
+      let is_blue = |p: [u8; 4]| p[0] < 50 && p[1] < 50 && p[2] > 200;
+      is_red(colors[0]) && is_blue(colors[1])
+    }
+    Ok(None) => true,
+    Err(()) => false,
+  }
+}
+
+/// Reads back the RGBA colors the surface would show at some frame positions
+///
+/// Draws the uploaded texture through the scaling renderer into an offscreen
+/// target of the surface format, the same pass that presents to the surface, and
+/// copies the requested pixels back. Returns `Ok(None)` for formats other than
+/// 8-bit RGBA/BGRA, which cannot be decoded here, and `Err` if mapping the
+/// readback fails.
+fn read_back_surface(
+  pixels: &pixels::Pixels<'_>,
+  points: &[(u32, u32)],
+) -> std::result::Result<Option<Vec<[u8; 4]>>, ()> {
+  use pixels::wgpu;
+
+  let format = pixels.render_texture_format();
+  let bgra = match format {
+    wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+    wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+    _ => return Ok(None),
+  };
+
+  let context = pixels.context();
+  let target = context.device.create_texture(&wgpu::TextureDescriptor {
+    label: Some("read_back_surface_target"),
+    size: context.texture_extent,
+    mip_level_count: 1,
+    sample_count: 1,
+    dimension: wgpu::TextureDimension::D2,
+    format,
+    usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+    view_formats: &[],
+  });
+  let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+  // One 1x1 copy per point, each on its own 256-byte aligned row
+  let row = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64;
+  let readback = context.device.create_buffer(&wgpu::BufferDescriptor {
+    label: Some("read_back_surface_buffer"),
+    size: row * points.len().max(1) as u64,
+    usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+    mapped_at_creation: false,
+  });
+
+  let mut encoder = context
+    .device
+    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+      label: Some("read_back_surface_encoder"),
+    });
+  context.scaling_renderer.render(&mut encoder, &view);
+  for (i, &(x, y)) in points.iter().enumerate() {
+    encoder.copy_texture_to_buffer(
+      wgpu::ImageCopyTexture {
+        texture: &target,
+        mip_level: 0,
+        origin: wgpu::Origin3d { x, y, z: 0 },
+        aspect: wgpu::TextureAspect::All,
+      },
+      wgpu::ImageCopyBuffer {
+        buffer: &readback,
+        layout: wgpu::ImageDataLayout {
+          offset: row * i as u64,
+          bytes_per_row: Some(row as u32),
+          rows_per_image: Some(1),
+        },
+      },
+      wgpu::Extent3d {
+        width: 1,
+        height: 1,
+        depth_or_array_layers: 1,
+      },
+    );
+  }
+  context.queue.submit(Some(encoder.finish()));
+
+  let slice = readback.slice(..);
+  let (sender, receiver) = std::sync::mpsc::channel();
+  slice.map_async(wgpu::MapMode::Read, move |result| {
+    let _ = sender.send(result.is_ok());
+  });
+  context.device.poll(wgpu::Maintain::Wait);
+  if receiver.recv() != Ok(true) {
+    return Err(());
   }
+
+  let data = slice.get_mapped_range();
+  let colors = (0..points.len())
+    .map(|i| {
+      let offset = row as usize * i;
+      let p = &data[offset..offset + 4];
+      if bgra {
+        [p[2], p[1], p[0], p[3]]
+      } else {
+        [p[0], p[1], p[2], p[3]]
+      }
+    })
+    .collect();
+  drop(data);
+  readback.unmap();
+  Ok(Some(colors))
 }
 
 /// Simple function to render a pixel buffer to a window
@@ -433,33 +2368,245 @@ pub fn render_pixels(
   buffer_height: u32,
 ) -> napi::Result<()> {
   let renderer = PixelRenderer::new(buffer_width, buffer_height);
-  renderer.render(window, buffer)
+  renderer.render(window, buffer).map(|_| ())
+}
+
+/// Guesses the byte layout of a `width` x `height` pixel buffer
+///
+/// A development aid for "colors are swapped" and "the window stays blank" bugs:
+/// samples the buffer and reports where its alpha byte seems to be, or `null` when
+/// the content doesn't tell (e.g. translucent or single-color buffers). Anything
+/// but `Rgba` means the buffer doesn't match what the renderer expects; renderers
+/// also log a warning the first time they are given such a buffer.
+#[napi]
+pub fn debug_detect_channel_order(
+  buffer: Buffer,
+  width: u32,
+  height: u32,
+) -> napi::Result<Option<PixelFormat>> {
+  let buffer = buffer_prefix(&buffer, width, height, false)?;
+  Ok(buffer_ops::detect_channel_order(buffer))
+}
+
+/// Gets the smallest window that shows a buffer whole with `ScaleMode::Integer`
+///
+/// Integer scaling never goes below 1x, so a window smaller than the buffer crops
+/// the content instead of shrinking it. The result is the inner size in physical
+/// pixels, i.e. the buffer size; `PixelRenderer.applyIntegerMinSize` sets it as
+/// the window's minimum.
+#[napi]
+pub fn min_window_size_for_integer(
+  buffer_width: u32,
+  buffer_height: u32,
+) -> crate::tao::structs::Size {
+  crate::tao::structs::Size {
+    width: buffer_width.max(1) as f64,
+    height: buffer_height.max(1) as f64,
+  }
+}
+
+/// Computes the letterbox bars a renderer leaves around the content
+///
+/// Returns the rectangles, in physical window pixels, that show the background
+/// color when a `buffer_width` x `buffer_height` buffer is rendered to a window of
+/// `window_width` x `window_height` with `scale_mode`: none when the content
+/// covers the window, two for letterboxing or pillarboxing, and up to four for
+/// `Integer` and `None`. Pass the renderer's `offsetAlignment` if it sets one.
+#[napi]
+pub fn letterbox_rects(
+  buffer_width: u32,
+  buffer_height: u32,
+  window_width: u32,
+  window_height: u32,
+  scale_mode: ScaleMode,
+  offset_alignment: Option<u32>,
+) -> Vec<crate::tao::structs::Rectangle> {
+  let layout = calculate_scaled_dimensions_aligned(
+    buffer_width,
+    buffer_height,
+    window_width,
+    window_height,
+    scale_mode,
+    offset_alignment.unwrap_or(1),
+  );
+  letterbox_bars(layout, window_width, window_height)
+    .into_iter()
+    .map(|(x, y, width, height)| crate::tao::structs::Rectangle {
+      origin: crate::tao::structs::Position {
+        x: x as f64,
+        y: y as f64,
+      },
+      size: crate::tao::structs::Size {
+        width: width as f64,
+        height: height as f64,
+      },
+    })
+    .collect()
+}
+
+/// Clipboard handle shared by all copies
+///
+/// On X11 `arboard` serves the copied data from a thread that stops once the
+/// last handle is dropped, so one handle stays open for the whole process.
+static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+/// Places an RGBA8 pixel buffer on the system clipboard as an image
+///
+/// The buffer must contain exactly `width * height * 4` bytes of straight
+/// (non-premultiplied) RGBA data, the same layout accepted by [`PixelRenderer`].
+/// Conversion to the platform image format (CF_DIBV5 on Windows, TIFF/PNG on
+/// macOS, PNG on X11/Wayland) is done by the clipboard backend.
+///
+/// # Platform support
+/// - **Windows / macOS**: Supported. The system keeps a copy of the image.
+/// - **Linux (X11/Wayland)**: Supported through X11 (XWayland on Wayland). The
+///   image is served by this process, from a clipboard kept open until the
+///   process exits, so it stays available after this returns until another
+///   application takes the clipboard. Once the process exits it is only kept
+///   if a clipboard manager took it over.
+#[napi]
+pub fn copy_rgba_to_clipboard(buffer: Buffer, width: u32, height: u32) -> napi::Result<()> {
+  let expected = width as usize * height as usize * 4;
+  if width == 0 || height == 0 || buffer.len() != expected {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!(
+        "Buffer size mismatch: expected {} bytes for {}x{} RGBA image, got {}",
+        expected,
+        width,
+        height,
+        buffer.len()
+      ),
+    ));
+  }
+
+  let mut clipboard = CLIPBOARD.lock().unwrap();
+  if clipboard.is_none() {
+    *clipboard = Some(arboard::Clipboard::new().map_err(|e| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to open clipboard: {}", e),
+      )
+    })?);
+  }
+
+  clipboard
+    .as_mut()
+    .unwrap()
+    .set_image(arboard::ImageData {
+      width: width as usize,
+      height: height as usize,
+      bytes: std::borrow::Cow::Borrowed(&buffer),
+    })
+    .map_err(|e| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to copy image to clipboard: {}", e),
+      )
+    })
 }
 
 pub mod buffer_ops;
+pub mod clock;
+pub mod compositor;
+pub mod deterministic;
+mod overlay;
 pub mod scaling;
+pub mod thread;
+
+/// Samples a source region of a buffer mapped onto a destination area
+struct ScaleSampler<'a> {
+  buffer: &'a [u8],
+  buffer_width: u32,
+  src_x: u32,
+  src_y: u32,
+  src_width: u32,
+  src_height: u32,
+  dst_width: u32,
+  dst_height: u32,
+  filter: ScaleFilter,
+}
+
+impl ScaleSampler<'_> {
+  /// Returns the color for destination pixel (x, y), or None if out of bounds
+  fn sample(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+    let scale_x = self.src_width as f32 / self.dst_width as f32;
+    let scale_y = self.src_height as f32 / self.dst_height as f32;
+    let max_x = self.src_width as f32 - 1.0;
+    let max_y = self.src_height as f32 - 1.0;
+
+    match self.filter {
+      ScaleFilter::Nearest => {
+        let src_x = self.src_x + (x as f32 * scale_x).min(max_x) as u32;
+        let src_y = self.src_y + (y as f32 * scale_y).min(max_y) as u32;
+        self.pixel(src_x, src_y)
+      }
+      ScaleFilter::Bilinear => {
+        // Sample at pixel centers so edges are not shifted
+        let fx = ((x as f32 + 0.5) * scale_x - 0.5).clamp(0.0, max_x.max(0.0));
+        let fy = ((y as f32 + 0.5) * scale_y - 0.5).clamp(0.0, max_y.max(0.0));
+        let x0 = fx as u32;
+        let y0 = fy as u32;
+        let x1 = (x0 + 1).min(self.src_width - 1);
+        let y1 = (y0 + 1).min(self.src_height - 1);
+        let tx = fx - x0 as f32;
+        let ty = fy - y0 as f32;
+
+        let p00 = self.pixel(self.src_x + x0, self.src_y + y0)?;
+        let p10 = self.pixel(self.src_x + x1, self.src_y + y0)?;
+        let p01 = self.pixel(self.src_x + x0, self.src_y + y1)?;
+        let p11 = self.pixel(self.src_x + x1, self.src_y + y1)?;
+
+        let mut out = [0u8; 4];
+        for c in 0..4 {
+          let top = p00[c] as f32 + (p10[c] as f32 - p00[c] as f32) * tx;
+          let bottom = p01[c] as f32 + (p11[c] as f32 - p01[c] as f32) * tx;
+          out[c] = (top + (bottom - top) * ty).round() as u8;
+        }
+        Some(out)
+      }
+    }
+  }
+
+  fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+    let idx = ((y * self.buffer_width + x) * 4) as usize;
+    self
+      .buffer
+      .get(idx..idx + 4)
+      .map(|p| [p[0], p[1], p[2], p[3]])
+  }
+}
 
-/// Scales buffer to fill the entire window using nearest neighbor
-fn scale_buffer_nearest_neighbor(
+/// Scales buffer to fill the entire window, ignoring aspect ratio
+fn scale_buffer_stretch(
   frame: &mut [u8],
   buffer: &[u8],
   buffer_width: u32,
   buffer_height: u32,
   window_width: u32,
   window_height: u32,
+  filter: ScaleFilter,
 ) {
+  let sampler = ScaleSampler {
+    buffer,
+    buffer_width,
+    src_x: 0,
+    src_y: 0,
+    src_width: buffer_width,
+    src_height: buffer_height,
+    dst_width: window_width,
+    dst_height: window_height,
+    filter,
+  };
+
   for y in 0..window_height {
     for x in 0..window_width {
-      let src_x = (x as f32 * buffer_width as f32 / window_width as f32)
-        .min(buffer_width as f32 - 1.0) as u32;
-      let src_y = (y as f32 * buffer_height as f32 / window_height as f32)
-        .min(buffer_height as f32 - 1.0) as u32;
-
-      let src_idx = ((src_y * buffer_width + src_x) * 4) as usize;
       let dst_idx = ((y * window_width + x) * 4) as usize;
 
-      if src_idx + 4 <= buffer.len() && dst_idx + 4 <= frame.len() {
-        frame[dst_idx..dst_idx + 4].copy_from_slice(&buffer[src_idx..src_idx + 4]);
+      if let Some(color) = sampler.sample(x, y) {
+        if dst_idx + 4 <= frame.len() {
+          frame[dst_idx..dst_idx + 4].copy_from_slice(&color);
+        }
       }
     }
   }
@@ -506,6 +2653,7 @@ fn scale_buffer_fill(
   buffer_height: u32,
   window_width: u32,
   window_height: u32,
+  filter: ScaleFilter,
 ) {
   let buffer_aspect = buffer_width as f32 / buffer_height as f32;
   let window_aspect = window_width as f32 / window_height as f32;
@@ -523,19 +2671,26 @@ fn scale_buffer_fill(
     )
   };
 
+  let sampler = ScaleSampler {
+    buffer,
+    buffer_width,
+    src_x: crop_x,
+    src_y: crop_y,
+    src_width: crop_width,
+    src_height: crop_height,
+    dst_width: window_width,
+    dst_height: window_height,
+    filter,
+  };
+
   for y in 0..window_height {
     for x in 0..window_width {
-      let src_x = crop_x
-        + (x as f32 * crop_width as f32 / window_width as f32).min(crop_width as f32 - 1.0) as u32;
-      let src_y = crop_y
-        + (y as f32 * crop_height as f32 / window_height as f32).min(crop_height as f32 - 1.0)
-          as u32;
-
-      let src_idx = ((src_y * buffer_width + src_x) * 4) as usize;
       let dst_idx = ((y * window_width + x) * 4) as usize;
 
-      if src_idx + 4 <= buffer.len() && dst_idx + 4 <= frame.len() {
-        frame[dst_idx..dst_idx + 4].copy_from_slice(&buffer[src_idx..src_idx + 4]);
+      if let Some(color) = sampler.sample(x, y) {
+        if dst_idx + 4 <= frame.len() {
+          frame[dst_idx..dst_idx + 4].copy_from_slice(&color);
+        }
       }
     }
   }
@@ -551,6 +2706,7 @@ struct ScaleBufferFitParams {
   offset_y: u32,
   scaled_width: u32,
   scaled_height: u32,
+  filter: ScaleFilter,
 }
 
 /// Scales buffer to fit window, maintaining aspect ratio with letterboxing
@@ -564,28 +2720,676 @@ fn scale_buffer_fit(frame: &mut [u8], buffer: &[u8], params: ScaleBufferFitParam
     offset_y,
     scaled_width,
     scaled_height,
+    filter,
   } = params;
 
   // Frame is already cleared with background color
 
+  let sampler = ScaleSampler {
+    buffer,
+    buffer_width,
+    src_x: 0,
+    src_y: 0,
+    src_width: buffer_width,
+    src_height: buffer_height,
+    dst_width: scaled_width,
+    dst_height: scaled_height,
+    filter,
+  };
+
   for y in 0..scaled_height {
     for x in 0..scaled_width {
-      let src_x = (x as f32 * buffer_width as f32 / scaled_width as f32)
-        .min(buffer_width as f32 - 1.0) as u32;
-      let src_y = (y as f32 * buffer_height as f32 / scaled_height as f32)
-        .min(buffer_height as f32 - 1.0) as u32;
-
       let dst_x = offset_x + x;
       let dst_y = offset_y + y;
 
       if dst_x < window_width && dst_y < window_height {
-        let src_idx = ((src_y * buffer_width + src_x) * 4) as usize;
         let dst_idx = ((dst_y * window_width + dst_x) * 4) as usize;
 
-        if src_idx + 4 <= buffer.len() && dst_idx + 4 <= frame.len() {
-          frame[dst_idx..dst_idx + 4].copy_from_slice(&buffer[src_idx..src_idx + 4]);
+        if let Some(color) = sampler.sample(x, y) {
+          if dst_idx + 4 <= frame.len() {
+            frame[dst_idx..dst_idx + 4].copy_from_slice(&color);
+          }
         }
       }
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_render_cache_keeps_one_state_per_window() {
+    let cache = RenderCache::new();
+    let first = cache.insert(1, 10u32);
+    cache.insert(2, 20);
+    assert_eq!(cache.len(), 2);
+
+    // A recreated surface replaces the window's entry
+    cache.insert(1, 11);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(*cache.get(1).unwrap().lock().unwrap(), 11);
+    assert_eq!(*first.lock().unwrap(), 10);
+
+    assert!(cache.remove(1));
+    assert!(!cache.remove(1));
+    assert!(cache.get(1).is_none());
+    assert_eq!(cache.len(), 1);
+  }
+
+  #[test]
+  fn test_surface_loss_is_recoverable() {
+    use pixels::wgpu::SurfaceError;
+
+    assert!(is_recoverable(&pixels::Error::Surface(SurfaceError::Lost)));
+    assert!(is_recoverable(&pixels::Error::Surface(
+      SurfaceError::Outdated
+    )));
+    assert!(is_recoverable(&pixels::Error::Surface(
+      SurfaceError::Timeout
+    )));
+    assert!(!is_recoverable(&pixels::Error::Surface(
+      SurfaceError::OutOfMemory
+    )));
+    assert!(!is_recoverable(&pixels::Error::AdapterNotFound));
+  }
+
+  #[test]
+  fn test_stretch_filters() {
+    // 2x1 black/white buffer stretched to 4x1
+    let buffer = [0, 0, 0, 255, 255, 255, 255, 255];
+    let mut nearest = vec![0u8; 16];
+    scale_buffer_stretch(&mut nearest, &buffer, 2, 1, 4, 1, ScaleFilter::Nearest);
+    let reds: Vec<u8> = nearest.chunks(4).map(|p| p[0]).collect();
+    assert_eq!(reds, vec![0, 0, 255, 255]);
+
+    let mut bilinear = vec![0u8; 16];
+    scale_buffer_stretch(&mut bilinear, &buffer, 2, 1, 4, 1, ScaleFilter::Bilinear);
+    let reds: Vec<u8> = bilinear.chunks(4).map(|p| p[0]).collect();
+    assert_eq!(reds, vec![0, 64, 191, 255]);
+    assert!(bilinear.chunks(4).all(|p| p[3] == 255));
+  }
+
+  #[test]
+  fn test_bilinear_upscale_of_2x2_gradient() {
+    // Red grows to the right, green downwards, blue only in the bottom-right corner
+    let buffer = [
+      0, 0, 0, 255, 255, 0, 0, 255, //
+      0, 255, 0, 255, 255, 255, 255, 255,
+    ];
+    let mut frame = vec![0u8; 4 * 4 * 4];
+    scale_buffer_stretch(&mut frame, &buffer, 2, 2, 4, 4, ScaleFilter::Bilinear);
+    let channel = |c: usize| -> Vec<Vec<u8>> {
+      frame
+        .chunks(4 * 4)
+        .map(|row| row.chunks(4).map(|p| p[c]).collect())
+        .collect()
+    };
+
+    // Sampling at pixel centers clamps the outer half pixel to the edge values
+    let ramp = vec![0, 64, 191, 255];
+    assert!(channel(0).iter().all(|row| *row == ramp));
+    for (y, row) in channel(1).iter().enumerate() {
+      assert!(row.iter().all(|&g| g == ramp[y]));
+    }
+    assert_eq!(
+      channel(2),
+      vec![
+        vec![0, 0, 0, 0],
+        vec![0, 16, 48, 64],
+        vec![0, 48, 143, 191],
+        vec![0, 64, 191, 255],
+      ]
+    );
+    assert!(frame.chunks(4).all(|p| p[3] == 255));
+  }
+
+  /// Color of source pixel (x, y) in [`gradient`]
+  fn gradient_pixel(x: u32, y: u32) -> [u8; 4] {
+    [(x * 60) as u8, (y * 60) as u8, 128, 255]
+  }
+
+  /// A `width` x `height` buffer whose every pixel is distinct
+  fn gradient(width: u32, height: u32) -> Vec<u8> {
+    (0..height)
+      .flat_map(|y| (0..width).flat_map(move |x| gradient_pixel(x, y)))
+      .collect()
+  }
+
+  /// Builds the expected frame from the source pixel of each frame pixel, if any
+  fn expected_frame(
+    width: u32,
+    height: u32,
+    source: impl Fn(u32, u32) -> Option<(u32, u32)>,
+  ) -> Vec<u8> {
+    (0..height)
+      .flat_map(|y| (0..width).map(move |x| (x, y)))
+      .flat_map(|(x, y)| source(x, y).map_or([0; 4], |(sx, sy)| gradient_pixel(sx, sy)))
+      .collect()
+  }
+
+  #[test]
+  fn test_stretch_nearest_output() {
+    // 2x2 to 3x2: columns map to 0, 0 and 1
+    let mut frame = vec![0u8; 3 * 2 * 4];
+    scale_buffer_stretch(
+      &mut frame,
+      &gradient(2, 2),
+      2,
+      2,
+      3,
+      2,
+      ScaleFilter::Nearest,
+    );
+    let columns = [0, 0, 1];
+    assert_eq!(
+      frame,
+      expected_frame(3, 2, |x, y| Some((columns[x as usize], y)))
+    );
+
+    // 4x4 to 2x2 keeps every other pixel
+    let mut frame = vec![0u8; 2 * 2 * 4];
+    scale_buffer_stretch(
+      &mut frame,
+      &gradient(4, 4),
+      4,
+      4,
+      2,
+      2,
+      ScaleFilter::Nearest,
+    );
+    assert_eq!(frame, expected_frame(2, 2, |x, y| Some((x * 2, y * 2))));
+  }
+
+  #[test]
+  fn test_fit_output_is_letterboxed() {
+    // 2x2 doubled to 4x4 and centered in 6x4, leaving a column on each side
+    let mut frame = vec![0u8; 6 * 4 * 4];
+    scale_buffer_fit(
+      &mut frame,
+      &gradient(2, 2),
+      ScaleBufferFitParams {
+        buffer_width: 2,
+        buffer_height: 2,
+        window_width: 6,
+        window_height: 4,
+        offset_x: 1,
+        offset_y: 0,
+        scaled_width: 4,
+        scaled_height: 4,
+        filter: ScaleFilter::Nearest,
+      },
+    );
+    let expected = expected_frame(6, 4, |x, y| {
+      (1..5).contains(&x).then(|| ((x - 1) / 2, y / 2))
+    });
+    assert_eq!(frame, expected);
+
+    // 4x2 halved to 2x1 and centered vertically in 2x3
+    let mut frame = vec![0u8; 2 * 3 * 4];
+    scale_buffer_fit(
+      &mut frame,
+      &gradient(4, 2),
+      ScaleBufferFitParams {
+        buffer_width: 4,
+        buffer_height: 2,
+        window_width: 2,
+        window_height: 3,
+        offset_x: 0,
+        offset_y: 1,
+        scaled_width: 2,
+        scaled_height: 1,
+        filter: ScaleFilter::Nearest,
+      },
+    );
+    assert_eq!(
+      frame,
+      expected_frame(2, 3, |x, y| (y == 1).then_some((x * 2, 0)))
+    );
+  }
+
+  #[test]
+  fn test_fill_output_is_cropped() {
+    // 4x2 into a square window keeps the middle 2x2
+    let mut frame = vec![0u8; 2 * 2 * 4];
+    scale_buffer_fill(
+      &mut frame,
+      &gradient(4, 2),
+      4,
+      2,
+      2,
+      2,
+      ScaleFilter::Nearest,
+    );
+    assert_eq!(frame, expected_frame(2, 2, |x, y| Some((x + 1, y))));
+
+    // The same crop doubled into 4x4
+    let mut frame = vec![0u8; 4 * 4 * 4];
+    scale_buffer_fill(
+      &mut frame,
+      &gradient(4, 2),
+      4,
+      2,
+      4,
+      4,
+      ScaleFilter::Nearest,
+    );
+    assert_eq!(frame, expected_frame(4, 4, |x, y| Some((x / 2 + 1, y / 2))));
+
+    // 2x4 into a square window keeps the middle rows
+    let mut frame = vec![0u8; 2 * 2 * 4];
+    scale_buffer_fill(
+      &mut frame,
+      &gradient(2, 4),
+      2,
+      4,
+      2,
+      2,
+      ScaleFilter::Nearest,
+    );
+    assert_eq!(frame, expected_frame(2, 2, |x, y| Some((x, y + 1))));
+  }
+
+  #[test]
+  fn test_centered_crop_output() {
+    // A larger buffer is cropped to its center
+    let mut frame = vec![0u8; 2 * 2 * 4];
+    copy_buffer_centered_crop(&mut frame, &gradient(4, 4), 4, 4, 2, 2);
+    assert_eq!(frame, expected_frame(2, 2, |x, y| Some((x + 1, y + 1))));
+
+    // A smaller buffer is centered and the rest of the frame left as is
+    let mut frame = vec![0u8; 4 * 4 * 4];
+    copy_buffer_centered_crop(&mut frame, &gradient(2, 2), 2, 2, 4, 4);
+    let expected = expected_frame(4, 4, |x, y| {
+      ((1..3).contains(&x) && (1..3).contains(&y)).then(|| (x - 1, y - 1))
+    });
+    assert_eq!(frame, expected);
+
+    // Wider than the window but shorter: cropped horizontally, centered vertically
+    let mut frame = vec![0u8; 2 * 4 * 4];
+    copy_buffer_centered_crop(&mut frame, &gradient(4, 2), 4, 2, 2, 4);
+    let expected = expected_frame(2, 4, |x, y| (1..3).contains(&y).then(|| (x + 1, y - 1)));
+    assert_eq!(frame, expected);
+  }
+
+  #[test]
+  fn test_options_round_trip() {
+    let mut renderer = PixelRenderer::new(320, 180);
+    renderer.set_scale_mode(ScaleMode::Integer);
+    renderer.set_background_color(10, 20, 30, 40);
+    renderer.set_filter(ScaleFilter::Bilinear);
+    renderer.set_offset_alignment(2);
+    renderer.set_redraw_on_resize(true);
+
+    let restored = PixelRenderer::with_options(renderer.options());
+    assert_eq!((restored.buffer_width, restored.buffer_height), (320, 180));
+    assert!(matches!(restored.scale_mode, ScaleMode::Integer));
+    assert_eq!(restored.bg_color, [10, 20, 30, 40]);
+    assert_eq!(restored.filter, ScaleFilter::Bilinear);
+    assert_eq!(restored.offset_alignment, 2);
+    assert!(restored.redraw_on_resize);
+    assert!(!restored.interpolate);
+    assert!(restored.use_cache);
+
+    renderer.set_use_cache(false);
+    assert!(!PixelRenderer::with_options(renderer.options()).use_cache);
+  }
+
+  #[test]
+  fn test_integer_scale_is_1x_at_min_window_size() {
+    let size = min_window_size_for_integer(320, 180);
+    let (width, height) = (size.width as u32, size.height as u32);
+    let (offset_x, offset_y, scaled_width, scaled_height) =
+      calculate_scaled_dimensions_aligned(320, 180, width, height, ScaleMode::Integer, 1);
+    assert_eq!(
+      (offset_x, offset_y, scaled_width, scaled_height),
+      (0, 0, 320, 180)
+    );
+    // One pixel less and the content no longer fits
+    let (_, _, scaled_width, _) =
+      calculate_scaled_dimensions_aligned(320, 180, width - 1, height, ScaleMode::Integer, 1);
+    assert!(scaled_width > width - 1);
+  }
+
+  #[test]
+  fn test_buffer_prefix() {
+    let buffer = vec![7u8; 2 * 2 * 4 + 5];
+    // Strict by default
+    assert!(buffer_prefix(&buffer, 2, 2, false).is_err());
+    assert_eq!(buffer_prefix(&buffer, 2, 2, true).unwrap().len(), 16);
+    assert_eq!(buffer_prefix(&buffer[..16], 2, 2, false).unwrap().len(), 16);
+    // Too short is always an error
+    assert!(buffer_prefix(&buffer[..15], 2, 2, true).is_err());
+  }
+
+  #[test]
+  fn test_decoded_files_evict_least_recently_used() {
+    let start = std::time::Instant::now();
+    let file = |bytes: usize, age: u64| DecodedFile {
+      modified: None,
+      len: bytes as u64,
+      width: 1,
+      height: 1,
+      rgba: std::sync::Arc::new(vec![0; bytes]),
+      last_used: start + std::time::Duration::from_secs(age),
+    };
+    let mut files = std::collections::HashMap::new();
+    files.insert(std::path::PathBuf::from("a"), file(40, 2));
+    files.insert(std::path::PathBuf::from("b"), file(40, 1));
+    files.insert(std::path::PathBuf::from("c"), file(40, 0));
+
+    evict_decoded_files(&mut files, std::path::Path::new("c"), 80);
+    assert!(!files.contains_key(std::path::Path::new("b")));
+    assert_eq!(files.len(), 2);
+
+    // The image being rendered stays even when it exceeds the budget on its own
+    evict_decoded_files(&mut files, std::path::Path::new("c"), 10);
+    assert_eq!(files.len(), 1);
+    assert!(files.contains_key(std::path::Path::new("c")));
+  }
+
+  #[test]
+  fn test_clear_color_matches_on_every_surface_kind() {
+    fn decode(value: u8) -> f64 {
+      let value = value as f64 / 255.0;
+      if value <= 0.04045 {
+        value / 12.92
+      } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+      }
+    }
+
+    let gray = [128, 128, 128, 255];
+    let mut encoded = Vec::new();
+    encode_frame_for_linear_surface(&gray, &mut encoded);
+    for (uploaded, surface_is_srgb) in [(&gray[..], true), (&encoded[..], false)] {
+      // Sampling the sRGB texture decodes; an sRGB surface encodes on write
+      let sampled = decode(uploaded[0]);
+      let stored = if surface_is_srgb {
+        srgb_encode(sampled)
+      } else {
+        sampled
+      };
+      let displayed = (stored * 255.0).round();
+      assert!(
+        (displayed - 128.0).abs() <= 1.0,
+        "srgb={} displayed {}",
+        surface_is_srgb,
+        displayed
+      );
+      assert_eq!(uploaded[3], 255);
+    }
+  }
+
+  #[test]
+  #[ignore = "requires a display and a GPU adapter"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_clear_color_reads_back_on_every_backend() {
+    use pixels::wgpu::{Backends, TextureFormat};
+
+    const GRAY: [u8; 4] = [128, 128, 128, 255];
+
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let tao_window = tao::window::WindowBuilder::new()
+        .with_inner_size(tao::dpi::PhysicalSize::new(64, 64))
+        .with_resizable(false)
+        .build(event_loop)
+        .expect("Failed to create window");
+
+      // A 1x1 buffer stretched over the frame, as `clear` renders it
+      let mut renderer = PixelRenderer::new(1, 1);
+      renderer.set_scale_mode(ScaleMode::Stretch);
+      let mut tested = Vec::new();
+      for backend in [
+        Backends::VULKAN,
+        Backends::GL,
+        Backends::DX12,
+        Backends::METAL,
+      ] {
+        // The default (usually sRGB) format and the linear fallbacks of `verifyFirstFrame`
+        for format in [
+          None,
+          Some(TextureFormat::Bgra8Unorm),
+          Some(TextureFormat::Rgba8Unorm),
+        ] {
+          let surface_texture = pixels::SurfaceTexture::new(64, 64, &tao_window);
+          let mut builder =
+            pixels::PixelsBuilder::new(64, 64, surface_texture).wgpu_backend(backend);
+          if let Some(format) = format {
+            builder = builder.surface_texture_format(format);
+          }
+          let Ok(pixels) = builder.build() else {
+            continue;
+          };
+          let mut state = render_state_from_pixels(pixels, 64, 64, std::thread::current().id());
+          renderer
+            .render_with_state(&mut state, &GRAY, 64, 64)
+            .unwrap();
+          let surface_format = state.pixels.render_texture_format();
+          let Some(colors) = read_back_surface(&state.pixels, &[(32, 32)]).unwrap() else {
+            continue;
+          };
+          for channel in 0..3 {
+            assert!(
+              colors[0][channel].abs_diff(GRAY[channel]) <= 2,
+              "{:?} with {:?} shows {:?}",
+              backend,
+              surface_format,
+              colors[0]
+            );
+          }
+          tested.push((backend, surface_format));
+        }
+      }
+      assert!(!tested.is_empty(), "no backend could create a surface");
+    });
+  }
+
+  #[test]
+  #[ignore = "requires a display and a GPU adapter"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_render_end_to_end_per_scale_mode() {
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const GREEN: [u8; 4] = [0, 255, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+    const BG: [u8; 4] = [0, 0, 0, 255];
+    /// Expected color at a physical frame position
+    type Probe = (u32, u32, [u8; 4]);
+
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let tao_window = tao::window::WindowBuilder::new()
+        .with_inner_size(tao::dpi::PhysicalSize::new(64, 32))
+        .with_resizable(false)
+        .build(event_loop)
+        .expect("Failed to create window");
+      let size = tao_window.inner_size();
+      assert_eq!(
+        (size.width, size.height),
+        (64, 32),
+        "window manager resized the window"
+      );
+      let window = crate::tao::structs::Window {
+        inner: Some(Arc::new(std::sync::Mutex::new(tao_window))),
+      };
+
+      // 4x4 buffer with a 2x2 red, green, blue and white quadrant each
+      let buffer: Vec<u8> = (0..16)
+        .flat_map(|i| match (i % 4 < 2, i / 4 < 2) {
+          (true, true) => RED,
+          (false, true) => GREEN,
+          (true, false) => BLUE,
+          (false, false) => WHITE,
+        })
+        .collect();
+
+      let full = [
+        (16, 8, RED),
+        (48, 8, GREEN),
+        (16, 24, BLUE),
+        (48, 24, WHITE),
+      ];
+      let pillarboxed = [
+        (4, 16, BG),
+        (24, 8, RED),
+        (40, 8, GREEN),
+        (24, 24, BLUE),
+        (40, 24, WHITE),
+      ];
+      let centered = [
+        (0, 0, BG),
+        (30, 14, RED),
+        (33, 14, GREEN),
+        (30, 17, BLUE),
+        (33, 17, WHITE),
+      ];
+      let cases: [(ScaleMode, &[Probe]); 5] = [
+        (ScaleMode::Stretch, &full),
+        (ScaleMode::Fill, &full),
+        (ScaleMode::Fit, &pillarboxed),
+        (ScaleMode::Integer, &pillarboxed),
+        (ScaleMode::None, &centered),
+      ];
+      let key = render_state_key(&window.inner.as_ref().unwrap().lock().unwrap());
+      for (mode, expected) in cases {
+        let mut renderer = PixelRenderer::new(4, 4);
+        renderer.set_scale_mode(mode);
+        renderer.render_bytes(&window, &buffer).unwrap();
+
+        // Read back what the surface was drawn with, not the CPU composite
+        let state = render_state(key).expect("window has no render state");
+        let state = state.lock().unwrap();
+        let points: Vec<(u32, u32)> = expected.iter().map(|&(x, y, _)| (x, y)).collect();
+        let Some(colors) = read_back_surface(&state.pixels, &points).unwrap() else {
+          panic!(
+            "cannot read back {:?} surfaces",
+            state.pixels.render_texture_format()
+          );
+        };
+        for (&(x, y, color), actual) in expected.iter().zip(colors) {
+          assert_eq!(actual, color, "{:?} at ({}, {})", mode, x, y);
+        }
+      }
+    });
+  }
+
+  #[test]
+  #[ignore = "requires a display and a GPU adapter"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_dropping_windows_clears_render_cache() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let renderer = PixelRenderer::new(4, 4);
+      let buffer = vec![255u8; 4 * 4 * 4];
+
+      for _ in 0..32 {
+        let window = crate::tao::test_display::build_window(
+          event_loop,
+          tao::window::WindowBuilder::new().with_inner_size(tao::dpi::PhysicalSize::new(64, 64)),
+        );
+        renderer.render_bytes(&window, &buffer).unwrap();
+        assert_eq!(render_cache_size(), 1);
+        drop(window);
+        assert_eq!(render_cache_size(), 0);
+      }
+    });
+  }
+
+  #[test]
+  #[ignore = "requires a display and a GPU adapter"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_free_render_state_releases_surface() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let tao_window = tao::window::WindowBuilder::new()
+        .with_inner_size(tao::dpi::PhysicalSize::new(64, 64))
+        .build(event_loop)
+        .expect("Failed to create window");
+      let window_id = tao_window.id();
+      let key = render_state_key(&tao_window);
+      let window = crate::tao::structs::Window {
+        inner: Some(Arc::new(std::sync::Mutex::new(tao_window))),
+      };
+      let cached = || render_state(key).is_some();
+
+      let renderer = PixelRenderer::new(4, 4);
+      renderer.render_bytes(&window, &[255u8; 4 * 4 * 4]).unwrap();
+      assert!(cached());
+      assert!(free_render_state(&window));
+      assert!(!cached());
+      assert!(!free_render_state(&window));
+
+      renderer.render_bytes(&window, &[255u8; 4 * 4 * 4]).unwrap();
+      release_destroyed_window(window_id, &tao::event::WindowEvent::Destroyed);
+      assert!(!cached());
+    });
+  }
+
+  #[test]
+  #[ignore = "requires a display and a GPU adapter"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_uncached_render_leaves_no_surface() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let tao_window = tao::window::WindowBuilder::new()
+        .with_inner_size(tao::dpi::PhysicalSize::new(64, 64))
+        .build(event_loop)
+        .expect("Failed to create window");
+      let key = render_state_key(&tao_window);
+      let window = crate::tao::structs::Window {
+        inner: Some(Arc::new(std::sync::Mutex::new(tao_window))),
+      };
+      let cached = || render_state(key).is_some();
+      let frame = [255u8; 4 * 4 * 4];
+
+      // A cached surface is released before the uncached render
+      PixelRenderer::new(4, 4)
+        .render_bytes(&window, &frame)
+        .unwrap();
+      assert!(cached());
+      let mut renderer = PixelRenderer::new(4, 4);
+      renderer.set_use_cache(false);
+      renderer.render_bytes(&window, &frame).unwrap();
+      renderer.render_bytes(&window, &frame).unwrap();
+      assert!(!cached());
+      renderer.prewarm(&window, None).unwrap();
+      assert!(!cached());
+      assert!(renderer.spawn_render_thread(&window).is_err());
+    });
+  }
+
+  #[test]
+  #[ignore = "requires a display and a GPU adapter"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_redraw_on_resize_presents_last_frame() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let tao_window = tao::window::WindowBuilder::new()
+        .with_inner_size(tao::dpi::PhysicalSize::new(64, 64))
+        .build(event_loop)
+        .expect("Failed to create window");
+      let window_id = tao_window.id();
+      let key = render_state_key(&tao_window);
+      let window = crate::tao::structs::Window {
+        inner: Some(Arc::new(std::sync::Mutex::new(tao_window))),
+      };
+
+      let mut renderer = PixelRenderer::new(4, 4);
+      renderer.set_redraw_on_resize(true);
+      renderer.render_bytes(&window, &[255u8; 4 * 4 * 4]).unwrap();
+
+      let cached_size = || {
+        let state = render_state(key).unwrap();
+        let state = state.lock().unwrap();
+        assert!(state.last_frame.is_some());
+        (state.last_window_width, state.last_window_height)
+      };
+      let resized = tao::event::WindowEvent::Resized(tao::dpi::PhysicalSize::new(96, 48));
+      redraw_after_resize(window_id, &resized);
+      assert_eq!(cached_size(), (96, 48));
+
+      // A frame rendered without the option drops the kept copy
+      renderer.set_redraw_on_resize(false);
+      renderer.render_bytes(&window, &[255u8; 4 * 4 * 4]).unwrap();
+      let state = render_state(key).unwrap();
+      assert!(state.lock().unwrap().last_frame.is_none());
+    });
+  }
+}
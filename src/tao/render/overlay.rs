@@ -0,0 +1,207 @@
+//! Debug overlay
+//!
+//! Draws a line of diagnostics text into a scaled frame with a built-in 3x5
+//! bitmap font, see [`super::PixelRenderer::set_debug_overlay`].
+
+use crate::tao::enums::OverlayCorner;
+
+/// Width of a glyph in font pixels
+const GLYPH_WIDTH: u32 = 3;
+/// Height of a glyph in font pixels
+const GLYPH_HEIGHT: u32 = 5;
+/// Size of a font pixel in frame pixels
+const FONT_SCALE: u32 = 2;
+/// Space around the text inside its backing box, in frame pixels
+const PADDING: u32 = 4;
+/// Distance of the backing box from the frame edges, in frame pixels
+const MARGIN: u32 = 4;
+/// Opacity of the dark box behind the text
+const BACKING_ALPHA: u8 = 160;
+
+/// Returns the rows of a glyph, most significant of the 3 low bits leftmost
+///
+/// Letters are uppercase only; characters without a glyph are drawn as spaces.
+fn glyph(c: char) -> [u8; 5] {
+  match c.to_ascii_uppercase() {
+    '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+    '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+    '2' => [0b110, 0b001, 0b010, 0b100, 0b111],
+    '3' => [0b110, 0b001, 0b010, 0b001, 0b110],
+    '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+    '5' => [0b111, 0b100, 0b110, 0b001, 0b110],
+    '6' => [0b011, 0b100, 0b110, 0b101, 0b010],
+    '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+    '8' => [0b010, 0b101, 0b010, 0b101, 0b010],
+    '9' => [0b010, 0b101, 0b011, 0b001, 0b110],
+    'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+    'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+    'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+    'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+    'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+    'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+    'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+    'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+    'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+    'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+    'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+    'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+    'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+    'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+    'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+    'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+    'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+    'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+    'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+    'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+    'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+    'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+    'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+    'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+    'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+    'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+    '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+    ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+    '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+    '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+    _ => [0; 5],
+  }
+}
+
+/// Formats the overlay line, e.g. "60 FPS 16.7 MS VULKAN"
+pub(crate) fn overlay_text(fps: f64, frame_ms: f64, backend: &str) -> String {
+  format!("{:.0} FPS {:.1} MS {}", fps, frame_ms, backend).to_ascii_uppercase()
+}
+
+/// Blends `color` over an RGBA pixel
+fn blend(pixel: &mut [u8], color: [u8; 4]) {
+  let alpha = color[3] as u32;
+  for channel in 0..3 {
+    pixel[channel] =
+      ((color[channel] as u32 * alpha + pixel[channel] as u32 * (255 - alpha)) / 255) as u8;
+  }
+  pixel[3] = (alpha + pixel[3] as u32 * (255 - alpha) / 255) as u8;
+}
+
+/// Draws `text` on a dark backing box in a corner of an RGBA frame
+///
+/// Nothing is drawn into a frame too small to hold the whole box.
+pub(crate) fn draw_overlay(
+  frame: &mut [u8],
+  width: u32,
+  height: u32,
+  text: &str,
+  corner: OverlayCorner,
+  color: [u8; 4],
+) {
+  let chars = text.chars().count() as u32;
+  if chars == 0 {
+    return;
+  }
+  let advance = (GLYPH_WIDTH + 1) * FONT_SCALE;
+  let box_width = chars * advance - FONT_SCALE + 2 * PADDING;
+  let box_height = GLYPH_HEIGHT * FONT_SCALE + 2 * PADDING;
+  if box_width + MARGIN > width || box_height + MARGIN > height {
+    return;
+  }
+
+  let left = match corner {
+    OverlayCorner::TopLeft | OverlayCorner::BottomLeft => MARGIN,
+    OverlayCorner::TopRight | OverlayCorner::BottomRight => width - MARGIN - box_width,
+  };
+  let top = match corner {
+    OverlayCorner::TopLeft | OverlayCorner::TopRight => MARGIN,
+    OverlayCorner::BottomLeft | OverlayCorner::BottomRight => height - MARGIN - box_height,
+  };
+  let pixel_at = |x: u32, y: u32| ((y * width + x) * 4) as usize;
+
+  for y in top..top + box_height {
+    for x in left..left + box_width {
+      let index = pixel_at(x, y);
+      blend(&mut frame[index..index + 4], [0, 0, 0, BACKING_ALPHA]);
+    }
+  }
+
+  for (i, c) in text.chars().enumerate() {
+    let glyph_left = left + PADDING + i as u32 * advance;
+    for (row, bits) in glyph(c).iter().enumerate() {
+      for column in 0..GLYPH_WIDTH {
+        if bits & (0b100 >> column) == 0 {
+          continue;
+        }
+        let x0 = glyph_left + column * FONT_SCALE;
+        let y0 = top + PADDING + row as u32 * FONT_SCALE;
+        for y in y0..y0 + FONT_SCALE {
+          for x in x0..x0 + FONT_SCALE {
+            let index = pixel_at(x, y);
+            blend(&mut frame[index..index + 4], color);
+          }
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_backend_names_have_glyphs() {
+    for backend in ["vulkan", "metal", "dx12", "gl", "empty"] {
+      let text = overlay_text(59.94, 16.683, backend);
+      assert!(text.chars().all(|c| c == ' ' || glyph(c) != [0; 5]));
+    }
+    assert_eq!(
+      overlay_text(59.94, 16.683, "vulkan"),
+      "60 FPS 16.7 MS VULKAN"
+    );
+  }
+
+  #[test]
+  fn test_overlay_drawn_in_requested_corner() {
+    let (width, height) = (200, 100);
+    let background = [10, 20, 30, 255];
+    let white = [255, 255, 255, 255];
+    let changed = |frame: &[u8], x: u32, y: u32| {
+      let index = ((y * width + x) * 4) as usize;
+      frame[index..index + 4] != background
+    };
+
+    let mut frame = background.repeat((width * height) as usize);
+    draw_overlay(
+      &mut frame,
+      width,
+      height,
+      "8",
+      OverlayCorner::BottomRight,
+      white,
+    );
+    // The backing box, and the top row of the glyph (lit in its middle column)
+    let glyph_x = width - MARGIN - PADDING - GLYPH_WIDTH * FONT_SCALE;
+    let glyph_y = height - MARGIN - PADDING - GLYPH_HEIGHT * FONT_SCALE;
+    assert!(changed(&frame, width - MARGIN - 1, height - MARGIN - 1));
+    assert!(!changed(&frame, width - 1, height - 1));
+    assert!(!changed(&frame, 0, 0));
+    let index = ((glyph_y * width + glyph_x + FONT_SCALE) * 4) as usize;
+    assert_eq!(&frame[index..index + 4], &white);
+
+    let mut frame = background.repeat((width * height) as usize);
+    draw_overlay(
+      &mut frame,
+      width,
+      height,
+      "8",
+      OverlayCorner::TopLeft,
+      white,
+    );
+    assert!(changed(&frame, MARGIN, MARGIN));
+    assert!(!changed(&frame, width - 1, height - 1));
+  }
+
+  #[test]
+  fn test_overlay_skipped_when_frame_too_small() {
+    let mut frame = vec![0u8; 8 * 8 * 4];
+    draw_overlay(&mut frame, 8, 8, "60 FPS", OverlayCorner::TopLeft, [255; 4]);
+    assert!(frame.iter().all(|&v| v == 0));
+  }
+}
@@ -66,6 +66,143 @@ pub fn calculate_scaled_dimensions(
   }
 }
 
+/// Rounds an offset down to the nearest multiple of `alignment`
+///
+/// An alignment of 0 or 1 leaves the offset unchanged.
+pub fn align_offset(offset: u32, alignment: u32) -> u32 {
+  if alignment <= 1 {
+    offset
+  } else {
+    offset - offset % alignment
+  }
+}
+
+/// Snaps the offsets of an `(offset_x, offset_y, scaled_width, scaled_height)`
+/// layout down to a multiple of `alignment`
+///
+/// The one alignment rule, applied in every scale mode: presenting, mapping
+/// window pixels back with [`window_to_buffer`] and the deterministic renderer all
+/// go through it, so they place the content identically. `Fill` and `Stretch`
+/// always start at 0, so only the letterbox offsets of `Fit`, `Integer` and `None`
+/// move.
+pub fn align_layout(layout: (u32, u32, u32, u32), alignment: u32) -> (u32, u32, u32, u32) {
+  let (offset_x, offset_y, scaled_width, scaled_height) = layout;
+  (
+    align_offset(offset_x, alignment),
+    align_offset(offset_y, alignment),
+    scaled_width,
+    scaled_height,
+  )
+}
+
+/// Calculates scaled dimensions with offsets snapped to an alignment grid
+///
+/// Same as [`calculate_scaled_dimensions`], but `offset_x`/`offset_y` are rounded
+/// down to a multiple of `alignment` by [`align_layout`] so that scaled content
+/// lands on consistent pixel boundaries (e.g. an alignment of 2 gives even offsets).
+pub fn calculate_scaled_dimensions_aligned(
+  buffer_width: u32,
+  buffer_height: u32,
+  window_width: u32,
+  window_height: u32,
+  scale_mode: ScaleMode,
+  alignment: u32,
+) -> (u32, u32, u32, u32) {
+  let layout = calculate_scaled_dimensions(
+    buffer_width,
+    buffer_height,
+    window_width,
+    window_height,
+    scale_mode,
+  );
+  align_layout(layout, alignment)
+}
+
+/// Returns whether the scaled content covers the whole window
+///
+/// Takes the `(offset_x, offset_y, scaled_width, scaled_height)` layout of
+/// [`calculate_scaled_dimensions_aligned`]. When it does, there is no letterbox
+/// area and clearing the frame to the background color first can be skipped.
+pub fn covers_window(layout: (u32, u32, u32, u32), window_width: u32, window_height: u32) -> bool {
+  let (offset_x, offset_y, scaled_width, scaled_height) = layout;
+  offset_x == 0 && offset_y == 0 && scaled_width >= window_width && scaled_height >= window_height
+}
+
+/// Returns the letterbox bars around the scaled content as `(x, y, width, height)`
+///
+/// Takes the layout of [`calculate_scaled_dimensions_aligned`]. The top and
+/// bottom bars span the window width and the left and right bars the height
+/// between them; bars of zero size are left out, so content that covers the
+/// window has none. Content larger than the window is clipped to it first.
+pub fn letterbox_bars(
+  layout: (u32, u32, u32, u32),
+  window_width: u32,
+  window_height: u32,
+) -> Vec<(u32, u32, u32, u32)> {
+  let (offset_x, offset_y, scaled_width, scaled_height) = layout;
+  let left = offset_x.min(window_width);
+  let top = offset_y.min(window_height);
+  let right = offset_x.saturating_add(scaled_width).min(window_width);
+  let bottom = offset_y.saturating_add(scaled_height).min(window_height);
+
+  let bars = [
+    (0, 0, window_width, top),
+    (0, bottom, window_width, window_height - bottom),
+    (0, top, left, bottom.saturating_sub(top)),
+    (right, top, window_width - right, bottom.saturating_sub(top)),
+  ];
+  bars
+    .into_iter()
+    .filter(|&(_, _, width, height)| width > 0 && height > 0)
+    .collect()
+}
+
+/// Maps a window pixel back to the source buffer pixel displayed there
+///
+/// Inverse of the layout computed by [`calculate_scaled_dimensions_aligned`],
+/// including the centered crop of `Fill` and `None`. Returns `None` for points in
+/// the letterbox area or outside the window.
+#[allow(clippy::too_many_arguments)]
+pub fn window_to_buffer(
+  x: u32,
+  y: u32,
+  buffer_width: u32,
+  buffer_height: u32,
+  window_width: u32,
+  window_height: u32,
+  scale_mode: ScaleMode,
+  alignment: u32,
+) -> Option<(u32, u32)> {
+  if x >= window_width || y >= window_height {
+    return None;
+  }
+  let (offset_x, offset_y, scaled_width, scaled_height) = calculate_scaled_dimensions_aligned(
+    buffer_width,
+    buffer_height,
+    window_width,
+    window_height,
+    scale_mode,
+    alignment,
+  );
+  if scaled_width == 0 || scaled_height == 0 {
+    return None;
+  }
+
+  // Position within the scaled image, accounting for the centered crop
+  let crop_x = scaled_width.saturating_sub(window_width) / 2;
+  let crop_y = scaled_height.saturating_sub(window_height) / 2;
+  let scaled_x = (x + crop_x).checked_sub(offset_x)?;
+  let scaled_y = (y + crop_y).checked_sub(offset_y)?;
+  if scaled_x >= scaled_width || scaled_y >= scaled_height {
+    return None;
+  }
+
+  Some((
+    (scaled_x as u64 * buffer_width as u64 / scaled_width as u64) as u32,
+    (scaled_y as u64 * buffer_height as u64 / scaled_height as u64) as u32,
+  ))
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -74,6 +211,40 @@ mod tests {
   // ScaleMode::Fit Tests
   // ============================================================================
 
+  #[test]
+  fn test_letterbox_bars() {
+    // 16:9 content in a 4:3 window: bars above and below
+    let layout = calculate_scaled_dimensions(1920, 1080, 800, 600, ScaleMode::Fit);
+    assert_eq!(
+      letterbox_bars(layout, 800, 600),
+      vec![(0, 0, 800, 75), (0, 525, 800, 75)]
+    );
+    // 4:3 content in a 16:9 window: bars left and right
+    let layout = calculate_scaled_dimensions(800, 600, 1920, 1080, ScaleMode::Fit);
+    assert_eq!(
+      letterbox_bars(layout, 1920, 1080),
+      vec![(0, 0, 240, 1080), (1680, 0, 240, 1080)]
+    );
+    // Integer scaling can leave bars on all sides
+    let layout = calculate_scaled_dimensions(100, 100, 250, 310, ScaleMode::Integer);
+    assert_eq!(
+      letterbox_bars(layout, 250, 310),
+      vec![
+        (0, 0, 250, 55),
+        (0, 255, 250, 55),
+        (0, 55, 25, 200),
+        (225, 55, 25, 200)
+      ]
+    );
+    // Content covering the window leaves none
+    for mode in [ScaleMode::Stretch, ScaleMode::Fill] {
+      let layout = calculate_scaled_dimensions(1920, 1080, 800, 600, mode);
+      assert!(letterbox_bars(layout, 800, 600).is_empty());
+    }
+    let layout = calculate_scaled_dimensions(1920, 1080, 800, 600, ScaleMode::None);
+    assert!(letterbox_bars(layout, 800, 600).is_empty());
+  }
+
   #[test]
   fn test_fit_16_9_buffer_to_16_9_window() {
     // 1920x1080 buffer to 1920x1080 window - exact fit
@@ -256,6 +427,49 @@ mod tests {
     assert_eq!(offset_y, 48); // (768 - 672) / 2
   }
 
+  // ============================================================================
+  // Offset Alignment Tests
+  // ============================================================================
+
+  #[test]
+  fn test_integer_snes_offsets_even() {
+    // 256x224 buffer (SNES resolution) to 1024x768 window with even alignment
+    let (offset_x, offset_y, scaled_w, scaled_h) =
+      calculate_scaled_dimensions_aligned(256, 224, 1024, 768, ScaleMode::Integer, 2);
+    assert_eq!(scaled_w, 768);
+    assert_eq!(scaled_h, 672);
+    assert_eq!(offset_x % 2, 0);
+    assert_eq!(offset_y % 2, 0);
+    assert_eq!(offset_x, 128);
+    assert_eq!(offset_y, 48);
+  }
+
+  #[test]
+  fn test_integer_odd_offset_snapped_to_even() {
+    // 256x224 buffer to 1025x770 window
+    // Unaligned offsets are (128, 49), the odd one is rounded down
+    let (offset_x, offset_y, _, _) =
+      calculate_scaled_dimensions(256, 224, 1025, 770, ScaleMode::Integer);
+    assert_eq!(offset_x, 128);
+    assert_eq!(offset_y, 49);
+
+    let (offset_x, offset_y, scaled_w, scaled_h) =
+      calculate_scaled_dimensions_aligned(256, 224, 1025, 770, ScaleMode::Integer, 2);
+    assert_eq!(scaled_w, 768);
+    assert_eq!(scaled_h, 672);
+    assert_eq!(offset_x, 128);
+    assert_eq!(offset_y, 48);
+  }
+
+  #[test]
+  fn test_align_offset_grid() {
+    assert_eq!(align_offset(49, 0), 49);
+    assert_eq!(align_offset(49, 1), 49);
+    assert_eq!(align_offset(49, 2), 48);
+    assert_eq!(align_offset(49, 8), 48);
+    assert_eq!(align_offset(7, 8), 0);
+  }
+
   // ============================================================================
   // ScaleMode::None Tests
   // ============================================================================
@@ -389,4 +603,63 @@ mod tests {
     assert_eq!(offset_x, 397); // (800 - 6) / 2
     assert_eq!(offset_y, 0);
   }
+
+  // ============================================================================
+  // window_to_buffer Tests
+  // ============================================================================
+
+  #[test]
+  fn test_window_to_buffer_fit_letterbox() {
+    // 4:3 buffer in a 16:9 window: pillarbox of 240px on each side
+    let map = |x, y| window_to_buffer(x, y, 800, 600, 1920, 1080, ScaleMode::Fit, 1);
+    assert_eq!(map(100, 500), None);
+    assert_eq!(map(1700, 500), None);
+    assert_eq!(map(240, 0), Some((0, 0)));
+    assert_eq!(map(1679, 1079), Some((799, 599)));
+    assert_eq!(map(960, 540), Some((400, 300)));
+  }
+
+  #[test]
+  fn test_window_to_buffer_integer_and_stretch() {
+    // 256x224 at 3x in 1024x768: offset (128, 48)
+    let map = |x, y| window_to_buffer(x, y, 256, 224, 1024, 768, ScaleMode::Integer, 1);
+    assert_eq!(map(127, 100), None);
+    assert_eq!(map(128, 48), Some((0, 0)));
+    assert_eq!(map(130, 50), Some((0, 0)));
+    assert_eq!(map(131, 51), Some((1, 1)));
+
+    let map = |x, y| window_to_buffer(x, y, 100, 50, 200, 200, ScaleMode::Stretch, 1);
+    assert_eq!(map(199, 199), Some((99, 49)));
+    assert_eq!(map(200, 0), None);
+  }
+
+  #[test]
+  fn test_window_to_buffer_cropped_modes() {
+    // None: 100x100 buffer centered and cropped in a 50x50 window
+    let map = |x, y| window_to_buffer(x, y, 100, 100, 50, 50, ScaleMode::None, 1);
+    assert_eq!(map(0, 0), Some((25, 25)));
+    assert_eq!(map(49, 49), Some((74, 74)));
+
+    // Fill: 16:9 buffer scaled to 1066x600 in a 4:3 window, 133px cropped per side
+    let map = |x, y| window_to_buffer(x, y, 1920, 1080, 800, 600, ScaleMode::Fill, 1);
+    assert_eq!(map(0, 0), Some((239, 0)));
+    assert_eq!(map(400, 300), Some((960, 540)));
+  }
+
+  #[test]
+  fn test_covers_window() {
+    let covers = |buffer: (u32, u32), window: (u32, u32), mode| {
+      let layout =
+        calculate_scaled_dimensions_aligned(buffer.0, buffer.1, window.0, window.1, mode, 1);
+      covers_window(layout, window.0, window.1)
+    };
+    // Stretch and Fill leave no letterbox, so the clear is skipped
+    assert!(covers((320, 240), (1920, 1080), ScaleMode::Stretch));
+    assert!(covers((1920, 1080), (800, 600), ScaleMode::Fill));
+    assert!(covers((800, 600), (800, 600), ScaleMode::Fit));
+    // Letterboxed layouts still need the background
+    assert!(!covers((1920, 1080), (800, 600), ScaleMode::Fit));
+    assert!(!covers((320, 240), (800, 600), ScaleMode::Integer));
+    assert!(!covers((320, 240), (800, 600), ScaleMode::None));
+  }
 }
@@ -0,0 +1,509 @@
+//! Dedicated render threads
+//!
+//! Presents the frames of a window from a thread of its own, see
+//! [`PixelRenderer::spawn_render_thread`].
+//!
+//! Native window handles must only be used on the event loop thread, so the
+//! work of a render thread that needs them, creating a surface and updating the
+//! click-through region, is posted back to that thread: the event loops run it
+//! on `MainEventsCleared`, see [`run_loop_thread_work`]. The window size is
+//! sampled there too, from `Resized` events, and queued with each frame.
+
+use crate::tao::render::{
+  insert_render_state, release_render_state, remove_render_state, render_state, render_state_key,
+  update_alpha_hittest, window_id_key, PixelRenderer,
+};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use std::sync::mpsc::{SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+
+/// Whether a window surface may be presented to from a render thread
+///
+/// Windows (DXGI, Vulkan) and Linux and the BSDs (Vulkan, EGL on X11 or Wayland)
+/// present to a native window from any thread once the surface exists. On macOS
+/// the surface is the view's `CAMetalLayer`, which AppKit only allows to be
+/// configured and resized on the main thread.
+pub(crate) const SURFACE_ON_RENDER_THREAD: bool = cfg!(any(
+  target_os = "windows",
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+));
+
+/// A message to the render thread of a window
+enum RenderMessage {
+  /// A frame to present at the given window size
+  Frame {
+    buffer: Vec<u8>,
+    width: u32,
+    height: u32,
+  },
+  /// Releases the window's surface; whether it had one is sent back
+  ReleaseSurface(SyncSender<bool>),
+}
+
+/// A window presented by a render thread
+struct ThreadedWindow {
+  thread: std::thread::ThreadId,
+  /// Inner size of the window, sampled on the event loop thread
+  size: (u32, u32),
+  /// Taken when the thread is stopped, to close its channel
+  sender: Option<SyncSender<RenderMessage>>,
+  /// Why the event loop thread failed to create a surface for the render thread
+  surface_error: Option<String>,
+}
+
+/// Windows presented by a render thread, keyed like the render cache
+static THREADED_WINDOWS: std::sync::LazyLock<
+  Mutex<std::collections::HashMap<u64, ThreadedWindow>>,
+> = std::sync::LazyLock::new(|| Mutex::new(std::collections::HashMap::new()));
+
+/// Work a render thread left to the event loop thread, see [`run_loop_thread_work`]
+struct LoopThreadWork {
+  window: Arc<Mutex<tao::window::Window>>,
+  /// Renderer to create the window's surface with, if the render thread has none
+  create_surface: Option<PixelRenderer>,
+  /// Whether the click-through region should be updated from the last frame
+  update_alpha_hittest: bool,
+}
+
+/// Pending work of render threads, at most one entry per window
+static LOOP_THREAD_WORK: std::sync::LazyLock<
+  Mutex<std::collections::HashMap<u64, LoopThreadWork>>,
+> = std::sync::LazyLock::new(|| Mutex::new(std::collections::HashMap::new()));
+
+/// Wakes the event loop when a render thread posts work
+static LOOP_PROXY: std::sync::OnceLock<Mutex<tao::event_loop::EventLoopProxy<()>>> =
+  std::sync::OnceLock::new();
+
+/// Lets render threads wake the event loop, called when the event loop is created
+pub(crate) fn register_loop_proxy(proxy: &tao::event_loop::EventLoopProxy<()>) {
+  let _ = LOOP_PROXY.set(Mutex::new(proxy.clone()));
+}
+
+/// Tracks the size of windows presented by a render thread
+///
+/// Called by the event loops for every window event, so render threads never
+/// query the window.
+pub(crate) fn track_resize(window_id: tao::window::WindowId, event: &tao::event::WindowEvent) {
+  let tao::event::WindowEvent::Resized(size) = event else {
+    return;
+  };
+  // Minimized windows report a zero size
+  if size.width == 0 || size.height == 0 {
+    return;
+  }
+  if let Some(threaded) = THREADED_WINDOWS
+    .lock()
+    .unwrap()
+    .get_mut(&window_id_key(window_id))
+  {
+    threaded.size = (size.width, size.height);
+  }
+}
+
+/// Checks whether the calling thread may render a window itself
+///
+/// Returns whether it is the window's render thread; a window with a render
+/// thread can't be rendered from any other thread.
+pub(crate) fn check_render_thread(window_id: u64) -> napi::Result<bool> {
+  let windows = THREADED_WINDOWS.lock().unwrap();
+  match windows.get(&window_id) {
+    None => Ok(false),
+    Some(threaded) if threaded.thread == std::thread::current().id() => Ok(true),
+    Some(_) => Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      "Window is presented by a render thread; stop it before rendering the window otherwise"
+        .to_string(),
+    )),
+  }
+}
+
+/// Releases the surface of a window through its render thread
+///
+/// The thread releases the surface between two frames, so it is never dropped
+/// while presenting. Returns `None` if the window has no render thread.
+pub(crate) fn release_threaded_surface(window_id: u64) -> Option<bool> {
+  let sender = {
+    let windows = THREADED_WINDOWS.lock().unwrap();
+    let threaded = windows.get(&window_id)?;
+    if threaded.thread == std::thread::current().id() {
+      drop(windows);
+      return Some(remove_render_state(window_id));
+    }
+    threaded.sender.clone()?
+  };
+  let (ack, released) = std::sync::mpsc::sync_channel(1);
+  match sender.send(RenderMessage::ReleaseSurface(ack)) {
+    Ok(()) => Some(released.recv().unwrap_or(false)),
+    // The thread stopped on an error and no longer uses the surface
+    Err(_) => Some(remove_render_state(window_id)),
+  }
+}
+
+/// Asks the event loop thread for a new surface for the render thread of a window
+///
+/// Fails with the reason the previous request failed, which stops the thread.
+pub(crate) fn request_surface(
+  window_id: u64,
+  window: &Arc<Mutex<tao::window::Window>>,
+  renderer: &PixelRenderer,
+) -> napi::Result<()> {
+  let failed = THREADED_WINDOWS
+    .lock()
+    .unwrap()
+    .get_mut(&window_id)
+    .and_then(|threaded| threaded.surface_error.take());
+  if let Some(reason) = failed {
+    return Err(napi::Error::new(napi::Status::GenericFailure, reason));
+  }
+  post_loop_thread_work(window_id, window, |work| {
+    work.create_surface = Some(renderer.clone());
+  });
+  Ok(())
+}
+
+/// Asks the event loop thread to update the click-through region of a window
+pub(crate) fn request_alpha_hittest(window_id: u64, window: &Arc<Mutex<tao::window::Window>>) {
+  post_loop_thread_work(window_id, window, |work| work.update_alpha_hittest = true);
+}
+
+/// Adds to the pending work of a window and wakes the event loop
+fn post_loop_thread_work<F>(window_id: u64, window: &Arc<Mutex<tao::window::Window>>, add: F)
+where
+  F: FnOnce(&mut LoopThreadWork),
+{
+  let mut work = LOOP_THREAD_WORK.lock().unwrap();
+  add(work.entry(window_id).or_insert_with(|| LoopThreadWork {
+    window: window.clone(),
+    create_surface: None,
+    update_alpha_hittest: false,
+  }));
+  if let Some(proxy) = LOOP_PROXY.get() {
+    let _ = proxy.lock().unwrap().send_event(());
+  }
+}
+
+/// Runs the work posted by render threads
+///
+/// Called by the event loops on `MainEventsCleared`, on the thread owning the
+/// windows.
+pub(crate) fn run_loop_thread_work() {
+  let pending = std::mem::take(&mut *LOOP_THREAD_WORK.lock().unwrap());
+  for (window_id, work) in pending {
+    if let Some(renderer) = &work.create_surface {
+      create_threaded_surface(window_id, &work.window, renderer);
+    }
+    if work.update_alpha_hittest {
+      if let Ok(window) = work.window.lock() {
+        update_alpha_hittest(window_id, &window);
+      }
+    }
+  }
+}
+
+/// Creates the surface of a window for its render thread
+///
+/// Does nothing if the thread was stopped or already has a surface. A failure is
+/// reported to the thread by its next [`request_surface`].
+fn create_threaded_surface(
+  window_id: u64,
+  window: &Mutex<tao::window::Window>,
+  renderer: &PixelRenderer,
+) {
+  let Some(thread) = THREADED_WINDOWS
+    .lock()
+    .unwrap()
+    .get(&window_id)
+    .map(|threaded| threaded.thread)
+  else {
+    return;
+  };
+  if render_state(window_id).is_some() {
+    return;
+  }
+  let Ok(window) = window.lock() else {
+    return;
+  };
+  if let Err(e) = new_threaded_surface(window_id, &window, renderer, thread) {
+    if let Some(threaded) = THREADED_WINDOWS.lock().unwrap().get_mut(&window_id) {
+      threaded.surface_error = Some(e.reason);
+    }
+  }
+}
+
+/// Creates and caches a surface of a window for its render thread
+///
+/// Called on the event loop thread; the surface is bound to the render thread
+/// from the start, see [`SURFACE_ON_RENDER_THREAD`].
+fn new_threaded_surface(
+  window_id: u64,
+  window: &tao::window::Window,
+  renderer: &PixelRenderer,
+  thread: std::thread::ThreadId,
+) -> napi::Result<()> {
+  let size = window.inner_size();
+  let state = renderer.new_render_state_for(window, size.width, size.height, thread)?;
+  insert_render_state(window_id, state);
+  Ok(())
+}
+
+/// A thread presenting the frames of one window
+///
+/// Created by `PixelRenderer.spawnRenderThread`. Frames are queued one deep:
+/// while the thread is presenting, one more frame waits and further frames are
+/// dropped, so a fast producer never builds up latency.
+///
+/// A failed render stops the thread and is reported by the renderer's `onError`
+/// callback, if set, and by the next `submitFrame`. Stopping the thread releases
+/// the window's surface, so the window can be rendered from JS again.
+///
+/// The thread needs a running event loop: when its surface must be created
+/// again, e.g. after `freeRenderState` or a lost surface, the event loop thread
+/// creates it and frames are dropped until then.
+#[napi]
+pub struct RenderThread {
+  renderer: PixelRenderer,
+  window_id: u64,
+  sender: Option<SyncSender<RenderMessage>>,
+  handle: Option<std::thread::JoinHandle<()>>,
+  /// Why the thread stopped on its own
+  error: Arc<Mutex<Option<String>>>,
+}
+
+impl RenderThread {
+  /// Spawns the render thread of a window, see [`PixelRenderer::spawn_render_thread`]
+  pub(crate) fn spawn(
+    renderer: &PixelRenderer,
+    window: Arc<Mutex<tao::window::Window>>,
+  ) -> napi::Result<Self> {
+    let failure = |reason: &str| napi::Error::new(napi::Status::GenericFailure, reason.to_string());
+    if !SURFACE_ON_RENDER_THREAD {
+      return Err(failure(
+        "Render threads are not supported on this platform: the window surface must be used on the main thread",
+      ));
+    }
+    if !crate::tao::platform::is_event_loop_thread() {
+      return Err(failure(
+        "Render threads must be spawned on the event loop thread",
+      ));
+    }
+    let (window_id, size) = {
+      let window = window
+        .lock()
+        .map_err(|_| failure("Failed to lock window"))?;
+      let window_id = render_state_key(&window);
+      if THREADED_WINDOWS.lock().unwrap().contains_key(&window_id) {
+        return Err(failure("Window already has a render thread"));
+      }
+      let size = window.inner_size();
+      (window_id, (size.width, size.height))
+    };
+    if let Some(state) = render_state(window_id) {
+      if state.lock().unwrap().thread != std::thread::current().id() {
+        return Err(failure(
+          "Window render surface was created on another thread",
+        ));
+      }
+    }
+    // The render thread gets a surface of its own
+    release_render_state(window_id);
+
+    let (sender, receiver) = std::sync::mpsc::sync_channel::<RenderMessage>(1);
+    let error = Arc::new(Mutex::new(None));
+    let thread_renderer = renderer.clone();
+    let thread_window = window.clone();
+    let thread_error = error.clone();
+    let handle = std::thread::Builder::new()
+      .name(format!("render-{}", window_id))
+      .spawn(move || {
+        for message in receiver {
+          let (frame, width, height) = match message {
+            RenderMessage::Frame {
+              buffer,
+              width,
+              height,
+            } => (buffer, width, height),
+            RenderMessage::ReleaseSurface(ack) => {
+              let _ = ack.send(remove_render_state(window_id));
+              continue;
+            }
+          };
+          if let Err(e) =
+            thread_renderer.render_threaded(window_id, &thread_window, &frame, width, height)
+          {
+            log_error!(
+              "render",
+              "Render thread of window {} stopped: {}",
+              window_id,
+              e.reason
+            );
+            *thread_error.lock().unwrap() = Some(e.reason);
+            break;
+          }
+        }
+      })
+      .map_err(|e| failure(&format!("Failed to spawn render thread: {}", e)))?;
+
+    // Registered before the first frame can be sent, so the thread finds itself
+    let thread = handle.thread().id();
+    THREADED_WINDOWS.lock().unwrap().insert(
+      window_id,
+      ThreadedWindow {
+        thread,
+        size,
+        sender: Some(sender.clone()),
+        surface_error: None,
+      },
+    );
+    let mut render_thread = Self {
+      renderer: renderer.clone(),
+      window_id,
+      sender: Some(sender),
+      handle: Some(handle),
+      error,
+    };
+    let created = match window.lock() {
+      Ok(window) => new_threaded_surface(window_id, &window, renderer, thread),
+      Err(_) => Err(failure("Failed to lock window")),
+    };
+    if let Err(e) = created {
+      render_thread.stop();
+      return Err(e);
+    }
+    Ok(render_thread)
+  }
+
+  /// Queues raw RGBA bytes, see [`RenderThread::submit_frame`]
+  fn submit_bytes(&self, buffer: &[u8]) -> napi::Result<bool> {
+    let frame = self.renderer.validate_buffer(buffer)?.to_vec();
+    let stopped = || {
+      let reason = self.error.lock().unwrap().clone();
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        match reason {
+          Some(reason) => format!("Render thread stopped: {}", reason),
+          None => "Render thread stopped".to_string(),
+        },
+      )
+    };
+    let Some(sender) = &self.sender else {
+      return Err(stopped());
+    };
+    let Some((width, height)) = THREADED_WINDOWS
+      .lock()
+      .unwrap()
+      .get(&self.window_id)
+      .map(|threaded| threaded.size)
+    else {
+      return Err(stopped());
+    };
+    let message = RenderMessage::Frame {
+      buffer: frame,
+      width,
+      height,
+    };
+    match sender.try_send(message) {
+      Ok(()) => Ok(true),
+      Err(TrySendError::Full(_)) => Ok(false),
+      Err(TrySendError::Disconnected(_)) => Err(stopped()),
+    }
+  }
+}
+
+#[napi]
+impl RenderThread {
+  /// Queues a frame for presentation
+  ///
+  /// The buffer must match the renderer's buffer size and is copied, so it can be
+  /// reused right away. Returns `false` if the frame was dropped because a frame
+  /// is still waiting for the thread. Throws once the thread has stopped.
+  #[napi]
+  pub fn submit_frame(&self, buffer: Buffer) -> napi::Result<bool> {
+    self.submit_bytes(&buffer)
+  }
+
+  /// Gets whether the thread is still presenting frames
+  #[napi]
+  pub fn is_running(&self) -> bool {
+    self.sender.is_some() && self.handle.as_ref().is_some_and(|h| !h.is_finished())
+  }
+
+  /// Stops the thread after the queued frame and releases the window's surface
+  ///
+  /// Blocks until the thread has exited. Does nothing if already stopped; the
+  /// thread is also stopped when this object is garbage collected.
+  #[napi]
+  pub fn stop(&mut self) {
+    // Closing the channel ends the thread's receive loop
+    self.sender = None;
+    let Some(handle) = self.handle.take() else {
+      return;
+    };
+    // The window stays registered until the thread has exited, so a last frame
+    // still leaves surface creation to the event loop thread
+    if let Some(threaded) = THREADED_WINDOWS.lock().unwrap().get_mut(&self.window_id) {
+      threaded.sender = None;
+    }
+    let _ = handle.join();
+    THREADED_WINDOWS.lock().unwrap().remove(&self.window_id);
+    LOOP_THREAD_WORK.lock().unwrap().remove(&self.window_id);
+    // The surface is bound to the exited thread; the next render creates a new one
+    remove_render_state(self.window_id);
+  }
+}
+
+impl Drop for RenderThread {
+  fn drop(&mut self) {
+    self.stop();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  #[ignore = "requires a display and a GPU adapter"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_render_thread_owns_window_surface() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let window = crate::tao::test_display::build_window(
+        event_loop,
+        tao::window::WindowBuilder::new().with_inner_size(tao::dpi::PhysicalSize::new(64, 64)),
+      );
+      let frame = [255u8; 4 * 4 * 4];
+
+      let renderer = PixelRenderer::new(4, 4);
+      let mut render_thread = renderer.spawn_render_thread(&window).unwrap();
+      assert!(renderer.spawn_render_thread(&window).is_err());
+      assert!(render_thread.submit_bytes(&frame[..8]).is_err());
+      assert!(render_thread.submit_bytes(&frame).unwrap());
+      // The surface belongs to the render thread now
+      assert!(renderer.render_bytes(&window, &frame).is_err());
+      assert!(render_thread.is_running());
+
+      // The render thread releases its own surface, and the event loop thread
+      // creates the next one for it
+      let key = render_state_key(&window.inner.as_ref().unwrap().lock().unwrap());
+      assert!(crate::tao::render::free_render_state(&window));
+      assert!(!crate::tao::render::free_render_state(&window));
+      render_thread.submit_bytes(&frame).unwrap();
+      let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+      while render_state(key).is_none() && std::time::Instant::now() < deadline {
+        run_loop_thread_work();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+      }
+      assert!(render_state(key).is_some());
+      assert!(render_thread.is_running());
+
+      render_thread.stop();
+      assert!(!render_thread.is_running());
+      assert!(render_thread.submit_bytes(&frame).is_err());
+      renderer.render_bytes(&window, &frame).unwrap();
+    });
+  }
+}
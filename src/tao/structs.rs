@@ -3,14 +3,20 @@
 //! This module contains all structs from the tao crate.
 
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use napi_derive::napi;
 use std::sync::{Arc, Mutex};
 
 use crate::tao::enums::{
-  CursorIcon, ModifiersState, MouseButton, MouseButtonState, TaoTheme, WindowEvent,
+  AppEventType, CursorGrabMode, CursorIcon, ModifiersState, MouseButton, MouseButtonState,
+  ScrollUnit, TaoFullscreenType, TaoTheme, TouchPhase, WindowEvent, WindowLevel,
 };
 use crate::tao::types::Result;
 
+#[cfg(target_os = "macos")]
+use crate::tao::platform::appkit;
+#[cfg(target_os = "windows")]
+use crate::tao::platform::win32;
 #[cfg(target_os = "macos")]
 use tao::platform::macos::WindowBuilderExtMacOS;
 #[cfg(any(
@@ -35,10 +41,68 @@ pub struct MonitorInfo {
   pub position: Position,
   /// The scale factor of monitor.
   pub scale_factor: f64,
+  /// Whether this is the primary monitor.
+  pub is_primary: bool,
+}
+
+impl MonitorInfo {
+  fn from_handle(
+    monitor: &tao::monitor::MonitorHandle,
+    primary: Option<&tao::monitor::MonitorHandle>,
+  ) -> Self {
+    let size = monitor.size();
+    let position = monitor.position();
+    Self {
+      name: monitor.name(),
+      size: Size {
+        width: size.width as f64,
+        height: size.height as f64,
+      },
+      position: Position {
+        x: position.x as f64,
+        y: position.y as f64,
+      },
+      scale_factor: monitor.scale_factor(),
+      is_primary: primary == Some(monitor),
+    }
+  }
+}
+
+/// Orders monitors primary first, then left to right and top to bottom.
+fn sort_monitors(monitors: &mut [MonitorInfo]) {
+  monitors.sort_by(monitor_order);
+}
+
+/// The order of [`sort_monitors`]
+fn monitor_order(a: &MonitorInfo, b: &MonitorInfo) -> std::cmp::Ordering {
+  b.is_primary
+    .cmp(&a.is_primary)
+    .then(a.position.x.total_cmp(&b.position.x))
+    .then(a.position.y.total_cmp(&b.position.y))
+}
+
+/// Gets the monitor at an index of [`Window::available_monitors`]
+fn monitor_at(window: &tao::window::Window, index: u32) -> Option<tao::monitor::MonitorHandle> {
+  let primary = window.primary_monitor();
+  let mut monitors: Vec<_> = window
+    .available_monitors()
+    .map(|monitor| {
+      (
+        MonitorInfo::from_handle(&monitor, primary.as_ref()),
+        monitor,
+      )
+    })
+    .collect();
+  monitors.sort_by(|a, b| monitor_order(&a.0, &b.0));
+  monitors
+    .into_iter()
+    .nth(index as usize)
+    .map(|(_, monitor)| monitor)
 }
 
 /// 2D position.
 #[napi(object)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct Position {
   /// The X coordinate.
   pub x: f64,
@@ -64,6 +128,19 @@ pub struct Rectangle {
   pub size: Size,
 }
 
+/// Distances from the edges of a window's content area, in logical pixels.
+#[napi(object)]
+pub struct Insets {
+  /// The top inset.
+  pub top: f64,
+  /// The left inset.
+  pub left: f64,
+  /// The bottom inset.
+  pub bottom: f64,
+  /// The right inset.
+  pub right: f64,
+}
+
 /// Window options for creating a window.
 #[napi(object)]
 pub struct WindowOptions {
@@ -124,29 +201,131 @@ pub struct CursorPosition {
 /// Mouse event data.
 #[napi(object)]
 pub struct MouseEvent {
-  /// The button that was pressed/released.
-  pub button: MouseButton,
-  /// The state of button.
-  pub state: MouseButtonState,
-  /// The position of mouse.
+  /// The button that was pressed/released, absent for cursor moves.
+  pub button: Option<MouseButton>,
+  /// The state of button, absent for cursor moves.
+  pub state: Option<MouseButtonState>,
+  /// The cursor position in physical pixels; for button events, where it was last moved to.
   pub position: Position,
-  /// The number of clicks.
+  /// The number of clicks: 1 for a single click, 2 for a double-click and so on.
+  ///
+  /// A release reports the count of the press it ends; cursor moves report 0.
   pub click_count: u16,
-  /// The modifiers state.
-  pub modifiers: ModifiersState,
+  /// The modifiers held, empty for none.
+  pub modifiers: Vec<ModifiersState>,
+}
+
+/// Mouse wheel event data.
+#[napi(object)]
+pub struct WheelEvent {
+  /// The scroll amount in pixels; positive `y` scrolls up, positive `x` right.
+  ///
+  /// Line deltas are converted at 40 pixels per line, pixel deltas passed on as is.
+  pub delta: Position,
+  /// The unit the device reported the delta in.
+  pub unit: ScrollUnit,
+  /// The scroll amount in lines, as reported (`unit` is `Line`).
+  pub lines: Option<Position>,
+  /// The modifiers held, empty for none.
+  pub modifiers: Vec<ModifiersState>,
+}
+
+impl WheelEvent {
+  fn from_tao(
+    delta: &tao::event::MouseScrollDelta,
+    modifiers: tao::keyboard::ModifiersState,
+  ) -> Option<Self> {
+    let (delta, unit, lines) = match delta {
+      tao::event::MouseScrollDelta::LineDelta(x, y) => {
+        let (px, py) = crate::tao::input::lines_to_pixels(*x as f64, *y as f64);
+        (
+          Position { x: px, y: py },
+          ScrollUnit::Line,
+          Some(Position {
+            x: *x as f64,
+            y: *y as f64,
+          }),
+        )
+      }
+      tao::event::MouseScrollDelta::PixelDelta(delta) => (
+        Position {
+          x: delta.x,
+          y: delta.y,
+        },
+        ScrollUnit::Pixel,
+        None,
+      ),
+      _ => return None,
+    };
+    Some(Self {
+      delta,
+      unit,
+      lines,
+      modifiers: modifiers_list(modifiers),
+    })
+  }
+}
+
+/// Converts a tao mouse button, or returns `None` for buttons without a [`MouseButton`].
+fn mouse_button(button: &tao::event::MouseButton) -> Option<MouseButton> {
+  Some(match button {
+    tao::event::MouseButton::Left => MouseButton::Left,
+    tao::event::MouseButton::Right => MouseButton::Right,
+    tao::event::MouseButton::Middle => MouseButton::Middle,
+    tao::event::MouseButton::Other(id) => MouseButton::Other(*id),
+    _ => return None,
+  })
 }
 
 /// Keyboard event data.
 #[napi(object)]
 pub struct KeyboardEvent {
-  /// The key that was pressed.
+  /// The logical key, e.g. "a", "A" with Shift, or "Enter".
   pub key: String,
-  /// The key code.
+  /// The physical key code, independent of the layout, e.g. "KeyA".
   pub code: String,
   /// The key state.
   pub state: MouseButtonState,
-  /// The modifiers state.
-  pub modifiers: ModifiersState,
+  /// Whether the key press is an auto-repeat.
+  pub repeat: bool,
+  /// The modifiers held when the key was pressed or released, empty for none.
+  pub modifiers: Vec<ModifiersState>,
+}
+
+impl KeyboardEvent {
+  fn from_tao(event: &tao::event::KeyEvent, modifiers: tao::keyboard::ModifiersState) -> Self {
+    Self {
+      key: key_name(&event.logical_key),
+      code: event.physical_key.to_string(),
+      state: match event.state {
+        tao::event::ElementState::Pressed => MouseButtonState::Pressed,
+        _ => MouseButtonState::Released,
+      },
+      repeat: event.repeat,
+      modifiers: modifiers_list(modifiers),
+    }
+  }
+}
+
+/// Names a logical key: the text it produces, or the name of a named key.
+fn key_name(key: &tao::keyboard::Key) -> String {
+  match key {
+    tao::keyboard::Key::Character(text) => text.to_string(),
+    other => format!("{:?}", other),
+  }
+}
+
+/// Lists the held modifiers, in the order Shift, Control, Alt, Super.
+fn modifiers_list(modifiers: tao::keyboard::ModifiersState) -> Vec<ModifiersState> {
+  [
+    (modifiers.shift_key(), ModifiersState::Shift),
+    (modifiers.control_key(), ModifiersState::Control),
+    (modifiers.alt_key(), ModifiersState::Alt),
+    (modifiers.super_key(), ModifiersState::Super),
+  ]
+  .into_iter()
+  .filter_map(|(held, modifier)| held.then_some(modifier))
+  .collect()
 }
 
 /// Raw keyboard event data.
@@ -165,14 +344,53 @@ pub struct RawKeyEvent {
 pub struct Touch {
   /// The touch identifier.
   pub id: u32,
+  /// The touch phase.
+  pub phase: TouchPhase,
   /// The position of touch.
   pub position: Position,
-  /// The force of touch.
+  /// The touch pressure, normalized to 0.0–1.0.
+  ///
+  /// Many devices, including most touchscreens, report no pressure; the field is
+  /// then absent.
   pub force: Option<f64>,
   /// The device ID.
   pub device_id: u32,
 }
 
+impl Touch {
+  fn from_tao(touch: &tao::event::Touch) -> Self {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    touch.device_id.hash(&mut hasher);
+    Self {
+      id: touch.id as u32,
+      phase: match touch.phase {
+        tao::event::TouchPhase::Started => TouchPhase::Started,
+        tao::event::TouchPhase::Moved => TouchPhase::Moved,
+        tao::event::TouchPhase::Ended => TouchPhase::Ended,
+        _ => TouchPhase::Cancelled,
+      },
+      position: Position {
+        x: touch.location.x,
+        y: touch.location.y,
+      },
+      force: touch.force.map(|force| normalized_force(&force)),
+      device_id: hasher.finish() as u32,
+    }
+  }
+}
+
+/// Converts a calibrated or normalized tao force to a 0.0–1.0 pressure.
+fn normalized_force(force: &tao::event::Force) -> f64 {
+  let pressure = force.normalized();
+  if pressure.is_finite() {
+    pressure.clamp(0.0, 1.0)
+  } else {
+    0.0
+  }
+}
+
 /// Gesture event data.
 #[napi(object)]
 pub struct GestureEvent {
@@ -185,12 +403,474 @@ pub struct GestureEvent {
 }
 
 /// Window event data.
-#[napi(object)]
+#[napi(object, object_from_js = false)]
 pub struct WindowEventData {
   /// The window event type.
   pub event: WindowEvent,
-  /// The window ID.
-  pub window_id: u32,
+  /// The window ID, as returned by `Window.id`.
+  pub window_id: u64,
+  /// The key and the held modifiers (`KeyboardInput`).
+  pub keyboard: Option<KeyboardEvent>,
+  /// The button, cursor position and held modifiers (`MouseInput`, `CursorMoved`).
+  pub mouse: Option<MouseEvent>,
+  /// The scroll delta and held modifiers (`MouseWheel`).
+  pub wheel: Option<WheelEvent>,
+}
+
+impl WindowEventData {
+  fn new(event: WindowEvent, window_id: tao::window::WindowId) -> Self {
+    Self {
+      event,
+      window_id: crate::tao::render::window_id_key(window_id),
+      keyboard: None,
+      mouse: None,
+      wheel: None,
+    }
+  }
+
+  /// Converts a tao window event, or returns `None` for events without a [`WindowEvent`] kind.
+  fn from_tao(window_id: tao::window::WindowId, event: &tao::event::WindowEvent) -> Option<Self> {
+    use tao::event::WindowEvent as TaoWindowEvent;
+
+    let new = |kind| Self::new(kind, window_id);
+    Some(match event {
+      TaoWindowEvent::CloseRequested => new(WindowEvent::CloseRequested),
+      TaoWindowEvent::Destroyed => new(WindowEvent::Destroyed),
+      TaoWindowEvent::Focused(true) => new(WindowEvent::Focused),
+      TaoWindowEvent::Focused(false) => new(WindowEvent::Unfocused),
+      TaoWindowEvent::Moved(_) => new(WindowEvent::Moved),
+      TaoWindowEvent::Resized(_) => new(WindowEvent::Resized),
+      TaoWindowEvent::ScaleFactorChanged { .. } => new(WindowEvent::ScaleFactorChanged),
+      TaoWindowEvent::ThemeChanged(_) => new(WindowEvent::ThemeChanged),
+      TaoWindowEvent::KeyboardInput { event, .. } => Self {
+        keyboard: Some(KeyboardEvent::from_tao(event, window_modifiers(window_id))),
+        ..new(WindowEvent::KeyboardInput)
+      },
+      TaoWindowEvent::MouseInput { state, button, .. } => {
+        let pointer = window_pointer(window_id);
+        Self {
+          mouse: Some(MouseEvent {
+            button: Some(mouse_button(button)?),
+            state: Some(match state {
+              tao::event::ElementState::Pressed => MouseButtonState::Pressed,
+              _ => MouseButtonState::Released,
+            }),
+            position: pointer.position,
+            click_count: pointer.click_count,
+            modifiers: modifiers_list(window_modifiers(window_id)),
+          }),
+          ..new(WindowEvent::MouseInput)
+        }
+      }
+      TaoWindowEvent::CursorMoved { position, .. } => Self {
+        mouse: Some(MouseEvent {
+          button: None,
+          state: None,
+          position: Position {
+            x: position.x,
+            y: position.y,
+          },
+          click_count: 0,
+          modifiers: modifiers_list(window_modifiers(window_id)),
+        }),
+        ..new(WindowEvent::CursorMoved)
+      },
+      TaoWindowEvent::MouseWheel { delta, .. } => Self {
+        wheel: Some(WheelEvent::from_tao(delta, window_modifiers(window_id))?),
+        ..new(WindowEvent::MouseWheel)
+      },
+      _ => return None,
+    })
+  }
+}
+
+/// Callback receiving the window events of an event loop as [`WindowEventData`].
+pub type WindowEventCallback = ThreadsafeFunction<WindowEventData>;
+
+thread_local! {
+  /// Modifiers held in each window, from its last `ModifiersChanged` event.
+  static WINDOW_MODIFIERS: std::cell::RefCell<
+    std::collections::HashMap<u64, tao::keyboard::ModifiersState>,
+  > = std::cell::RefCell::new(std::collections::HashMap::new());
+}
+
+/// Gets the modifiers held in a window
+fn window_modifiers(window_id: tao::window::WindowId) -> tao::keyboard::ModifiersState {
+  let key = crate::tao::render::window_id_key(window_id);
+  WINDOW_MODIFIERS.with(|modifiers| modifiers.borrow().get(&key).copied().unwrap_or_default())
+}
+
+/// Records the modifiers held in a window, since tao's key events do not carry them.
+fn track_modifiers(window_id: tao::window::WindowId, event: &tao::event::WindowEvent) {
+  let key = crate::tao::render::window_id_key(window_id);
+  match event {
+    tao::event::WindowEvent::ModifiersChanged(state) => {
+      WINDOW_MODIFIERS.with(|modifiers| modifiers.borrow_mut().insert(key, *state));
+    }
+    tao::event::WindowEvent::Destroyed => {
+      WINDOW_MODIFIERS.with(|modifiers| modifiers.borrow_mut().remove(&key));
+    }
+    _ => {}
+  }
+}
+
+/// Last cursor position and click count of a window.
+#[derive(Debug, Default)]
+struct WindowPointer {
+  position: Position,
+  clicks: crate::tao::input::ClickCounter,
+  click_count: u16,
+}
+
+thread_local! {
+  /// Cursor state of each window, since tao's button events carry no position.
+  static WINDOW_POINTERS: std::cell::RefCell<std::collections::HashMap<u64, WindowPointer>> =
+    std::cell::RefCell::new(std::collections::HashMap::new());
+}
+
+/// Last cursor position and click count of a window, returned by [`window_pointer`].
+struct PointerSnapshot {
+  position: Position,
+  click_count: u16,
+}
+
+/// Gets where the cursor was last moved to in a window and the count of its last press
+fn window_pointer(window_id: tao::window::WindowId) -> PointerSnapshot {
+  let key = crate::tao::render::window_id_key(window_id);
+  WINDOW_POINTERS.with(|pointers| {
+    let pointers = pointers.borrow();
+    let pointer = pointers.get(&key);
+    PointerSnapshot {
+      position: pointer.map(|p| p.position).unwrap_or_default(),
+      click_count: pointer.map_or(0, |p| p.click_count),
+    }
+  })
+}
+
+/// Identifies a mouse button for the click counter.
+fn click_button_id(button: &tao::event::MouseButton) -> u16 {
+  match button {
+    tao::event::MouseButton::Left => 0,
+    tao::event::MouseButton::Middle => 1,
+    tao::event::MouseButton::Right => 2,
+    tao::event::MouseButton::Other(id) => id.saturating_add(3),
+    _ => u16::MAX,
+  }
+}
+
+/// Records the cursor position and counts the clicks in a window.
+fn track_pointer(window_id: tao::window::WindowId, event: &tao::event::WindowEvent) {
+  let key = crate::tao::render::window_id_key(window_id);
+  match event {
+    tao::event::WindowEvent::CursorMoved { position, .. } => {
+      WINDOW_POINTERS.with(|pointers| {
+        pointers.borrow_mut().entry(key).or_default().position = Position {
+          x: position.x,
+          y: position.y,
+        };
+      });
+    }
+    tao::event::WindowEvent::MouseInput {
+      state: tao::event::ElementState::Pressed,
+      button,
+      ..
+    } => {
+      WINDOW_POINTERS.with(|pointers| {
+        let mut pointers = pointers.borrow_mut();
+        let pointer = pointers.entry(key).or_default();
+        pointer.click_count = pointer.clicks.press(
+          click_button_id(button),
+          pointer.position.x,
+          pointer.position.y,
+          std::time::Instant::now(),
+        );
+      });
+    }
+    tao::event::WindowEvent::Destroyed => {
+      WINDOW_POINTERS.with(|pointers| pointers.borrow_mut().remove(&key));
+    }
+    _ => {}
+  }
+}
+
+/// An event delivered to an `EventLoop.runApp` handler.
+///
+/// `type` tells which event it is; only the fields documented for that type are set.
+#[napi(object, object_from_js = false)]
+pub struct AppEvent {
+  /// The event type.
+  #[napi(js_name = "type")]
+  pub event_type: AppEventType,
+  /// The window the event belongs to, as returned by `Window.id`.
+  pub window_id: Option<u64>,
+  /// Window position (`Moved`) or cursor position (`CursorMoved`) in physical pixels.
+  pub position: Option<Position>,
+  /// New inner size in physical pixels (`Resized`, `ScaleFactorChanged`).
+  pub size: Option<Size>,
+  /// New scale factor (`ScaleFactorChanged`).
+  pub scale_factor: Option<f64>,
+  /// New theme (`ThemeChanged`).
+  pub theme: Option<TaoTheme>,
+  /// Logical key, e.g. "a" or "Enter" (`KeyboardInput`).
+  pub key: Option<String>,
+  /// Physical key code, e.g. "KeyA" (`KeyboardInput`).
+  pub code: Option<String>,
+  /// Whether the key or button was pressed or released (`KeyboardInput`, `MouseInput`).
+  pub state: Option<MouseButtonState>,
+  /// Whether the key press is an auto-repeat (`KeyboardInput`).
+  pub repeat: Option<bool>,
+  /// Committed text (`ReceivedText`).
+  pub text: Option<String>,
+  /// Mouse button (`MouseInput`).
+  pub button: Option<MouseButton>,
+  /// Scroll amount in lines, or in pixels for precise touchpads (`MouseWheel`).
+  pub delta: Option<Position>,
+  /// File path (`DroppedFile`, `HoveredFile`).
+  pub path: Option<String>,
+  /// Touch point, including its phase and pressure (`Touch`).
+  pub touch: Option<Touch>,
+}
+
+impl AppEvent {
+  fn new(event_type: AppEventType, window_id: Option<tao::window::WindowId>) -> Self {
+    Self {
+      event_type,
+      window_id: window_id.map(crate::tao::render::window_id_key),
+      position: None,
+      size: None,
+      scale_factor: None,
+      theme: None,
+      key: None,
+      code: None,
+      state: None,
+      repeat: None,
+      text: None,
+      button: None,
+      delta: None,
+      path: None,
+      touch: None,
+    }
+  }
+
+  /// Converts a tao event, or returns `None` for events that are not forwarded.
+  pub(crate) fn from_tao(event: &tao::event::Event<'_, ()>) -> Option<Self> {
+    use tao::event::{Event, WindowEvent as TaoWindowEvent};
+
+    let (window_id, event) = match event {
+      Event::WindowEvent {
+        window_id, event, ..
+      } => (*window_id, event),
+      Event::Resumed => return Some(Self::new(AppEventType::Resumed, None)),
+      Event::Suspended => return Some(Self::new(AppEventType::Suspended, None)),
+      Event::MainEventsCleared => return Some(Self::new(AppEventType::AboutToWait, None)),
+      Event::LoopDestroyed => return Some(Self::new(AppEventType::Exiting, None)),
+      Event::RedrawRequested(window_id) => {
+        return Some(Self::new(AppEventType::RedrawRequested, Some(*window_id)))
+      }
+      _ => return None,
+    };
+
+    let state = |state: &tao::event::ElementState| match state {
+      tao::event::ElementState::Pressed => MouseButtonState::Pressed,
+      _ => MouseButtonState::Released,
+    };
+    let new = |event_type| Self::new(event_type, Some(window_id));
+
+    Some(match event {
+      TaoWindowEvent::CloseRequested => new(AppEventType::CloseRequested),
+      TaoWindowEvent::Destroyed => new(AppEventType::Destroyed),
+      TaoWindowEvent::Focused(true) => new(AppEventType::Focused),
+      TaoWindowEvent::Focused(false) => new(AppEventType::Unfocused),
+      TaoWindowEvent::Moved(position) => Self {
+        position: Some(Position {
+          x: position.x as f64,
+          y: position.y as f64,
+        }),
+        ..new(AppEventType::Moved)
+      },
+      TaoWindowEvent::Resized(size) => Self {
+        size: Some(Size {
+          width: size.width as f64,
+          height: size.height as f64,
+        }),
+        ..new(AppEventType::Resized)
+      },
+      TaoWindowEvent::ScaleFactorChanged {
+        scale_factor,
+        new_inner_size,
+      } => Self {
+        scale_factor: Some(*scale_factor),
+        size: Some(Size {
+          width: new_inner_size.width as f64,
+          height: new_inner_size.height as f64,
+        }),
+        ..new(AppEventType::ScaleFactorChanged)
+      },
+      TaoWindowEvent::ThemeChanged(theme) => Self {
+        theme: Some(match theme {
+          tao::window::Theme::Dark => TaoTheme::Dark,
+          _ => TaoTheme::Light,
+        }),
+        ..new(AppEventType::ThemeChanged)
+      },
+      TaoWindowEvent::KeyboardInput { event, .. } => Self {
+        key: Some(key_name(&event.logical_key)),
+        code: Some(event.physical_key.to_string()),
+        state: Some(state(&event.state)),
+        repeat: Some(event.repeat),
+        ..new(AppEventType::KeyboardInput)
+      },
+      TaoWindowEvent::ReceivedImeText(text) => Self {
+        text: Some(text.clone()),
+        ..new(AppEventType::ReceivedText)
+      },
+      TaoWindowEvent::CursorMoved { position, .. } => Self {
+        position: Some(Position {
+          x: position.x,
+          y: position.y,
+        }),
+        ..new(AppEventType::CursorMoved)
+      },
+      TaoWindowEvent::CursorEntered { .. } => new(AppEventType::CursorEntered),
+      TaoWindowEvent::CursorLeft { .. } => new(AppEventType::CursorLeft),
+      TaoWindowEvent::MouseInput {
+        state: button_state,
+        button,
+        ..
+      } => Self {
+        button: Some(mouse_button(button)?),
+        state: Some(state(button_state)),
+        ..new(AppEventType::MouseInput)
+      },
+      TaoWindowEvent::MouseWheel { delta, .. } => Self {
+        delta: Some(match delta {
+          tao::event::MouseScrollDelta::LineDelta(x, y) => Position {
+            x: *x as f64,
+            y: *y as f64,
+          },
+          tao::event::MouseScrollDelta::PixelDelta(delta) => Position {
+            x: delta.x,
+            y: delta.y,
+          },
+          _ => return None,
+        }),
+        ..new(AppEventType::MouseWheel)
+      },
+      TaoWindowEvent::Touch(touch) => Self {
+        touch: Some(Touch::from_tao(touch)),
+        ..new(AppEventType::Touch)
+      },
+      TaoWindowEvent::DroppedFile(path) => Self {
+        path: Some(path.to_string_lossy().into_owned()),
+        ..new(AppEventType::DroppedFile)
+      },
+      TaoWindowEvent::HoveredFile(path) => Self {
+        path: Some(path.to_string_lossy().into_owned()),
+        ..new(AppEventType::HoveredFile)
+      },
+      TaoWindowEvent::HoveredFileCancelled => new(AppEventType::HoveredFileCancelled),
+      _ => return None,
+    })
+  }
+}
+
+/// Handler receiving every [`AppEvent`] of an event loop.
+pub type AppEventHandler = ThreadsafeFunction<AppEvent>;
+
+/// Callback receiving every raw event of an event loop as a JSON string.
+pub type AnyEventCallback = ThreadsafeFunction<String>;
+
+/// Serializes any tao event for [`EventLoop::set_on_any_event`]
+///
+/// Common window events get their fields as JSON values; everything else falls
+/// back to [`debug_json`], so no event is dropped.
+pub(crate) fn event_to_json(event: &tao::event::Event<'_, ()>) -> String {
+  use tao::event::Event;
+
+  let value = match event {
+    Event::WindowEvent {
+      window_id, event, ..
+    } => serde_json::json!({
+      "type": "WindowEvent",
+      "windowId": crate::tao::render::window_id_key(*window_id),
+      "event": window_event_json(event),
+    }),
+    Event::DeviceEvent { event, .. } => serde_json::json!({
+      "type": "DeviceEvent",
+      "event": debug_json(event),
+    }),
+    Event::RedrawRequested(window_id) => serde_json::json!({
+      "type": "RedrawRequested",
+      "windowId": crate::tao::render::window_id_key(*window_id),
+    }),
+    _ => debug_json(event),
+  };
+  value.to_string()
+}
+
+/// Serializes a window event, see [`event_to_json`]
+fn window_event_json(event: &tao::event::WindowEvent<'_>) -> serde_json::Value {
+  use tao::event::WindowEvent as TaoWindowEvent;
+
+  match event {
+    TaoWindowEvent::Resized(size) => serde_json::json!({
+      "type": "Resized",
+      "width": size.width,
+      "height": size.height,
+    }),
+    TaoWindowEvent::Moved(position) => serde_json::json!({
+      "type": "Moved",
+      "x": position.x,
+      "y": position.y,
+    }),
+    TaoWindowEvent::Focused(focused) => serde_json::json!({
+      "type": "Focused",
+      "focused": focused,
+    }),
+    TaoWindowEvent::CursorMoved { position, .. } => serde_json::json!({
+      "type": "CursorMoved",
+      "x": position.x,
+      "y": position.y,
+    }),
+    TaoWindowEvent::MouseInput { state, button, .. } => serde_json::json!({
+      "type": "MouseInput",
+      "state": format!("{:?}", state),
+      "button": format!("{:?}", button),
+    }),
+    TaoWindowEvent::KeyboardInput { event, .. } => serde_json::json!({
+      "type": "KeyboardInput",
+      "key": format!("{:?}", event.logical_key),
+      "code": event.physical_key.to_string(),
+      "state": format!("{:?}", event.state),
+      "repeat": event.repeat,
+    }),
+    TaoWindowEvent::ScaleFactorChanged {
+      scale_factor,
+      new_inner_size,
+    } => serde_json::json!({
+      "type": "ScaleFactorChanged",
+      "scaleFactor": scale_factor,
+      "width": new_inner_size.width,
+      "height": new_inner_size.height,
+    }),
+    _ => debug_json(event),
+  }
+}
+
+/// Best-effort serialization of any value from its `Debug` output
+///
+/// `type` is the variant name, the text before the first `(`, `{` or space, and
+/// `debug` the whole `Debug` output.
+fn debug_json(value: &impl std::fmt::Debug) -> serde_json::Value {
+  let debug = format!("{:?}", value);
+  let name = debug
+    .split(|c: char| c == '(' || c == '{' || c.is_whitespace())
+    .next()
+    .unwrap_or_default();
+  serde_json::json!({
+    "type": name,
+    "debug": debug,
+  })
 }
 
 /// HiDPI scaling information.
@@ -266,6 +946,92 @@ pub struct OsError {
   pub message: String,
 }
 
+/// Status of errors thrown by platform-specific operations.
+///
+/// Exposed as the `code` of the thrown JS error, so callers can tell an operation
+/// that is unsupported on this platform (`"NotSupported"`) from one the OS failed
+/// to perform (`"OsError"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlatformErrorStatus {
+  /// The operation is not supported on this platform or backend.
+  NotSupported,
+  /// The OS failed to perform the operation.
+  OsError,
+  /// Any other failure.
+  GenericFailure,
+}
+
+impl AsRef<str> for PlatformErrorStatus {
+  fn as_ref(&self) -> &str {
+    match self {
+      PlatformErrorStatus::NotSupported => "NotSupported",
+      PlatformErrorStatus::OsError => "OsError",
+      PlatformErrorStatus::GenericFailure => "GenericFailure",
+    }
+  }
+}
+
+impl NotSupportedError {
+  pub(crate) fn new(message: impl Into<String>) -> Self {
+    Self {
+      message: message.into(),
+    }
+  }
+}
+
+impl From<NotSupportedError> for napi::Error<PlatformErrorStatus> {
+  fn from(error: NotSupportedError) -> Self {
+    napi::Error::new(PlatformErrorStatus::NotSupported, error.message)
+  }
+}
+
+impl From<OsError> for napi::Error<PlatformErrorStatus> {
+  fn from(error: OsError) -> Self {
+    napi::Error::new(
+      PlatformErrorStatus::OsError,
+      format!("OS error {}: {}", error.code, error.message),
+    )
+  }
+}
+
+impl From<tao::error::NotSupportedError> for NotSupportedError {
+  fn from(error: tao::error::NotSupportedError) -> Self {
+    Self::new(error.to_string())
+  }
+}
+
+impl From<tao::error::OsError> for OsError {
+  fn from(error: tao::error::OsError) -> Self {
+    Self {
+      code: os_error_code(&error.to_string()),
+      message: error.to_string(),
+    }
+  }
+}
+
+/// Converts a tao `ExternalError` into a structured platform error.
+fn external_error(error: tao::error::ExternalError) -> napi::Error<PlatformErrorStatus> {
+  match error {
+    tao::error::ExternalError::NotSupported(e) => NotSupportedError::from(e).into(),
+    tao::error::ExternalError::Os(e) => OsError::from(e).into(),
+    other => napi::Error::new(PlatformErrorStatus::GenericFailure, other.to_string()),
+  }
+}
+
+/// Extracts the OS error code from a tao `OsError` message, or -1 if it has none.
+///
+/// tao keeps the platform error private, so its variants are told apart by how
+/// they print: Windows `IoError`s end in `(os error N)`, macOS `CGError`s read
+/// `CGError N`, and Linux errors carry no code.
+fn os_error_code(message: &str) -> i32 {
+  let code = if let Some((_, rest)) = message.rsplit_once("(os error ") {
+    rest.strip_suffix(')')
+  } else {
+    message.rsplit_once("CGError ").map(|(_, code)| code)
+  };
+  code.and_then(|code| code.trim().parse().ok()).unwrap_or(-1)
+}
+
 /// Video mode information.
 #[napi(object)]
 pub struct VideoMode {
@@ -277,6 +1043,121 @@ pub struct VideoMode {
   pub refresh_rate: u32,
 }
 
+/// Fullscreen mode applied by [`Window::set_fullscreen`].
+#[napi(object)]
+pub struct FullscreenApplied {
+  /// The mode asked for, `null` to leave fullscreen.
+  pub requested: Option<TaoFullscreenType>,
+  /// The mode the window was put in: `Borderless` where exclusive fullscreen is
+  /// unavailable, `null` when not fullscreen.
+  pub actual: Option<TaoFullscreenType>,
+}
+
+/// Picks the video mode for exclusive fullscreen from `(width, height, bit depth,
+/// refresh rate)` entries; returns its index, or `None` if there are no modes.
+///
+/// Prefers the monitor's current resolution, so the switch is quick and the
+/// desktop is not rearranged, then the highest refresh rate and bit depth.
+fn pick_video_mode(modes: &[(u32, u32, u16, u16)], monitor_size: (u32, u32)) -> Option<usize> {
+  modes
+    .iter()
+    .enumerate()
+    .max_by_key(|(_, &(width, height, bit_depth, refresh_rate))| {
+      (
+        (width, height) == monitor_size,
+        width as u64 * height as u64,
+        refresh_rate,
+        bit_depth,
+      )
+    })
+    .map(|(index, _)| index)
+}
+
+/// Finds the entry of `modes` matching a requested video mode exactly.
+fn find_video_mode(modes: &[(u32, u32, u16, u16)], requested: &VideoMode) -> Option<usize> {
+  modes
+    .iter()
+    .position(|&(width, height, bit_depth, refresh_rate)| {
+      width as f64 == requested.size.width
+        && height as f64 == requested.size.height
+        && bit_depth == requested.bit_depth
+        && refresh_rate as u32 == requested.refresh_rate
+    })
+}
+
+/// Gets the video modes of a monitor as `(width, height, bit depth, refresh rate)`
+fn video_mode_keys(modes: &[tao::monitor::VideoMode]) -> Vec<(u32, u32, u16, u16)> {
+  modes
+    .iter()
+    .map(|mode| {
+      let size = mode.size();
+      (
+        size.width,
+        size.height,
+        mode.bit_depth(),
+        mode.refresh_rate(),
+      )
+    })
+    .collect()
+}
+
+/// Gets the video mode for exclusive fullscreen on a monitor: `requested` if
+/// given, else the one [`pick_video_mode`] prefers
+///
+/// Returns `None` where the monitor reports no video modes, e.g. on Linux, and
+/// fails if the monitor has modes but none matches `requested`.
+fn exclusive_video_mode(
+  monitor: &tao::monitor::MonitorHandle,
+  requested: Option<&VideoMode>,
+) -> Result<Option<tao::monitor::VideoMode>> {
+  let modes: Vec<_> = monitor.video_modes().collect();
+  if modes.is_empty() {
+    return match requested {
+      Some(requested) => Err(napi::Error::new(
+        napi::Status::InvalidArg,
+        format!(
+          "Video mode {} can't be applied: the monitor has no video modes (exclusive fullscreen is unavailable)",
+          video_mode_label(requested)
+        ),
+      )),
+      None => Ok(None),
+    };
+  }
+  let keys = video_mode_keys(&modes);
+  let index = match requested {
+    Some(requested) => Some(find_video_mode(&keys, requested).ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::InvalidArg,
+        format!(
+          "Video mode {} is not supported by the monitor",
+          video_mode_label(requested)
+        ),
+      )
+    })?),
+    None => {
+      let size = monitor.size();
+      pick_video_mode(&keys, (size.width, size.height))
+    }
+  };
+  Ok(index.and_then(|index| modes.into_iter().nth(index)))
+}
+
+/// Describes a video mode for error messages
+fn video_mode_label(mode: &VideoMode) -> String {
+  format!(
+    "{}x{} at {} Hz ({} bit)",
+    mode.size.width, mode.size.height, mode.refresh_rate, mode.bit_depth
+  )
+}
+
+/// Gets the fullscreen mode a window is in
+fn fullscreen_type(window: &tao::window::Window) -> Option<TaoFullscreenType> {
+  window.fullscreen().map(|fullscreen| match fullscreen {
+    tao::window::Fullscreen::Exclusive(_) => TaoFullscreenType::Exclusive,
+    _ => TaoFullscreenType::Borderless,
+  })
+}
+
 /// Window icon data.
 #[napi(object)]
 pub struct WindowIconData {
@@ -288,6 +1169,32 @@ pub struct WindowIconData {
   pub rgba: Buffer,
 }
 
+/// Decoded image data.
+#[napi(object)]
+pub struct DecodedImage {
+  /// The width of the image.
+  pub width: u32,
+  /// The height of the image.
+  pub height: u32,
+  /// The RGBA8 pixel data.
+  pub rgba: Buffer,
+}
+
+/// Build information and compiled-in capabilities.
+#[napi(object)]
+pub struct BuildInfo {
+  /// The version of the tao crate (the windowing library).
+  pub tao_version: String,
+  /// The version of the wry crate (the webview library).
+  pub wry_version: String,
+  /// The graphics backend used by the pixel renderer.
+  pub pixels_backend: String,
+  /// The optional features compiled into this build.
+  pub features: Vec<String>,
+  /// The target operating system (e.g. "windows", "macos", "linux").
+  pub target_os: String,
+}
+
 /// Window attributes.
 #[napi(object)]
 pub struct WindowAttributes {
@@ -307,6 +1214,10 @@ pub struct WindowAttributes {
   pub decorated: bool,
   /// Whether window is always on top.
   pub always_on_top: bool,
+  /// Whether window is always below other windows.
+  pub always_on_bottom: bool,
+  /// Steps of interactive resizing in logical pixels.
+  pub resize_increments: Option<Size>,
   /// Whether window is visible.
   pub visible: bool,
   /// Whether window is transparent.
@@ -321,6 +1232,57 @@ pub struct WindowAttributes {
   pub icon: Option<WindowIconData>,
   /// The theme of window.
   pub theme: Option<TaoTheme>,
+  /// Minimum and maximum inner sizes in physical pixels.
+  pub size_constraints: Option<WindowSizeConstraints>,
+}
+
+/// A snapshot of a window and renderer configuration for session restore.
+///
+/// Created with `Window.exportConfig` and turned back into a window with
+/// `applyConfig`. Sizes and positions are in logical pixels, so they carry over to
+/// monitors with a different scale factor.
+///
+/// ## Platform-specific
+///
+/// - **Wayland**: Clients cannot position their windows: `x` and `y` are exported
+///   as `null` and ignored on restore. `AlwaysOnBottom` is not restored either.
+/// - **Linux**: `opacity` and `transparent` require a compositing window manager.
+/// - **Exclusive fullscreen** is restored with the monitor's best video mode, or as
+///   borderless fullscreen where the platform has no video modes.
+#[napi(object)]
+pub struct WindowConfig {
+  /// The title of window.
+  pub title: String,
+  /// The inner width in logical pixels.
+  pub width: f64,
+  /// The inner height in logical pixels.
+  pub height: f64,
+  /// The outer X position in logical pixels, `null` where unknown.
+  pub x: Option<f64>,
+  /// The outer Y position in logical pixels, `null` where unknown.
+  pub y: Option<f64>,
+  /// Whether window is resizable.
+  pub resizable: bool,
+  /// Whether window has decorations.
+  pub decorated: bool,
+  /// Whether window is visible.
+  pub visible: bool,
+  /// Whether window is maximized.
+  pub maximized: bool,
+  /// The fullscreen mode, `null` when windowed.
+  pub fullscreen: Option<TaoFullscreenType>,
+  /// The window level.
+  pub level: WindowLevel,
+  /// The theme, `null` to follow the system theme.
+  pub theme: Option<TaoTheme>,
+  /// Whether window is transparent.
+  pub transparent: bool,
+  /// The window opacity, from 0.0 to 1.0.
+  pub opacity: f64,
+  /// The locked width / height ratio, `null` for none.
+  pub aspect_ratio: Option<f64>,
+  /// The renderer to recreate with the window, e.g. from `PixelRenderer.options`.
+  pub renderer: Option<crate::tao::render::RenderOptions>,
 }
 
 /// Progress bar data from Tao.
@@ -350,6 +1312,13 @@ pub struct EventLoop {
   pub(crate) inner: Option<tao::event_loop::EventLoop<()>>,
   #[allow(dead_code)]
   pub(crate) proxy: Option<tao::event_loop::EventLoopProxy<()>>,
+  pub(crate) app_handler: Option<Arc<AppEventHandler>>,
+  pub(crate) any_event_handler: Option<Arc<AnyEventCallback>>,
+  pub(crate) window_event_handler: Option<Arc<WindowEventCallback>>,
+  pub(crate) exit_on_last_window_closed: bool,
+  pub(crate) exit_requested: Arc<std::sync::atomic::AtomicBool>,
+  /// Timers of this loop, see [`EventLoop::set_timer`]
+  pub(crate) timers: std::rc::Rc<std::cell::RefCell<LoopTimers>>,
 }
 
 /// Global flag to track if an EventLoop has been created in this process.
@@ -363,12 +1332,9 @@ impl EventLoop {
   /// Creates a new event loop.
   #[napi(constructor)]
   pub fn new() -> Result<Self> {
-    // Force X11 backend on Linux before creating the event loop
-    // This must happen BEFORE the event loop is created to prevent Wayland protocol errors
-    #[cfg(target_os = "linux")]
-    {
-      let _ = crate::tao::platform::platform_info();
-    }
+    // Backend preferences (see `prefer_x11_backend`) must be applied BEFORE this point:
+    // the first event loop initializes GTK, which picks its backend only once
+    crate::tao::platform::mark_backend_initialized();
 
     // On Linux, GTK can only be initialized once per process.
     // Attempting to create a second EventLoop will cause a panic with:
@@ -378,6 +1344,10 @@ impl EventLoop {
     {
       use std::sync::atomic::Ordering;
       if EVENT_LOOP_CREATED.swap(true, Ordering::SeqCst) {
+        log_error!(
+          "event_loop",
+          "Refusing to create a second EventLoop: GTK can only be initialized once per process"
+        );
         return Err(napi::Error::new(
           napi::Status::GenericFailure,
           "Only one EventLoop can be created per process on Linux/GTK. \
@@ -389,26 +1359,44 @@ impl EventLoop {
 
     let event_loop = tao::event_loop::EventLoop::new();
     let proxy = event_loop.create_proxy();
+    crate::tao::render::thread::register_loop_proxy(&proxy);
     Ok(Self {
       inner: Some(event_loop),
       proxy: Some(proxy),
+      app_handler: None,
+      any_event_handler: None,
+      window_event_handler: None,
+      exit_on_last_window_closed: true,
+      exit_requested: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+      timers: Default::default(),
     })
   }
 
   /// Runs the event loop.
   #[napi]
   pub fn run(&mut self) -> Result<()> {
+    let app_handler = self.app_handler.clone();
+    let any_event_handler = self.any_event_handler.clone();
+    let window_event_handler = self.window_event_handler.clone();
+    let exit_on_close = self.exit_on_last_window_closed;
+    let exit_requested = self.exit_requested.clone();
+    // An `exit` of a previous run must not end this one
+    exit_requested.store(false, std::sync::atomic::Ordering::SeqCst);
+    let timers = self.timers.clone();
     if let Some(event_loop) = self.inner.take() {
-      event_loop.run(move |event, _, control_flow| {
-        *control_flow = tao::event_loop::ControlFlow::Wait;
-        if let tao::event::Event::WindowEvent {
-          event: tao::event::WindowEvent::CloseRequested,
-          ..
-        } = event
-        {
-          *control_flow = tao::event_loop::ControlFlow::Exit;
-        }
-      });
+      event_loop.run(blocking_loop(
+        Some(timers),
+        move || exit_requested.load(std::sync::atomic::Ordering::SeqCst),
+        move |event, _| {
+          dispatch_loop_event(
+            event,
+            any_event_handler.as_deref(),
+            window_event_handler.as_deref(),
+            app_handler.as_deref(),
+            exit_on_close,
+          )
+        },
+      ));
     }
     Ok(())
   }
@@ -416,7 +1404,13 @@ impl EventLoop {
   /// Runs a single iteration of the event loop.
   #[napi]
   pub fn run_iteration(&mut self) -> Result<bool> {
+    use std::sync::atomic::Ordering;
+
+    if self.exit_requested.load(Ordering::SeqCst) {
+      return Ok(false);
+    }
     let mut keep_running = true;
+    let exit_on_close = self.exit_on_last_window_closed;
     if let Some(event_loop) = &mut self.inner {
       #[cfg(any(
         target_os = "linux",
@@ -429,16 +1423,27 @@ impl EventLoop {
       ))]
       {
         use tao::platform::run_return::EventLoopExtRunReturn;
+        let app_handler = self.app_handler.as_deref();
+        let any_event_handler = self.any_event_handler.as_deref();
+        let window_event_handler = self.window_event_handler.as_deref();
         event_loop.run_return(|event, _, control_flow| {
           *control_flow = tao::event_loop::ControlFlow::Poll;
+          dispatch_internal_event(&event);
+          dispatch_any_event(any_event_handler, &event);
+          dispatch_window_event(window_event_handler, &event);
+          dispatch_app_event(app_handler, &event);
           match event {
             tao::event::Event::WindowEvent {
+              window_id,
               event: tao::event::WindowEvent::CloseRequested,
               ..
-            } => {
+            } if exit_on_close && is_last_open_window(window_id) => {
               keep_running = false;
               *control_flow = tao::event_loop::ControlFlow::Exit;
             }
+            tao::event::Event::MainEventsCleared => {
+              run_idle_tasks(Some(&self.timers));
+            }
             tao::event::Event::RedrawEventsCleared => {
               *control_flow = tao::event_loop::ControlFlow::Exit;
             }
@@ -450,40 +1455,366 @@ impl EventLoop {
     Ok(keep_running)
   }
 
-  /// Creates an event loop proxy.
+  /// Runs the event loop, delivering every event to a single handler.
+  ///
+  /// A single dispatch point alternative to the granular `on*` callbacks, in the
+  /// shape of winit's `ApplicationHandler`: each call receives an [`AppEvent`]
+  /// whose `type` tells which payload fields are set. Like `run`, this blocks and
+  /// exits when the last open window requests to close (after delivering
+  /// `CloseRequested`).
   #[napi]
-  pub fn create_proxy(&self) -> Result<EventLoopProxy> {
-    Ok(EventLoopProxy {
-      inner: self.proxy.clone(),
-    })
+  pub fn run_app(&mut self, handler: AppEventHandler) -> Result<()> {
+    self.app_handler = Some(Arc::new(handler));
+    self.run()
   }
-}
 
-/// Builder for creating event loops.
-#[napi]
-pub struct EventLoopBuilder {
-  inner: Option<tao::event_loop::EventLoopBuilder<()>>,
-}
+  /// Runs the event loop until it exits, then returns so it can be run again.
+  ///
+  /// Unlike `run`, which consumes the loop, this suits modal loops: show a dialog
+  /// window, run until it closes, then return to the main loop. The loop ends on
+  /// `exit`, which `handler` may call, or, with `exitOnLastWindowClosed`, on a close
+  /// request of the last open window; the exit is then reset for the loop this one
+  /// was started from. `handler` receives every event of this run as an
+  /// [`AppEvent`], in place of the handler set with `setAppEventHandler`; the
+  /// other callbacks are called as usual.
+  ///
+  /// `handler` is called synchronously on this thread, which the loop blocks: the
+  /// other callbacks, timers and promises of JS only run after this returns, so
+  /// `handler` is where the run decides to exit. Throws what `handler` throws,
+  /// after stopping the loop.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS / Android**: Unsupported. The macOS event loop historically
+  ///   can't be re-entered once started.
+  #[napi]
+  pub fn run_on_demand(
+    &mut self,
+    env: &Env,
+    handler: Function<AppEvent>,
+  ) -> napi::Result<(), PlatformErrorStatus> {
+    self.run_nested(env, None, Some(&handler)).map(|_| ())
+  }
 
-#[napi]
-impl EventLoopBuilder {
-  /// Creates a new event loop builder.
-  #[napi(constructor)]
-  pub fn new() -> Result<Self> {
-    Ok(Self {
-      inner: Some(tao::event_loop::EventLoopBuilder::new()),
-    })
+  /// Like `runOnDemand`, but also returns once `done` returns `true`.
+  ///
+  /// `done` is called synchronously before the loop starts and after each batch of
+  /// events, after `handler`, if given, received them. So it must check what
+  /// `handler` records, e.g. a `CloseRequested` of the dialog, or native state
+  /// such as `Window.isVisible`.
+  ///
+  /// Returns `true` once `done` did, or `false` if the loop exited first. Throws
+  /// what `done` or `handler` throws, after stopping the loop.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS / Android**: Unsupported, see `runOnDemand`.
+  #[napi]
+  pub fn run_until(
+    &mut self,
+    env: &Env,
+    done: Function<(), bool>,
+    handler: Option<Function<AppEvent>>,
+  ) -> napi::Result<bool, PlatformErrorStatus> {
+    self.run_nested(env, Some(&done), handler.as_ref())
   }
 
-  /// Builds the event loop.
+  /// Sets a handler receiving every event as an [`AppEvent`], or clears it.
+  ///
+  /// The non-blocking counterpart of `run_app`: the handler is called from
+  /// `run_iteration` and `process_pending` as well as `run`.
   #[napi]
-  pub fn build(&mut self) -> Result<EventLoop> {
-    // Force X11 backend on Linux before creating the event loop
-    // This must happen BEFORE the event loop is created to prevent Wayland protocol errors
-    #[cfg(target_os = "linux")]
+  pub fn set_app_event_handler(&mut self, handler: Option<AppEventHandler>) {
+    self.app_handler = handler.map(Arc::new);
+  }
+
+  /// Sets whether closing the last open window ends the loop (default: true).
+  ///
+  /// With `true`, a close request ends the loop once every other window built on
+  /// it was closed with `Window.close` or dropped; close the other windows from
+  /// their `CloseRequested` handler to let the last one end the loop.
+  ///
+  /// With `false`, `run` keeps running and `run_iteration` keeps returning `true`
+  /// after a close request, even with no window left, e.g. for tray-only and
+  /// background apps. The window is not closed by the request either: hide or drop
+  /// it from a `CloseRequested` handler. The app must then provide another way to
+  /// quit, such as a tray menu item calling [`EventLoop::exit`].
+  #[napi]
+  pub fn set_exit_on_last_window_closed(&mut self, exit: bool) {
+    self.exit_on_last_window_closed = exit;
+  }
+
+  /// Ends the event loop: `run` returns and `run_iteration` returns `false`.
+  ///
+  /// Wakes up a waiting `run`, so the loop ends right away rather than at the next
+  /// event.
+  #[napi]
+  pub fn exit(&self) {
+    self
+      .exit_requested
+      .store(true, std::sync::atomic::Ordering::SeqCst);
+    if let Some(proxy) = &self.proxy {
+      let _ = proxy.send_event(());
+    }
+  }
+
+  /// Sets a callback receiving every event as a JSON string, or clears it.
+  ///
+  /// A diagnostic firehose for logging and debugging, separate from the typed
+  /// callbacks: every event of `run`, `run_iteration` and `process_pending` is
+  /// delivered, including the ones no typed callback forwards. Each string is an
+  /// object with a `type`; window events carry a `windowId` and the window event
+  /// in `event`. Common events have their fields as JSON values, the others a
+  /// `debug` string with a best-effort dump of their fields. The format is meant
+  /// for people, not for parsing: it may change between versions.
+  #[napi]
+  pub fn set_on_any_event(&mut self, callback: Option<AnyEventCallback>) {
+    self.any_event_handler = callback.map(Arc::new);
+  }
+
+  /// Sets a callback receiving the window events as [`WindowEventData`], or clears it.
+  ///
+  /// Called from `run`, `run_iteration` and `process_pending` for the window
+  /// events with a [`WindowEvent`] kind; `KeyboardInput` events carry the key,
+  /// its code and the modifiers held in `keyboard`.
+  #[napi]
+  pub fn set_on_window_event(&mut self, callback: Option<WindowEventCallback>) {
+    self.window_event_handler = callback.map(Arc::new);
+  }
+
+  /// Sets the double-click time and movement tolerance used for `clickCount`.
+  ///
+  /// A press counts as a repeat click when it uses the same button, comes within
+  /// `ms` milliseconds of the previous press and lies within `tolerance_px` pixels
+  /// of the first press. `clickCount` is 1 for a single click and escalates by one
+  /// for each repeat: 2 for a double-click, 3 for a triple-click, and so on.
+  ///
+  /// Defaults to platform-typical values (400 ms on Linux, 500 ms elsewhere; 4 px
+  /// on Windows, 5 px elsewhere). The setting is process-wide.
+  #[napi]
+  pub fn set_double_click(&self, ms: u32, tolerance_px: u32) {
+    crate::tao::input::set_double_click(ms, tolerance_px);
+  }
+
+  /// Processes the events that are queued right now without waiting for new ones.
+  ///
+  /// Intended for embedding in another main loop (e.g. a game loop): call it once per
+  /// frame. Returns the number of window, device and user events dispatched; loop
+  /// bookkeeping events such as `NewEvents` or `MainEventsCleared` are not counted.
+  #[napi]
+  pub fn process_pending(&mut self) -> Result<u32> {
+    let mut count = 0u32;
+    if let Some(event_loop) = &mut self.inner {
+      #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "windows",
+        target_os = "macos",
+      ))]
+      {
+        use tao::platform::run_return::EventLoopExtRunReturn;
+        let app_handler = self.app_handler.as_deref();
+        let any_event_handler = self.any_event_handler.as_deref();
+        let window_event_handler = self.window_event_handler.as_deref();
+        event_loop.run_return(|event, _, control_flow| {
+          // Poll never blocks, so this behaves like a pump with a zero timeout
+          *control_flow = tao::event_loop::ControlFlow::Poll;
+          dispatch_internal_event(&event);
+          dispatch_any_event(any_event_handler, &event);
+          dispatch_window_event(window_event_handler, &event);
+          dispatch_app_event(app_handler, &event);
+          match event {
+            tao::event::Event::WindowEvent { .. }
+            | tao::event::Event::DeviceEvent { .. }
+            | tao::event::Event::UserEvent(_)
+            | tao::event::Event::RedrawRequested(_) => {
+              count += 1;
+            }
+            tao::event::Event::MainEventsCleared => {
+              run_idle_tasks(Some(&self.timers));
+            }
+            tao::event::Event::RedrawEventsCleared => {
+              *control_flow = tao::event_loop::ControlFlow::Exit;
+            }
+            _ => {}
+          }
+        });
+      }
+    }
+    Ok(count)
+  }
+
+  /// Starts a timer that calls the `on_timer` callback with `id` after `interval_ms`.
+  ///
+  /// A repeating timer fires every `interval_ms` until cleared; intervals missed
+  /// while the loop was busy are skipped rather than fired in a burst. Setting an
+  /// existing `id` restarts it with the new interval. Ids and timers belong to
+  /// this event loop.
+  ///
+  /// Timers are driven from the loop's idle point: `run` sleeps with a deadline
+  /// until the next timer is due instead of polling, so idle animations cost no
+  /// CPU between frames. With `run_iteration` and `process_pending`, due timers
+  /// fire on the next call.
+  #[napi]
+  pub fn set_timer(&self, id: u32, interval_ms: u32, repeat: bool) {
+    let interval = std::time::Duration::from_millis(interval_ms.max(1) as u64);
+    let timer = TimerEntry {
+      id,
+      interval,
+      next: std::time::Instant::now() + interval,
+      repeat,
+    };
+    let entries = &mut self.timers.borrow_mut().entries;
+    entries.retain(|timer| timer.id != id);
+    entries.push(timer);
+  }
+
+  /// Stops a timer; returns whether it was running.
+  #[napi]
+  pub fn clear_timer(&self, id: u32) -> bool {
+    let entries = &mut self.timers.borrow_mut().entries;
+    let count = entries.len();
+    entries.retain(|timer| timer.id != id);
+    entries.len() != count
+  }
+
+  /// Sets the callback receiving the id of each elapsed timer, or clears it.
+  #[napi]
+  pub fn on_timer(&self, callback: Option<TimerCallback>) {
+    self.timers.borrow_mut().callback = callback;
+  }
+
+  /// Creates an event loop proxy.
+  #[napi]
+  pub fn create_proxy(&self) -> Result<EventLoopProxy> {
+    Ok(EventLoopProxy {
+      inner: self.proxy.clone(),
+    })
+  }
+}
+
+impl EventLoop {
+  /// Runs a nested loop for `run_on_demand` and `run_until`
+  ///
+  /// Returns whether `done` ended the loop, rather than an exit.
+  fn run_nested(
+    &mut self,
+    env: &Env,
+    done: Option<&Function<(), bool>>,
+    handler: Option<&Function<AppEvent>>,
+  ) -> napi::Result<bool, PlatformErrorStatus> {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+      target_os = "windows",
+    ))]
+    {
+      use std::sync::atomic::Ordering;
+      use tao::platform::run_return::EventLoopExtRunReturn;
+
+      let failed = |e: napi::Error| napi::Error::new(PlatformErrorStatus::GenericFailure, e.reason);
+      // Each call gets its own handle scope, or the handles of a long run pile up
+      let is_done = || match done {
+        Some(done) => env.run_in_scope(|| done.call(())),
+        None => Ok(false),
+      };
+      let deliver = |event: &tao::event::Event<'_, ()>| match (handler, AppEvent::from_tao(event)) {
+        (Some(handler), Some(app_event)) => {
+          env.run_in_scope(|| handler.call(app_event).map(|_| ()))
+        }
+        _ => Ok(()),
+      };
+      if is_done().map_err(failed)? {
+        return Ok(true);
+      }
+
+      let exit_on_close = self.exit_on_last_window_closed;
+      let exit_requested = self.exit_requested.clone();
+      exit_requested.store(false, Ordering::SeqCst);
+      let any_event_handler = self.any_event_handler.clone();
+      let window_event_handler = self.window_event_handler.clone();
+      let event_loop = self.inner.as_mut().ok_or_else(|| {
+        napi::Error::new(
+          PlatformErrorStatus::GenericFailure,
+          "Event loop already running or consumed".to_string(),
+        )
+      })?;
+      let mut outcome = Ok(false);
+      event_loop.run_return(blocking_loop(
+        Some(self.timers.clone()),
+        || exit_requested.load(Ordering::SeqCst),
+        |event, _| {
+          let exits = dispatch_loop_event(
+            event,
+            any_event_handler.as_deref(),
+            window_event_handler.as_deref(),
+            None,
+            exit_on_close,
+          );
+          let called = deliver(event).and_then(|()| match event {
+            tao::event::Event::MainEventsCleared => is_done(),
+            _ => Ok(false),
+          });
+          match called {
+            Ok(false) => exits,
+            ended => {
+              outcome = ended;
+              true
+            }
+          }
+        },
+      ));
+      // Let the loop this one was started from go on
+      exit_requested.store(false, Ordering::SeqCst);
+      outcome.map_err(failed)
+    }
+
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+      target_os = "windows",
+    )))]
     {
-      let _ = crate::tao::platform::platform_info();
+      let _ = (env, done, handler);
+      Err(
+        NotSupportedError::new("Re-entering the event loop is not supported on this platform")
+          .into(),
+      )
     }
+  }
+}
+
+/// Builder for creating event loops.
+#[napi]
+pub struct EventLoopBuilder {
+  inner: Option<tao::event_loop::EventLoopBuilder<()>>,
+}
+
+#[napi]
+impl EventLoopBuilder {
+  /// Creates a new event loop builder.
+  #[napi(constructor)]
+  pub fn new() -> Result<Self> {
+    Ok(Self {
+      inner: Some(tao::event_loop::EventLoopBuilder::new()),
+    })
+  }
+
+  /// Builds the event loop.
+  #[napi]
+  pub fn build(&mut self) -> Result<EventLoop> {
+    // Backend preferences (see `prefer_x11_backend`) must be applied BEFORE this point:
+    // the first event loop initializes GTK, which picks its backend only once
+    crate::tao::platform::mark_backend_initialized();
 
     // Handle backend selection BEFORE creating the event loop
     // This ensures the environment is set up correctly before tao selects the backend
@@ -498,9 +1829,16 @@ impl EventLoopBuilder {
       })?
       .build();
     let proxy = event_loop.create_proxy();
+    crate::tao::render::thread::register_loop_proxy(&proxy);
     Ok(EventLoop {
       inner: Some(event_loop),
       proxy: Some(proxy),
+      app_handler: None,
+      any_event_handler: None,
+      window_event_handler: None,
+      exit_on_last_window_closed: true,
+      exit_requested: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+      timers: Default::default(),
     })
   }
 }
@@ -547,6 +1885,33 @@ pub struct Window {
   pub(crate) inner: Option<Arc<Mutex<tao::window::Window>>>,
 }
 
+/// Releases the window's render cache entries.
+///
+/// napi drops the native object when the JS wrapper is finalized, so this runs on GC.
+/// Only the last handle to a window cleans up; clones returned by e.g. `App.window()`
+/// share the same underlying window.
+impl Drop for Window {
+  fn drop(&mut self) {
+    if let Some(inner) = &self.inner {
+      if Arc::strong_count(inner) == 1 {
+        if let Ok(window) = inner.lock() {
+          forget_window(crate::tao::render::render_state_key(&window));
+          #[cfg(target_os = "windows")]
+          forget_snap_button_rect(&window);
+        }
+      }
+    }
+  }
+}
+
+/// Releases the render surface and forgets the tracked state of a window
+/// whose last handle is dropped
+fn forget_window(key: u64) {
+  crate::tao::render::release_render_state(key);
+  crate::tao::render::remove_alpha_hittest(key);
+  WINDOW_STATE.lock().unwrap().remove(&key);
+}
+
 #[napi]
 impl Window {
   /// Creates a new window with default attributes.
@@ -607,7 +1972,15 @@ impl Window {
   #[napi]
   pub fn set_visible(&self, visible: bool) -> Result<()> {
     if let Some(inner) = &self.inner {
-      inner.lock().unwrap().set_visible(visible);
+      let window = inner.lock().unwrap();
+      if visible && tracked_window_state(&window).closed {
+        return Ok(());
+      }
+      window.set_visible(visible);
+      // Some platforms reset the input state when the window is mapped again
+      if visible && tracked_window_state(&window).ignore_cursor_events {
+        let _ = window.set_ignore_cursor_events(true);
+      }
     }
     Ok(())
   }
@@ -642,14 +2015,184 @@ impl Window {
   }
 
   /// Sets whether the window is decorated.
+  ///
+  /// The inner size and the outer position are kept: adding or removing the
+  /// title bar and borders would otherwise shrink or grow the content area and
+  /// move the window. Maximized and fullscreen windows keep the geometry the
+  /// window system gives them.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / Linux**: The size and position are requested again after the
+  ///   change and applied asynchronously, so a `Resized` event may still arrive.
+  /// - **Wayland**: Windows cannot be positioned; only the size is kept.
   #[napi]
   pub fn set_decorated(&self, decorated: bool) -> Result<()> {
     if let Some(inner) = &self.inner {
-      inner.lock().unwrap().set_decorations(decorated);
+      let window = inner.lock().unwrap();
+      if window.is_decorated() == decorated {
+        return Ok(());
+      }
+      let keep_geometry = !window.is_maximized() && window.fullscreen().is_none();
+      let inner_size = window.inner_size();
+      let outer_position = window.outer_position().ok();
+      window.set_decorations(decorated);
+      if keep_geometry {
+        window.set_inner_size(inner_size);
+        if let Some(position) = outer_position {
+          window.set_outer_position(position);
+        }
+      }
     }
     Ok(())
   }
 
+  /// Puts the window in fullscreen on a monitor, or takes it out with `null`.
+  ///
+  /// `monitor` is an index into `availableMonitors`; by default the monitor the
+  /// window is on is used. Exclusive fullscreen switches the monitor to
+  /// `videoMode`, one of its `videoModes`, or by default to its video mode at the
+  /// current resolution with the highest refresh rate. Where the monitor has no
+  /// video modes to switch to, or the window's monitor is unknown, it falls back
+  /// to borderless with a warning: the result tells which mode was applied.
+  ///
+  /// Throws if `monitor` is out of range, or `videoMode` is given but can't be
+  /// applied: with borderless fullscreen, or when the monitor doesn't support it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Exclusive fullscreen is unavailable (X11 and Wayland), so it is
+  ///   always borderless.
+  #[napi]
+  pub fn set_fullscreen(
+    &self,
+    mode: Option<TaoFullscreenType>,
+    monitor: Option<u32>,
+    video_mode: Option<VideoMode>,
+  ) -> Result<FullscreenApplied> {
+    let applied = |actual| FullscreenApplied {
+      requested: mode,
+      actual,
+    };
+    let Some(inner) = &self.inner else {
+      return Ok(applied(None));
+    };
+    let window = inner.lock().unwrap();
+    let Some(mode) = mode else {
+      window.set_fullscreen(None);
+      return Ok(applied(None));
+    };
+
+    let monitor = match monitor {
+      Some(index) => Some(monitor_at(&window, index).ok_or_else(|| {
+        napi::Error::new(
+          napi::Status::InvalidArg,
+          format!("Monitor index {} is out of range", index),
+        )
+      })?),
+      None => window.current_monitor(),
+    };
+    let exclusive = match (mode, &monitor) {
+      (TaoFullscreenType::Borderless, _) => match &video_mode {
+        Some(video_mode) => {
+          return Err(napi::Error::new(
+            napi::Status::InvalidArg,
+            format!(
+              "Video mode {} requires exclusive fullscreen",
+              video_mode_label(video_mode)
+            ),
+          ))
+        }
+        None => None,
+      },
+      (TaoFullscreenType::Exclusive, Some(monitor)) => {
+        let exclusive = exclusive_video_mode(monitor, video_mode.as_ref())?;
+        if exclusive.is_none() {
+          log_warn!(
+            "window",
+            "The monitor has no video modes to switch to (e.g. on Linux); using borderless fullscreen"
+          );
+        }
+        exclusive
+      }
+      (TaoFullscreenType::Exclusive, None) => {
+        if let Some(video_mode) = &video_mode {
+          return Err(napi::Error::new(
+            napi::Status::InvalidArg,
+            format!(
+              "Video mode {} can't be applied: the window's monitor is unknown",
+              video_mode_label(video_mode)
+            ),
+          ));
+        }
+        log_warn!(
+          "window",
+          "The window's monitor is unknown, so it can't switch video modes; using borderless fullscreen"
+        );
+        None
+      }
+    };
+    let actual = match exclusive {
+      Some(video_mode) => {
+        window.set_fullscreen(Some(tao::window::Fullscreen::Exclusive(video_mode)));
+        TaoFullscreenType::Exclusive
+      }
+      None => {
+        window.set_fullscreen(Some(tao::window::Fullscreen::Borderless(monitor)));
+        TaoFullscreenType::Borderless
+      }
+    };
+    Ok(applied(Some(actual)))
+  }
+
+  /// Gets whether the window is fullscreen, borderless or exclusive.
+  #[napi]
+  pub fn is_fullscreen(&self) -> bool {
+    self
+      .inner
+      .as_ref()
+      .is_some_and(|inner| fullscreen_type(&inner.lock().unwrap()).is_some())
+  }
+
+  /// Gets the video modes exclusive fullscreen can switch a monitor to.
+  ///
+  /// `monitor` is an index into `availableMonitors`; by default the monitor the
+  /// window is on is used. Empty where exclusive fullscreen is unavailable.
+  ///
+  /// Throws if `monitor` is out of range.
+  #[napi]
+  pub fn video_modes(&self, monitor: Option<u32>) -> Result<Vec<VideoMode>> {
+    let Some(inner) = &self.inner else {
+      return Ok(Vec::new());
+    };
+    let window = inner.lock().unwrap();
+    let monitor = match monitor {
+      Some(index) => Some(monitor_at(&window, index).ok_or_else(|| {
+        napi::Error::new(
+          napi::Status::InvalidArg,
+          format!("Monitor index {} is out of range", index),
+        )
+      })?),
+      None => window.current_monitor(),
+    };
+    let modes: Vec<_> = monitor
+      .map(|monitor| monitor.video_modes().collect())
+      .unwrap_or_default();
+    Ok(
+      video_mode_keys(&modes)
+        .into_iter()
+        .map(|(width, height, bit_depth, refresh_rate)| VideoMode {
+          size: Size {
+            width: width as f64,
+            height: height as f64,
+          },
+          bit_depth,
+          refresh_rate: refresh_rate as u32,
+        })
+        .collect(),
+    )
+  }
+
   /// Gets the window position.
   #[napi]
   pub fn outer_position(&self) -> Result<Position> {
@@ -669,13 +2212,19 @@ impl Window {
   }
 
   /// Sets the window position.
+  ///
+  /// Throws with code `"NotSupported"` on Wayland, where clients cannot position
+  /// their windows.
   #[napi]
-  pub fn set_outer_position(&self, x: f64, y: f64) -> Result<()> {
+  pub fn set_outer_position(&self, x: f64, y: f64) -> napi::Result<(), PlatformErrorStatus> {
     if let Some(inner) = &self.inner {
-      inner
-        .lock()
-        .unwrap()
-        .set_outer_position(tao::dpi::PhysicalPosition::new(x as i32, y as i32));
+      let window = inner.lock().unwrap();
+      if crate::tao::platform::is_wayland_window(&window) {
+        return Err(
+          NotSupportedError::new("Window positioning is not supported on Wayland").into(),
+        );
+      }
+      window.set_outer_position(tao::dpi::PhysicalPosition::new(x as i32, y as i32));
     }
     Ok(())
   }
@@ -697,18 +2246,217 @@ impl Window {
     }
   }
 
-  /// Sets the window size.
+  /// Gets the outer window size, including the title bar and borders.
+  ///
+  /// The difference to `innerSize` is the thickness of the decorations.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Wayland**: Decorations drawn by the compositor are not included.
   #[napi]
-  pub fn set_inner_size(&self, width: f64, height: f64) -> Result<()> {
+  pub fn outer_size(&self) -> Result<Size> {
     if let Some(inner) = &self.inner {
-      inner
-        .lock()
-        .unwrap()
-        .set_inner_size(tao::dpi::PhysicalSize::new(width as u32, height as u32));
+      let size = inner.lock().unwrap().outer_size();
+      Ok(Size {
+        width: size.width as f64,
+        height: size.height as f64,
+      })
+    } else {
+      Ok(Size {
+        width: 800.0,
+        height: 600.0,
+      })
+    }
+  }
+
+  /// Requests a new window size and returns the size that was granted.
+  ///
+  /// The window system may grant a different size than requested (size
+  /// constraints, tiling window managers, the Wayland compositor). Returns the
+  /// resulting size when the resize is applied immediately, or `null` when it is
+  /// asynchronous and the granted size arrives later with a `Resized` event.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Applied immediately, the granted size is returned.
+  /// - **macOS / Linux**: Asynchronous, always returns `null`. On Wayland the
+  ///   compositor decides the final size, which may differ from the request.
+  #[napi]
+  pub fn set_inner_size(&self, width: f64, height: f64) -> Result<Option<Size>> {
+    if let Some(inner) = &self.inner {
+      let window = inner.lock().unwrap();
+      request_inner_size_with_aspect_ratio(&window, width, height);
+      if cfg!(target_os = "windows") {
+        let size = window.inner_size();
+        return Ok(Some(Size {
+          width: size.width as f64,
+          height: size.height as f64,
+        }));
+      }
+    }
+    Ok(None)
+  }
+
+  /// Sets the minimum inner size in logical pixels, i.e. excluding decorations.
+  ///
+  /// Pass 0 for a dimension to leave it unlimited, or for both to remove the
+  /// minimum. Only user resizing is limited; the window is not resized if it is
+  /// currently smaller.
+  #[napi]
+  pub fn set_min_size_logical(&self, width: f64, height: f64) {
+    if let Some(inner) = &self.inner {
+      let window = inner.lock().unwrap();
+      let min_size = (
+        (width > 0.0).then_some(width),
+        (height > 0.0).then_some(height),
+      );
+      apply_size_limits(&window, min_size, tracked_window_state(&window).max_size);
+    }
+  }
+
+  /// Sets the minimum inner size in physical pixels, or removes it with `null`.
+  ///
+  /// A minimum larger than the maximum set with `set_max_inner_size` is clamped
+  /// to it, with a warning.
+  #[napi]
+  pub fn set_min_inner_size(&self, size: Option<Size>) {
+    if let Some(inner) = &self.inner {
+      let window = inner.lock().unwrap();
+      let max_size = tracked_window_state(&window).max_size;
+      let min_size = logical_size_limit(&window, physical_size_limit(size), max_size, true);
+      apply_size_limits(&window, min_size, max_size);
+    }
+  }
+
+  /// Sets the maximum inner size in physical pixels, or removes it with `null`.
+  ///
+  /// A maximum smaller than the minimum is clamped to it, with a warning. Only
+  /// user resizing is limited; the window is not resized if it is larger.
+  #[napi]
+  pub fn set_max_inner_size(&self, size: Option<Size>) {
+    if let Some(inner) = &self.inner {
+      let window = inner.lock().unwrap();
+      let min_size = tracked_window_state(&window).min_size;
+      let max_size = logical_size_limit(&window, physical_size_limit(size), min_size, false);
+      apply_size_limits(&window, min_size, max_size);
+    }
+  }
+
+  /// Requests a new window size in physical pixels and returns the size requested.
+  ///
+  /// With an aspect ratio set by `set_aspect_ratio`, the size is first snapped
+  /// to the largest size of that ratio fitting in `width` x `height`, so the
+  /// returned size always conforms. The window system may still grant another
+  /// size, as with `set_inner_size`.
+  #[napi]
+  pub fn request_inner_size(&self, width: f64, height: f64) -> Size {
+    let (width, height) = match &self.inner {
+      Some(inner) => request_inner_size_with_aspect_ratio(&inner.lock().unwrap(), width, height),
+      None => (width, height),
+    };
+    Size { width, height }
+  }
+
+  /// Locks the inner size to an aspect ratio (width / height), or unlocks it with `null`.
+  ///
+  /// The window is resized at once to the largest conforming size within its
+  /// current size. Later `set_inner_size` and `request_inner_size` calls are
+  /// snapped the same way, and interactive resizes are corrected to the ratio
+  /// following the edge the user drags.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: The ratio is also given to the window manager as a geometry
+  ///   hint, so most window managers keep it while resizing.
+  #[napi]
+  pub fn set_aspect_ratio(&self, ratio: Option<f64>) -> Result<()> {
+    if ratio.is_some_and(|ratio| !(ratio.is_finite() && ratio > 0.0)) {
+      return Err(napi::Error::new(
+        napi::Status::InvalidArg,
+        "Aspect ratio must be a positive number".to_string(),
+      ));
+    }
+    let Some(inner) = &self.inner else {
+      return Ok(());
+    };
+    let window = inner.lock().unwrap();
+    update_tracked_window_state(&window, |state| state.aspect_ratio = ratio);
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    ))]
+    {
+      use tao::platform::unix::WindowExtUnix;
+
+      let state = tracked_window_state(&window);
+      set_gtk_geometry_hints(
+        window.gtk_window(),
+        state.resize_increments,
+        state.min_size,
+        state.max_size,
+        ratio,
+      );
+    }
+    let key = crate::tao::render::render_state_key(&window);
+    ASPECT_RATIO_LOCKS.with(|locks| {
+      let mut locks = locks.borrow_mut();
+      match ratio {
+        Some(ratio) => {
+          locks.insert(
+            key,
+            AspectRatioLock {
+              window: Arc::downgrade(inner),
+              ratio,
+              last_size: None,
+            },
+          );
+        }
+        None => {
+          locks.remove(&key);
+        }
+      }
+    });
+    if ratio.is_some() {
+      let size = window.inner_size();
+      request_inner_size_with_aspect_ratio(&window, size.width as f64, size.height as f64);
     }
     Ok(())
   }
 
+  /// Gets the aspect ratio set with `set_aspect_ratio`, or `null` when unlocked.
+  #[napi]
+  pub fn aspect_ratio(&self) -> Option<f64> {
+    self
+      .inner
+      .as_ref()
+      .and_then(|inner| tracked_window_state(&inner.lock().unwrap()).aspect_ratio)
+  }
+
+  /// Sizes the window to a content size and makes that size the minimum.
+  ///
+  /// `width` and `height` are the logical inner size, so decorations are added
+  /// by the window system. Combines `set_inner_size` and `set_min_size_logical`
+  /// for dialogs laid out to their content.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / Linux**: The resize is asynchronous; listen for `Resized` to get
+  ///   the final size.
+  /// - **Wayland**: The compositor decides the final size and may ignore the
+  ///   request, e.g. for tiled windows; the minimum still applies.
+  #[napi]
+  pub fn fit_to_content(&self, width: f64, height: f64) {
+    if let Some(inner) = &self.inner {
+      let window = inner.lock().unwrap();
+      let size = (width.max(1.0), height.max(1.0));
+      apply_min_size(&window, Some(size));
+      window.set_inner_size(tao::dpi::LogicalSize::new(size.0, size.1));
+    }
+  }
+
   /// Gets whether the window is maximized.
   #[napi]
   pub fn is_maximized(&self) -> Result<bool> {
@@ -728,6 +2476,105 @@ impl Window {
     Ok(())
   }
 
+  /// Centers the window on its current monitor's work area.
+  ///
+  /// Uses the current outer size, so call it again after resizing. The work area
+  /// excludes the taskbar, dock and menu bar; where it is unknown the full monitor
+  /// bounds are used. Returns `false` without moving the window where windows
+  /// cannot be positioned (Wayland) or the monitor is unknown.
+  #[napi]
+  pub fn center(&self) -> bool {
+    let Some(inner) = &self.inner else {
+      return false;
+    };
+    let window = inner.lock().unwrap();
+    if crate::tao::platform::is_wayland_window(&window) {
+      return false;
+    }
+    let area = monitor_work_area(&window).or_else(|| {
+      window
+        .current_monitor()
+        .map(|monitor| (monitor.position(), monitor.size()))
+    });
+    let Some((position, size)) = area else {
+      return false;
+    };
+    window.set_outer_position(centered_position(position, size, window.outer_size()));
+    true
+  }
+
+  /// Sizes and positions the window to fill its monitor's work area.
+  ///
+  /// The work area excludes the taskbar, dock and menu bar, unlike `set_maximized`
+  /// on some platforms. The window is not put in the maximized state; its frame is
+  /// placed so the whole outer window fits the work area.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Uses the GDK monitor work area, which requires the window to be
+  ///   realized and a window manager that publishes `_NET_WORKAREA`.
+  /// - **Other / unknown work area**: Falls back to the full monitor bounds.
+  #[napi]
+  pub fn maximize_to_workarea(&self) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      let window = inner.lock().unwrap();
+      let area = monitor_work_area(&window).or_else(|| {
+        window
+          .current_monitor()
+          .map(|monitor| (monitor.position(), monitor.size()))
+      });
+      let Some((position, size)) = area else {
+        return Err(napi::Error::new(
+          napi::Status::GenericFailure,
+          "Could not determine the window's monitor".to_string(),
+        ));
+      };
+
+      // Leave room for the decorations around the inner area
+      let outer = window.outer_size();
+      let inner_size = window.inner_size();
+      let frame_width = outer.width.saturating_sub(inner_size.width);
+      let frame_height = outer.height.saturating_sub(inner_size.height);
+
+      window.set_maximized(false);
+      window.set_outer_position(position);
+      window.set_inner_size(tao::dpi::PhysicalSize::new(
+        size.width.saturating_sub(frame_width),
+        size.height.saturating_sub(frame_height),
+      ));
+    }
+    Ok(())
+  }
+
+  /// Sets how the menu bar and Dock behave while the app is fullscreen.
+  ///
+  /// `menu`/`dock` set to true make the menu bar/Dock auto-hide, revealed when the
+  /// pointer reaches the screen edge; false hides them completely. A hidden menu bar
+  /// requires a hidden Dock, so `menu: false` also hides the Dock.
+  ///
+  /// This changes the presentation options of the whole application, not just this
+  /// window. The options are derived from the current fullscreen state, so call it
+  /// again after entering or leaving fullscreen.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Linux**: Unsupported.
+  #[napi]
+  pub fn set_fullscreen_autohide(&self, menu: bool, dock: bool) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+      if let Some(inner) = &self.inner {
+        let fullscreen = inner.lock().unwrap().fullscreen().is_some();
+        appkit::set_presentation_options(presentation_options(menu, dock, fullscreen));
+      }
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+      let _ = (menu, dock);
+    }
+    Ok(())
+  }
+
   /// Gets whether the window is minimized.
   #[napi]
   pub fn is_minimized(&self) -> Result<bool> {
@@ -758,21 +2605,387 @@ impl Window {
   }
 
   /// Sets whether the window is always on top.
+  ///
+  /// Mutually exclusive with `set_always_on_bottom`: enabling one disables the other.
   #[napi]
   pub fn set_always_on_top(&self, always_on_top: bool) -> Result<()> {
     if let Some(inner) = &self.inner {
-      inner.lock().unwrap().set_always_on_top(always_on_top);
+      let window = inner.lock().unwrap();
+      let level = toggled_window_level(
+        tracked_window_state(&window).level,
+        WindowLevel::AlwaysOnTop,
+        always_on_top,
+      );
+      apply_window_level(&window, level);
     }
     Ok(())
   }
 
-  /// Gets whether the window is focused.
+  /// Sets whether the window is always below other windows, e.g. for a live wallpaper.
+  ///
+  /// Mutually exclusive with `set_always_on_top`: enabling one disables the other.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Depends on the window manager honoring `_NET_WM_STATE_BELOW`; not
+  ///   supported on Wayland.
   #[napi]
-  pub fn is_focused(&self) -> Result<bool> {
+  pub fn set_always_on_bottom(&self, always_on_bottom: bool) -> Result<()> {
     if let Some(inner) = &self.inner {
-      Ok(inner.lock().unwrap().is_focused())
-    } else {
-      Ok(true)
+      let window = inner.lock().unwrap();
+      let level = toggled_window_level(
+        tracked_window_state(&window).level,
+        WindowLevel::AlwaysOnBottom,
+        always_on_bottom,
+      );
+      apply_window_level(&window, level);
+    }
+    Ok(())
+  }
+
+  /// Puts the window back at the normal level, neither always on top nor always
+  /// on bottom.
+  #[napi]
+  pub fn set_level_normal(&self) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      apply_window_level(&inner.lock().unwrap(), WindowLevel::Normal);
+    }
+    Ok(())
+  }
+
+  /// Gets the window level: always on top, always on bottom or normal.
+  ///
+  /// This is the level last set through this crate, at build time or later, since
+  /// the platforms have no way to query the always-on-bottom state.
+  #[napi]
+  pub fn window_level(&self) -> Result<WindowLevel> {
+    Ok(match &self.inner {
+      Some(inner) => tracked_window_state(&inner.lock().unwrap()).level,
+      None => WindowLevel::Normal,
+    })
+  }
+
+  /// Sets the steps, in logical pixels, by which the user resizes the window.
+  ///
+  /// Terminals and editors use the size of a character cell so the window always
+  /// fits whole rows and columns. An axis passed as `null` resizes freely; passing
+  /// `null` for both clears the increments. Only interactive resizing is affected.
+  ///
+  /// Throws with code `"NotSupported"` on Windows.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Increments are rounded to whole logical pixels, and some window
+  ///   managers (and most Wayland compositors) ignore them.
+  #[napi]
+  pub fn set_resize_increments(
+    &self,
+    width: Option<f64>,
+    height: Option<f64>,
+  ) -> napi::Result<(), PlatformErrorStatus> {
+    let Some(inner) = &self.inner else {
+      return Ok(());
+    };
+    let window = inner.lock().unwrap();
+    let increments = normalize_resize_increments(width, height);
+    if !apply_resize_increments(&window, increments) {
+      return Err(
+        NotSupportedError::new("Resize increments are not supported on this platform").into(),
+      );
+    }
+    update_tracked_window_state(&window, |state| state.resize_increments = increments);
+    Ok(())
+  }
+
+  /// Sets whether the window is left out of screen recordings and screen sharing.
+  ///
+  /// This is a hint to the capture APIs, not DRM-style content protection: the
+  /// window is omitted from captures (what is behind it shows instead) rather
+  /// than blacked out, and capture tools that bypass the system APIs still see
+  /// it. Use it to keep sensitive windows out of shared screens by accident.
+  ///
+  /// Throws with code `"NotSupported"` where no mechanism exists.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Uses the `WDA_EXCLUDEFROMCAPTURE` display affinity, available
+  ///   since Windows 10 2004.
+  /// - **macOS**: Sets the window's `sharingType` to none.
+  /// - **Linux**: Unsupported; X11 and Wayland have no exclusion hint.
+  #[napi]
+  pub fn set_excluded_from_capture(&self, excluded: bool) -> napi::Result<(), PlatformErrorStatus> {
+    let Some(inner) = &self.inner else {
+      return Ok(());
+    };
+    let window = inner.lock().unwrap();
+    if !apply_capture_exclusion(&window, excluded) {
+      return Err(
+        NotSupportedError::new("Excluding windows from capture is not supported on this platform")
+          .into(),
+      );
+    }
+    update_tracked_window_state(&window, |state| state.excluded_from_capture = excluded);
+    Ok(())
+  }
+
+  /// Gets whether the window was excluded from capture with `set_excluded_from_capture`.
+  #[napi]
+  pub fn is_excluded_from_capture(&self) -> bool {
+    self
+      .inner
+      .as_ref()
+      .is_some_and(|inner| tracked_window_state(&inner.lock().unwrap()).excluded_from_capture)
+  }
+
+  /// Gets the insets that keep content clear of the window chrome, in logical pixels.
+  ///
+  /// Returns the insets set with `set_content_insets` if any. Otherwise, on macOS
+  /// they cover the titlebar and traffic lights when the content view extends
+  /// under a transparent titlebar; elsewhere the content area never sits under
+  /// platform chrome and they are zero.
+  #[napi]
+  pub fn content_insets(&self) -> Insets {
+    let (top, left, bottom, right) = self
+      .inner
+      .as_ref()
+      .map(|inner| {
+        let window = inner.lock().unwrap();
+        tracked_window_state(&window)
+          .content_insets
+          .unwrap_or_else(|| platform_content_insets(&window))
+      })
+      .unwrap_or_default();
+    Insets {
+      top,
+      left,
+      bottom,
+      right,
+    }
+  }
+
+  /// Sets the insets returned by `content_insets`, e.g. for custom chrome drawn by
+  /// the app; `null` restores the platform insets.
+  ///
+  /// No platform lets apps move its own chrome, so this only records the layout
+  /// for the renderer. Negative values are clamped to zero.
+  #[napi]
+  pub fn set_content_insets(&self, insets: Option<Insets>) {
+    if let Some(inner) = &self.inner {
+      let insets = insets.map(|insets| {
+        (
+          insets.top.max(0.0),
+          insets.left.max(0.0),
+          insets.bottom.max(0.0),
+          insets.right.max(0.0),
+        )
+      });
+      update_tracked_window_state(&inner.lock().unwrap(), |state| {
+        state.content_insets = insets
+      });
+    }
+  }
+
+  /// Gets the resize increments last set, in logical pixels, or `null` if none.
+  #[napi]
+  pub fn resize_increments(&self) -> Option<Size> {
+    let inner = self.inner.as_ref()?;
+    let (width, height) = tracked_window_state(&inner.lock().unwrap()).resize_increments?;
+    Some(Size { width, height })
+  }
+
+  /// Sets the opacity of the whole window, from 0.0 (invisible) to 1.0 (opaque).
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Makes the window a layered window.
+  /// - **Linux**: Requires a compositing window manager.
+  #[napi]
+  pub fn set_opacity(&self, opacity: f64) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      set_window_opacity(&inner.lock().unwrap(), opacity);
+    }
+    Ok(())
+  }
+
+  /// Gets the opacity last set with [`Window::set_opacity`] or [`Window::fade`] (default: 1.0).
+  #[napi]
+  pub fn opacity(&self) -> Result<f64> {
+    if let Some(inner) = &self.inner {
+      Ok(window_opacity(&inner.lock().unwrap()))
+    } else {
+      Ok(1.0)
+    }
+  }
+
+  /// Snapshots the window configuration, see [`WindowConfig`].
+  ///
+  /// `renderer` is left `null`; set it to `PixelRenderer.options()` to restore a
+  /// renderer along with the window. The size is the current one, so a maximized
+  /// window is restored maximized at its maximized size.
+  #[napi]
+  pub fn export_config(&self) -> Result<WindowConfig> {
+    let inner = self.inner.as_ref().ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Window not initialized".to_string(),
+      )
+    })?;
+    let window = inner.lock().unwrap();
+    let state = tracked_window_state(&window);
+    let scale_factor = window.scale_factor();
+    let size = window.inner_size().to_logical::<f64>(scale_factor);
+    let position = if crate::tao::platform::is_wayland_window(&window) {
+      None
+    } else {
+      window
+        .outer_position()
+        .ok()
+        .map(|position| position.to_logical::<f64>(scale_factor))
+    };
+    Ok(WindowConfig {
+      title: window.title(),
+      width: size.width,
+      height: size.height,
+      x: position.map(|position| position.x),
+      y: position.map(|position| position.y),
+      resizable: window.is_resizable(),
+      decorated: window.is_decorated(),
+      visible: window.is_visible(),
+      maximized: window.is_maximized(),
+      fullscreen: fullscreen_type(&window),
+      level: state.level,
+      theme: state.theme,
+      transparent: state.transparent,
+      opacity: state.opacity,
+      aspect_ratio: state.aspect_ratio,
+      renderer: None,
+    })
+  }
+
+  /// Gets the window's transparency, opacity, blur and theme in one call.
+  ///
+  /// The values are the ones applied through this crate: `transparent` is set at
+  /// creation, `theme` is `null` while the window follows the system theme.
+  #[napi]
+  pub fn visual_state(&self) -> VisualState {
+    let state = self
+      .inner
+      .as_ref()
+      .map(|inner| tracked_window_state(&inner.lock().unwrap()))
+      .unwrap_or_default();
+    VisualState::from(state)
+  }
+
+  /// Applies opacity, blur and theme together and returns the resulting state.
+  ///
+  /// Transparency can only be chosen when the window is created, so `transparent`
+  /// is ignored and the returned value keeps the actual one. Likewise `blur` stays
+  /// `false` where it is unsupported.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Blur uses the acrylic system backdrop of Windows 11 22H2+.
+  /// - **macOS / Linux**: Blur is unsupported.
+  #[napi]
+  pub fn set_visual_state(&self, visual_state: VisualState) -> VisualState {
+    let Some(inner) = &self.inner else {
+      return VisualState::from(TrackedWindowState::default());
+    };
+    let window = inner.lock().unwrap();
+    set_window_opacity(&window, visual_state.opacity);
+    window.set_theme(visual_state.theme.map(|theme| match theme {
+      TaoTheme::Light => tao::window::Theme::Light,
+      TaoTheme::Dark => tao::window::Theme::Dark,
+    }));
+    let blur = apply_window_blur(&window, visual_state.blur) && visual_state.blur;
+    update_tracked_window_state(&window, |state| {
+      state.theme = visual_state.theme;
+      state.blur = blur;
+    });
+    VisualState::from(tracked_window_state(&window))
+  }
+
+  /// Animates the window opacity to `target_opacity` over `duration_ms` milliseconds.
+  ///
+  /// The animation is driven by the event loop's idle tick, so the loop must be
+  /// running. Starting another fade on the same window cancels the previous one.
+  /// `callback` receives `true` when the fade completes and `false` if it was cancelled.
+  #[napi]
+  pub fn fade(
+    &self,
+    target_opacity: f64,
+    duration_ms: u32,
+    callback: Option<FadeCallback>,
+  ) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      let from = window_opacity(&inner.lock().unwrap());
+      let cancelled = FADES.with(|fades| {
+        let mut fades = fades.borrow_mut();
+        let position = fades
+          .iter()
+          .position(|fade| std::ptr::eq(fade.window.as_ptr(), Arc::as_ptr(inner)));
+        let cancelled = position.map(|i| fades.remove(i));
+        fades.push(FadeEntry {
+          window: Arc::downgrade(inner),
+          from,
+          to: target_opacity.clamp(0.0, 1.0),
+          start: std::time::Instant::now(),
+          duration: std::time::Duration::from_millis(duration_ms as u64),
+          callback,
+        });
+        cancelled
+      });
+      if let Some(callback) = cancelled.and_then(|fade| fade.callback) {
+        let _ = callback.call(Ok(false), ThreadsafeFunctionCallMode::NonBlocking);
+      }
+    }
+    Ok(())
+  }
+
+  /// Re-asserts the always-on-top level, raising the window above other topmost windows.
+  ///
+  /// Useful for overlays that get buried when another always-on-top window is raised.
+  #[napi]
+  pub fn ensure_on_top(&self) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      ensure_window_on_top(&inner.lock().unwrap());
+    }
+    Ok(())
+  }
+
+  /// Keeps the window on top by calling [`Window::ensure_on_top`] periodically.
+  ///
+  /// The check runs from the event loop when it becomes idle, at most once every
+  /// `interval_ms` milliseconds. Pass 0 to stop.
+  #[napi]
+  pub fn keep_on_top(&self, interval_ms: u32) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      KEEP_ON_TOP.with(|entries| {
+        let mut entries = entries.borrow_mut();
+        entries.retain(|entry| !std::ptr::eq(entry.window.as_ptr(), Arc::as_ptr(inner)));
+        if interval_ms > 0 {
+          let interval = std::time::Duration::from_millis(interval_ms as u64);
+          entries.push(KeepOnTopEntry {
+            window: Arc::downgrade(inner),
+            interval,
+            next: std::time::Instant::now() + interval,
+          });
+        }
+      });
+      if interval_ms > 0 {
+        ensure_window_on_top(&inner.lock().unwrap());
+      }
+    }
+    Ok(())
+  }
+
+  /// Gets whether the window is focused.
+  #[napi]
+  pub fn is_focused(&self) -> Result<bool> {
+    if let Some(inner) = &self.inner {
+      Ok(inner.lock().unwrap().is_focused())
+    } else {
+      Ok(true)
     }
   }
 
@@ -785,13 +2998,24 @@ impl Window {
     Ok(())
   }
 
-  /// Gets the current cursor icon.
+  /// Gets the cursor icon last set with `set_cursor_icon` (initially `Default`).
   #[napi]
   pub fn cursor_icon(&self) -> Result<CursorIcon> {
-    Ok(CursorIcon::Default)
+    if let Some(inner) = &self.inner {
+      Ok(tracked_window_state(&inner.lock().unwrap()).cursor_icon)
+    } else {
+      Ok(CursorIcon::Default)
+    }
   }
 
   /// Sets the cursor icon.
+  ///
+  /// Each `CursorIcon` maps to its own system cursor where the platform has one.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Has fewer system cursors, so some icons share one, e.g. `Grab`,
+  ///   `Grabbing` and `Move` all show the four-way arrow.
   #[napi]
   pub fn set_cursor_icon(&self, cursor: CursorIcon) -> Result<()> {
     if let Some(inner) = &self.inner {
@@ -823,26 +3047,192 @@ impl Window {
         CursorIcon::AllScroll => tao::window::CursorIcon::AllScroll,
         CursorIcon::ZoomIn => tao::window::CursorIcon::ZoomIn,
         CursorIcon::ZoomOut => tao::window::CursorIcon::ZoomOut,
+        CursorIcon::ContextMenu => tao::window::CursorIcon::ContextMenu,
+        CursorIcon::Cell => tao::window::CursorIcon::Cell,
+        CursorIcon::VerticalText => tao::window::CursorIcon::VerticalText,
+        CursorIcon::Alias => tao::window::CursorIcon::Alias,
+        CursorIcon::Copy => tao::window::CursorIcon::Copy,
+        CursorIcon::NoDrop => tao::window::CursorIcon::NoDrop,
+        CursorIcon::Grab => tao::window::CursorIcon::Grab,
+        CursorIcon::Grabbing => tao::window::CursorIcon::Grabbing,
       };
-      inner.lock().unwrap().set_cursor_icon(tao_cursor);
+      let window = inner.lock().unwrap();
+      window.set_cursor_icon(tao_cursor);
+      let mut rehide = false;
+      update_tracked_window_state(&window, |state| rehide = state.apply_cursor_icon(cursor));
+      // Some platforms show the cursor again when its icon changes
+      if rehide {
+        window.set_cursor_visible(false);
+      }
+    }
+    Ok(())
+  }
+
+  /// Sets the cursor icon from a CSS `cursor` keyword, e.g. "pointer", "grab" or
+  /// "ew-resize".
+  ///
+  /// Keywords are matched case-insensitively; "auto" shows the default cursor.
+  /// Throws for unknown keywords, and for "none": hide the cursor with
+  /// `set_cursor_visible` instead.
+  #[napi]
+  pub fn set_cursor_by_name(&self, name: String) -> Result<()> {
+    let cursor = cursor_icon_from_css(&name).ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::InvalidArg,
+        format!("Unknown cursor name: {:?}", name),
+      )
+    })?;
+    self.set_cursor_icon(cursor)
+  }
+
+  /// Gets whether the cursor is visible, as last set with `set_cursor_visible`.
+  #[napi]
+  pub fn is_cursor_visible(&self) -> Result<bool> {
+    if let Some(inner) = &self.inner {
+      Ok(tracked_window_state(&inner.lock().unwrap()).cursor_visible)
+    } else {
+      Ok(true)
+    }
+  }
+
+  /// Shows or hides the cursor while it is over the window.
+  ///
+  /// A hidden cursor stays hidden when the icon is changed with `set_cursor_icon`.
+  #[napi]
+  pub fn set_cursor_visible(&self, visible: bool) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      let window = inner.lock().unwrap();
+      window.set_cursor_visible(visible);
+      update_tracked_window_state(&window, |state| state.cursor_visible = visible);
     }
     Ok(())
   }
 
   /// Sets the cursor position.
+  ///
+  /// Throws with code `"NotSupported"` where the platform does not allow it (e.g.
+  /// Wayland), or `"OsError"` when the OS rejects the request.
   #[napi]
-  pub fn set_cursor_position(&self, x: f64, y: f64) -> Result<()> {
+  pub fn set_cursor_position(&self, x: f64, y: f64) -> napi::Result<(), PlatformErrorStatus> {
     if let Some(inner) = &self.inner {
-      let _ = inner
+      inner
         .lock()
         .unwrap()
         .set_cursor_position(tao::dpi::Position::Physical(
           tao::dpi::PhysicalPosition::new(x as i32, y as i32),
-        ));
+        ))
+        .map_err(external_error)?;
+    }
+    Ok(())
+  }
+
+  /// Grabs the cursor, keeping it inside the window, or releases it.
+  ///
+  /// Throws with code `"NotSupported"` or `"OsError"` when it cannot be applied;
+  /// the grab mode is then left unchanged.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: `Confined` locks the cursor in place instead.
+  #[napi]
+  pub fn set_cursor_grab(&self, mode: CursorGrabMode) -> napi::Result<(), PlatformErrorStatus> {
+    if let Some(inner) = &self.inner {
+      let window = inner.lock().unwrap();
+      window
+        .set_cursor_grab(mode == CursorGrabMode::Confined)
+        .map_err(external_error)?;
+      update_tracked_window_state(&window, |state| state.cursor_grab = mode);
+    }
+    Ok(())
+  }
+
+  /// Gets the cursor grab mode, as last applied with `set_cursor_grab`.
+  ///
+  /// tao has no way to query the grab, so a grab the OS released on its own, e.g.
+  /// while another window is focused, is not reflected.
+  #[napi]
+  pub fn cursor_grab_mode(&self) -> CursorGrabMode {
+    self.inner.as_ref().map_or(CursorGrabMode::None, |inner| {
+      tracked_window_state(&inner.lock().unwrap()).cursor_grab
+    })
+  }
+
+  /// Confines the cursor to a rectangle of the window, or releases it with `null`.
+  ///
+  /// The rectangle is in physical pixels relative to the top-left of the client area,
+  /// e.g. a game viewport. Unlike `setCursorGrab`, this is a software confinement
+  /// built on movement events: whenever a `CursorMoved` event lands outside the
+  /// rectangle, the cursor is warped back to the nearest point inside it. It only
+  /// takes effect while an event loop is running, and the cursor may briefly
+  /// overshoot before it is moved back.
+  ///
+  /// Throws with code `"NotSupported"` where the cursor cannot be moved (e.g.
+  /// Wayland).
+  #[napi]
+  pub fn confine_cursor_to_rect(
+    &self,
+    rect: Option<Rectangle>,
+  ) -> napi::Result<(), PlatformErrorStatus> {
+    let Some(inner) = &self.inner else {
+      return Ok(());
+    };
+    let key = crate::tao::render::render_state_key(&inner.lock().unwrap());
+    let Some(rect) = rect else {
+      CURSOR_CONFINEMENTS.with(|confinements| confinements.borrow_mut().remove(&key));
+      return Ok(());
+    };
+    if rect.size.width < 0.0 || rect.size.height < 0.0 {
+      return Err(napi::Error::new(
+        PlatformErrorStatus::GenericFailure,
+        "Confinement rectangle must have a non-negative size",
+      ));
+    }
+    if crate::tao::platform::is_wayland_window(&inner.lock().unwrap()) {
+      return Err(NotSupportedError::new("Cursor confinement is not supported on Wayland").into());
     }
+    let confinement = CursorConfinement {
+      window: Arc::downgrade(inner),
+      left: rect.origin.x,
+      top: rect.origin.y,
+      right: rect.origin.x + rect.size.width,
+      bottom: rect.origin.y + rect.size.height,
+    };
+    CURSOR_CONFINEMENTS.with(|confinements| confinements.borrow_mut().insert(key, confinement));
     Ok(())
   }
 
+  /// Gets the connected monitors.
+  ///
+  /// The order is stable: the primary monitor first, then the others by position,
+  /// left to right and then top to bottom. Positions and sizes are in physical
+  /// pixels in the desktop coordinate space.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Wayland**: There is no primary monitor, so `isPrimary` is always false.
+  #[napi]
+  pub fn available_monitors(&self) -> Vec<MonitorInfo> {
+    let Some(inner) = &self.inner else {
+      return Vec::new();
+    };
+    let window = inner.lock().unwrap();
+    let primary = window.primary_monitor();
+    let mut monitors: Vec<MonitorInfo> = window
+      .available_monitors()
+      .map(|monitor| MonitorInfo::from_handle(&monitor, primary.as_ref()))
+      .collect();
+    sort_monitors(&mut monitors);
+    monitors
+  }
+
+  /// Gets the primary monitor, or `null` where there is none (e.g. Wayland).
+  #[napi]
+  pub fn primary_monitor(&self) -> Option<MonitorInfo> {
+    let inner = self.inner.as_ref()?;
+    let primary = inner.lock().unwrap().primary_monitor()?;
+    Some(MonitorInfo::from_handle(&primary, Some(&primary)))
+  }
+
   /// Gets the cursor position.
   #[napi]
   pub fn cursor_position(&self) -> Result<Position> {
@@ -879,7 +3269,128 @@ impl Window {
         TaoTheme::Light => tao::window::Theme::Light,
         TaoTheme::Dark => tao::window::Theme::Dark,
       };
-      inner.lock().unwrap().set_theme(Some(tao_theme));
+      let window = inner.lock().unwrap();
+      window.set_theme(Some(tao_theme));
+      update_tracked_window_state(&window, |state| state.theme = Some(theme));
+    }
+    Ok(())
+  }
+
+  /// Sets whether the titlebar uses dark mode.
+  ///
+  /// Uses the DWM immersive dark mode attribute on Windows 10/11. No-op on other platforms.
+  #[napi]
+  pub fn set_titlebar_dark(&self, dark: bool) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+      if let Some(inner) = &self.inner {
+        apply_titlebar_dark(&inner.lock().unwrap(), dark);
+      }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+      let _ = dark;
+    }
+    Ok(())
+  }
+
+  /// Enables Windows 11 snap layouts for borderless / custom-chrome windows.
+  ///
+  /// Adds the maximize box and resize frame styles that the shell requires before
+  /// it shows the snap layouts flyout. Use [`Window::set_maximize_button_rect`] to
+  /// mark where the custom maximize button is drawn. Disabling removes the styles
+  /// again, except those the window had before.
+  ///
+  /// Windows 11 only. Older Windows versions ignore the hint and other platforms
+  /// are a no-op.
+  #[napi]
+  pub fn enable_snap_layouts(&self, enabled: bool) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+      if let Some(inner) = &self.inner {
+        apply_snap_layouts(&inner.lock().unwrap(), enabled);
+      }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+      let _ = enabled;
+    }
+    Ok(())
+  }
+
+  /// Sets the area of the custom maximize button, in physical client coordinates.
+  ///
+  /// While snap layouts are enabled, the window reports this area as the maximize
+  /// button during hit-testing, so hovering it shows the snap layouts flyout and
+  /// clicking it toggles maximize. Pass `null` to remove the area.
+  ///
+  /// The hit-test is only seen by the window itself, so the area must not be
+  /// covered by a webview. Windows 11 only; no-op on other platforms.
+  #[napi]
+  pub fn set_maximize_button_rect(&self, rect: Option<Rectangle>) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+      use tao::platform::windows::WindowExtWindows;
+
+      if let Some(inner) = &self.inner {
+        let hwnd = inner.lock().unwrap().hwnd();
+        let mut rects = SNAP_BUTTON_RECTS.lock().unwrap();
+        match rect {
+          Some(rect) => {
+            rects.insert(
+              hwnd,
+              [
+                rect.origin.x as i32,
+                rect.origin.y as i32,
+                (rect.origin.x + rect.size.width) as i32,
+                (rect.origin.y + rect.size.height) as i32,
+              ],
+            );
+          }
+          None => {
+            rects.remove(&hwnd);
+          }
+        }
+      }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+      let _ = rect;
+    }
+    Ok(())
+  }
+
+  /// Sets a non-rectangular window shape from an alpha mask.
+  ///
+  /// `mask` is either `width * height` alpha bytes or a `width * height * 4` RGBA
+  /// buffer, in physical pixels from the window's top-left corner. Pixels with
+  /// alpha >= 128 are visible and receive input; the rest are cut out and clicks
+  /// pass through to whatever is below. Intended for undecorated windows; combine
+  /// with transparency for anti-aliased edges.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Uses a window region (`SetWindowRgn`).
+  /// - **Linux (X11)**: Uses the X shape extension through GTK for both the
+  ///   visible and the input region.
+  /// - **Linux (Wayland)**: Only the input region is applied; the compositor
+  ///   ignores the visible shape, so transparent pixels are needed for the look.
+  /// - **macOS**: No-op. Transparent windows already let clicks pass through
+  ///   fully transparent pixels, so render the shape into a transparent window.
+  #[napi]
+  pub fn set_shape(&self, mask: Buffer, width: u32, height: u32) -> Result<()> {
+    let runs = shape_mask_runs(&mask, width, height)?;
+    if let Some(inner) = &self.inner {
+      apply_window_shape(&inner.lock().unwrap(), Some(&runs));
+    }
+    Ok(())
+  }
+
+  /// Removes a shape set with [`Window::set_shape`], restoring the rectangular window.
+  #[napi]
+  pub fn clear_shape(&self) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      apply_window_shape(&inner.lock().unwrap(), None);
     }
     Ok(())
   }
@@ -899,46 +3410,1788 @@ impl Window {
     }
   }
 
-  /// Sets the window icon.
-  #[napi]
-  pub fn set_window_icon(&self, width: u32, height: u32, rgba: Buffer) -> Result<()> {
-    if let Some(inner) = &self.inner {
-      let icon = tao::window::Icon::from_rgba(rgba.to_vec(), width, height).map_err(|e| {
-        napi::Error::new(napi::Status::GenericFailure, format!("Invalid icon: {}", e))
-      })?;
-      inner.lock().unwrap().set_window_icon(Some(icon));
+  /// Sets the window icon.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Sets the small icon (`ICON_SMALL`) of the title bar and
+  ///   Alt+Tab; see [`Window::set_taskbar_icon`] for the large taskbar icon.
+  #[napi]
+  pub fn set_window_icon(&self, width: u32, height: u32, rgba: Buffer) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      let icon = tao::window::Icon::from_rgba(rgba.to_vec(), width, height).map_err(|e| {
+        napi::Error::new(napi::Status::GenericFailure, format!("Invalid icon: {}", e))
+      })?;
+      inner.lock().unwrap().set_window_icon(Some(icon));
+    }
+    Ok(())
+  }
+
+  /// Sets the large icon shown in the taskbar, separately from the window icon.
+  ///
+  /// Provide a larger image than for [`Window::set_window_icon`], up to 256x256,
+  /// so the taskbar does not upscale the small icon.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Sets the large icon (`ICON_BIG`).
+  /// - **macOS / Linux**: No-op; the icon is only validated.
+  #[napi]
+  pub fn set_taskbar_icon(&self, width: u32, height: u32, rgba: Buffer) -> Result<()> {
+    let icon = tao::window::Icon::from_rgba(rgba.to_vec(), width, height).map_err(|e| {
+      napi::Error::new(napi::Status::GenericFailure, format!("Invalid icon: {}", e))
+    })?;
+    if let Some(inner) = &self.inner {
+      apply_taskbar_icon(&inner.lock().unwrap(), icon);
+    }
+    Ok(())
+  }
+
+  /// Sets the large taskbar icon from PNG (or JPEG, BMP, WEBP) bytes.
+  ///
+  /// See [`Window::set_taskbar_icon`].
+  #[napi]
+  pub fn set_taskbar_icon_from_png(&self, png: Buffer) -> Result<()> {
+    let (width, height, rgba) = crate::tao::functions::decode_rgba(&png)?;
+    self.set_taskbar_icon(width, height, rgba.into())
+  }
+
+  /// Sets whether to ignore cursor events.
+  ///
+  /// Throws with code `"NotSupported"` or `"OsError"` when it cannot be applied.
+  #[napi]
+  pub fn set_ignore_cursor_events(&self, ignore: bool) -> napi::Result<(), PlatformErrorStatus> {
+    if let Some(inner) = &self.inner {
+      let window = inner.lock().unwrap();
+      window
+        .set_ignore_cursor_events(ignore)
+        .map_err(external_error)?;
+      update_tracked_window_state(&window, |state| state.ignore_cursor_events = ignore);
+      let key = crate::tao::render::render_state_key(&window);
+      IGNORED_CURSOR_WINDOWS.with(|windows| {
+        let mut windows = windows.borrow_mut();
+        if ignore {
+          windows.insert(key, Arc::downgrade(inner));
+        } else {
+          windows.remove(&key);
+        }
+      });
+    }
+    Ok(())
+  }
+
+  /// Gets whether cursor events are ignored, as last set with `set_ignore_cursor_events`.
+  ///
+  /// The state is kept across hiding and showing the window and re-applied when
+  /// the window is shown or gains focus.
+  #[napi]
+  pub fn is_ignoring_cursor_events(&self) -> bool {
+    self
+      .inner
+      .as_ref()
+      .is_some_and(|inner| tracked_window_state(&inner.lock().unwrap()).ignore_cursor_events)
+  }
+
+  /// Requests a redrawing of the window.
+  #[napi]
+  pub fn request_redraw(&self) -> Result<()> {
+    if let Some(inner) = &self.inner {
+      inner.lock().unwrap().request_redraw();
+    }
+    Ok(())
+  }
+
+  /// Closes the window.
+  ///
+  /// The window is hidden at once, its render surface released and this handle
+  /// let go of the native window, which is destroyed; the event loop then
+  /// delivers its `Destroyed` event like any other. Other methods of this handle
+  /// do nothing or throw afterwards. Closing does not end the loop, even for the
+  /// last window: call `EventLoop.exit` for that. Closing twice does nothing.
+  ///
+  /// Other handles to the same window, e.g. from `App.window()`, keep the native
+  /// window alive, hidden, until they are closed or garbage collected too.
+  #[napi]
+  pub fn close(&mut self) -> Result<()> {
+    let Some(inner) = self.inner.take() else {
+      return Ok(());
+    };
+    let key = {
+      let window = inner.lock().unwrap();
+      window.set_visible(false);
+      update_tracked_window_state(&window, |state| state.closed = true);
+      crate::tao::render::render_state_key(&window)
+    };
+    // Not under the window lock: a render thread may need it to finish its frame
+    crate::tao::render::release_render_state(key);
+    crate::tao::render::remove_alpha_hittest(key);
+    // Dropped like a garbage collected handle: the last one destroys the window
+    drop(Window { inner: Some(inner) });
+    Ok(())
+  }
+
+  /// Gets whether the window was closed with `close`, through this or another handle.
+  #[napi]
+  pub fn is_closed(&self) -> bool {
+    self
+      .inner
+      .as_ref()
+      .is_none_or(|inner| tracked_window_state(&inner.lock().unwrap()).closed)
+  }
+}
+
+/// Sets the DWM immersive dark mode attribute so the titlebar follows a dark theme.
+#[cfg(target_os = "windows")]
+fn apply_titlebar_dark(window: &tao::window::Window, dark: bool) {
+  use tao::platform::windows::WindowExtWindows;
+
+  win32::set_dark_titlebar(window.hwnd(), dark);
+}
+
+/// Sets the acrylic system backdrop; returns whether the platform accepted it.
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+fn apply_window_blur(window: &tao::window::Window, blur: bool) -> bool {
+  #[cfg(target_os = "windows")]
+  {
+    use tao::platform::windows::WindowExtWindows;
+
+    win32::set_acrylic_backdrop(window.hwnd(), blur)
+  }
+  #[cfg(not(target_os = "windows"))]
+  {
+    false
+  }
+}
+
+/// A window registered with [`Window::keep_on_top`].
+struct KeepOnTopEntry {
+  window: std::sync::Weak<Mutex<tao::window::Window>>,
+  interval: std::time::Duration,
+  next: std::time::Instant,
+}
+
+thread_local! {
+  /// Windows are owned by the event loop thread, so the registry lives there too.
+  static KEEP_ON_TOP: std::cell::RefCell<Vec<KeepOnTopEntry>> =
+    const { std::cell::RefCell::new(Vec::new()) };
+}
+
+fn ensure_window_on_top(window: &tao::window::Window) {
+  #[cfg(target_os = "windows")]
+  {
+    use tao::platform::windows::WindowExtWindows;
+
+    // tao only applies always-on-top when the flag changes, so call SetWindowPos directly
+    win32::raise_topmost(window.hwnd());
+  }
+  #[cfg(not(target_os = "windows"))]
+  {
+    window.set_always_on_top(true);
+  }
+}
+
+/// Wraps the event handler of a blocking loop (`EventLoop.run`, `runOnDemand`
+/// and `Application.run`) with their shared control flow.
+///
+/// The loop waits for events, or until the next idle task is due: the deadline
+/// from [`run_idle_tasks`] set on `MainEventsCleared` holds until the next
+/// `NewEvents`, so timers, fades and splash timeouts fire while the loop is idle.
+/// `handler` is called after the internal helpers and returns `true` to end the
+/// loop, as does `exit_requested`. `timers` are the loop's own timers, if it has
+/// any.
+pub(crate) fn blocking_loop<E, F>(
+  timers: Option<std::rc::Rc<std::cell::RefCell<LoopTimers>>>,
+  exit_requested: E,
+  mut handler: F,
+) -> impl FnMut(
+  tao::event::Event<'_, ()>,
+  &tao::event_loop::EventLoopWindowTarget<()>,
+  &mut tao::event_loop::ControlFlow,
+)
+where
+  E: Fn() -> bool,
+  F: FnMut(&tao::event::Event<'_, ()>, &tao::event_loop::EventLoopWindowTarget<()>) -> bool,
+{
+  use tao::event_loop::ControlFlow;
+
+  move |event, target, control_flow| {
+    if *control_flow == ControlFlow::Exit {
+      return;
+    }
+    if exit_requested() {
+      *control_flow = ControlFlow::Exit;
+      return;
+    }
+    if let tao::event::Event::NewEvents(_) = event {
+      *control_flow = ControlFlow::Wait;
+    }
+    dispatch_internal_event(&event);
+    if handler(&event, target) {
+      *control_flow = ControlFlow::Exit;
+      return;
+    }
+    if let tao::event::Event::MainEventsCleared = event {
+      *control_flow = match run_idle_tasks(timers.as_deref()) {
+        Some(deadline) => ControlFlow::WaitUntil(deadline),
+        None => ControlFlow::Wait,
+      };
+    }
+  }
+}
+
+/// Delivers an event of `run` or `run_on_demand` to the callbacks; returns
+/// whether it ends the loop.
+fn dispatch_loop_event(
+  event: &tao::event::Event<'_, ()>,
+  any_event_handler: Option<&AnyEventCallback>,
+  window_event_handler: Option<&WindowEventCallback>,
+  app_handler: Option<&AppEventHandler>,
+  exit_on_close: bool,
+) -> bool {
+  dispatch_any_event(any_event_handler, event);
+  dispatch_window_event(window_event_handler, event);
+  dispatch_app_event(app_handler, event);
+  match event {
+    tao::event::Event::WindowEvent {
+      window_id,
+      event: tao::event::WindowEvent::CloseRequested,
+      ..
+    } => exit_on_close && is_last_open_window(*window_id),
+    _ => false,
+  }
+}
+
+/// Callback fired with the id of an [`EventLoop::set_timer`] timer when it elapses.
+pub type TimerCallback = ThreadsafeFunction<u32>;
+
+/// A timer registered with [`EventLoop::set_timer`].
+struct TimerEntry {
+  id: u32,
+  interval: std::time::Duration,
+  next: std::time::Instant,
+  repeat: bool,
+}
+
+/// The timers of one [`EventLoop`] and the callback they fire.
+#[derive(Default)]
+pub(crate) struct LoopTimers {
+  entries: Vec<TimerEntry>,
+  callback: Option<TimerCallback>,
+}
+
+/// Removes or reschedules the timers due at `now` and returns their ids in firing order.
+fn take_due_timers(timers: &mut Vec<TimerEntry>, now: std::time::Instant) -> Vec<u32> {
+  let mut due: Vec<(std::time::Instant, u32)> = Vec::new();
+  timers.retain_mut(|timer| {
+    if timer.next > now {
+      return true;
+    }
+    due.push((timer.next, timer.id));
+    // Skip intervals missed while the loop was busy instead of firing a burst
+    timer.next += timer.interval;
+    if timer.next <= now {
+      timer.next = now + timer.interval;
+    }
+    timer.repeat
+  });
+  due.sort();
+  due.into_iter().map(|(_, id)| id).collect()
+}
+
+/// Fires the due [`EventLoop::set_timer`] timers of a loop.
+fn tick_timers(timers: &std::cell::RefCell<LoopTimers>) -> Option<std::time::Instant> {
+  let now = std::time::Instant::now();
+  let mut timers = timers.borrow_mut();
+  let due = take_due_timers(&mut timers.entries, now);
+  if let Some(callback) = timers.callback.as_ref() {
+    for id in due {
+      let _ = callback.call(Ok(id), ThreadsafeFunctionCallMode::NonBlocking);
+    }
+  }
+  timers.entries.iter().map(|timer| timer.next).min()
+}
+
+/// Callback fired when a [`Window::fade`] ends, with `true` if it completed.
+pub type FadeCallback = ThreadsafeFunction<bool>;
+
+/// Interval between fade steps, roughly one frame at 60 Hz
+const FADE_STEP: std::time::Duration = std::time::Duration::from_millis(16);
+
+/// A running [`Window::fade`] animation.
+struct FadeEntry {
+  window: std::sync::Weak<Mutex<tao::window::Window>>,
+  from: f64,
+  to: f64,
+  start: std::time::Instant,
+  duration: std::time::Duration,
+  callback: Option<FadeCallback>,
+}
+
+thread_local! {
+  static FADES: std::cell::RefCell<Vec<FadeEntry>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// A [`Window::confine_cursor_to_rect`] region, in physical inner coordinates.
+struct CursorConfinement {
+  window: std::sync::Weak<Mutex<tao::window::Window>>,
+  left: f64,
+  top: f64,
+  right: f64,
+  bottom: f64,
+}
+
+impl CursorConfinement {
+  /// Returns where to warp the cursor to keep it inside, or `None` if it already is.
+  fn clamp(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+    let clamped = (
+      x.clamp(self.left, self.right),
+      y.clamp(self.top, self.bottom),
+    );
+    (clamped != (x, y)).then_some(clamped)
+  }
+}
+
+thread_local! {
+  static CURSOR_CONFINEMENTS: std::cell::RefCell<std::collections::HashMap<u64, CursorConfinement>> =
+    std::cell::RefCell::new(std::collections::HashMap::new());
+}
+
+/// Warps the cursor back into the confinement rect of `window_id` when it leaves it.
+fn confine_cursor_on_move(window_id: tao::window::WindowId, event: &tao::event::WindowEvent) {
+  let tao::event::WindowEvent::CursorMoved { position, .. } = event else {
+    return;
+  };
+  let key = crate::tao::render::window_id_key(window_id);
+  CURSOR_CONFINEMENTS.with(|confinements| {
+    let mut confinements = confinements.borrow_mut();
+    let Some(confinement) = confinements.get(&key) else {
+      return;
+    };
+    let Some(window) = confinement.window.upgrade() else {
+      confinements.remove(&key);
+      return;
+    };
+    if let Some((x, y)) = confinement.clamp(position.x, position.y) {
+      let _ = window
+        .lock()
+        .unwrap()
+        .set_cursor_position(tao::dpi::PhysicalPosition::new(x, y));
+    }
+  });
+}
+
+thread_local! {
+  /// Windows ignoring cursor events, to re-apply it when they gain focus.
+  static IGNORED_CURSOR_WINDOWS: std::cell::RefCell<
+    std::collections::HashMap<u64, std::sync::Weak<Mutex<tao::window::Window>>>,
+  > = std::cell::RefCell::new(std::collections::HashMap::new());
+}
+
+/// Re-applies ignored cursor events when a window gains focus, since some
+/// platforms reset the input state then.
+fn restore_ignored_cursor_on_focus(
+  window_id: tao::window::WindowId,
+  event: &tao::event::WindowEvent,
+) {
+  if !matches!(event, tao::event::WindowEvent::Focused(true)) {
+    return;
+  }
+  let key = crate::tao::render::window_id_key(window_id);
+  IGNORED_CURSOR_WINDOWS.with(|windows| {
+    let mut windows = windows.borrow_mut();
+    let Some(window) = windows.get(&key) else {
+      return;
+    };
+    match window.upgrade() {
+      Some(window) => {
+        let _ = window.lock().unwrap().set_ignore_cursor_events(true);
+      }
+      None => {
+        windows.remove(&key);
+      }
+    }
+  });
+}
+
+/// A window locked to an aspect ratio with `set_aspect_ratio`
+struct AspectRatioLock {
+  window: std::sync::Weak<Mutex<tao::window::Window>>,
+  ratio: f64,
+  /// Last conforming inner size, to tell which edge the user drags
+  last_size: Option<(f64, f64)>,
+}
+
+thread_local! {
+  static ASPECT_RATIO_LOCKS: std::cell::RefCell<std::collections::HashMap<u64, AspectRatioLock>> =
+    std::cell::RefCell::new(std::collections::HashMap::new());
+}
+
+/// Snaps a size to the largest size of an aspect ratio fitting inside it
+fn fit_to_aspect_ratio(width: f64, height: f64, ratio: f64) -> (f64, f64) {
+  let (width, height) = (width.max(1.0), height.max(1.0));
+  if width / height > ratio {
+    ((height * ratio).round().max(1.0), height)
+  } else {
+    (width, (width / ratio).round().max(1.0))
+  }
+}
+
+/// Whether a whole-pixel size is as close to an aspect ratio as rounding allows
+fn conforms_to_aspect_ratio(width: f64, height: f64, ratio: f64) -> bool {
+  let (fit_width, fit_height) = fit_to_aspect_ratio(width, height, ratio);
+  (width - fit_width).abs() <= 1.0 && (height - fit_height).abs() <= 1.0
+}
+
+/// Corrects an interactively resized size to an aspect ratio
+///
+/// Keeps the axis that changed most since the last conforming size, so dragging
+/// a horizontal edge drives the height and a vertical edge the width. Returns
+/// `None` if the size already conforms.
+fn snap_resize_to_aspect_ratio(
+  size: (f64, f64),
+  previous: Option<(f64, f64)>,
+  ratio: f64,
+) -> Option<(f64, f64)> {
+  let (width, height) = size;
+  if conforms_to_aspect_ratio(width, height, ratio) {
+    return None;
+  }
+  let keep_height = previous.is_some_and(|(previous_width, previous_height)| {
+    (height - previous_height).abs() / previous_height.max(1.0)
+      > (width - previous_width).abs() / previous_width.max(1.0)
+  });
+  Some(if keep_height {
+    ((height * ratio).round().max(1.0), height.max(1.0))
+  } else {
+    (width.max(1.0), (width / ratio).round().max(1.0))
+  })
+}
+
+/// Requests a physical inner size snapped to the window's aspect ratio, if any;
+/// returns the size requested.
+fn request_inner_size_with_aspect_ratio(
+  window: &tao::window::Window,
+  width: f64,
+  height: f64,
+) -> (f64, f64) {
+  let size = match tracked_window_state(window).aspect_ratio {
+    Some(ratio) => {
+      let size = fit_to_aspect_ratio(width, height, ratio);
+      let key = crate::tao::render::render_state_key(window);
+      ASPECT_RATIO_LOCKS.with(|locks| {
+        if let Some(lock) = locks.borrow_mut().get_mut(&key) {
+          lock.last_size = Some(size);
+        }
+      });
+      size
+    }
+    None => (width, height),
+  };
+  window.set_inner_size(tao::dpi::PhysicalSize::new(size.0 as u32, size.1 as u32));
+  size
+}
+
+/// Corrects a resized window locked to an aspect ratio back to the ratio.
+fn keep_aspect_ratio_on_resize(window_id: tao::window::WindowId, event: &tao::event::WindowEvent) {
+  let tao::event::WindowEvent::Resized(size) = event else {
+    return;
+  };
+  if size.width == 0 || size.height == 0 {
+    return;
+  }
+  let key = crate::tao::render::window_id_key(window_id);
+  ASPECT_RATIO_LOCKS.with(|locks| {
+    let mut locks = locks.borrow_mut();
+    let Some(lock) = locks.get_mut(&key) else {
+      return;
+    };
+    let Some(window) = lock.window.upgrade() else {
+      locks.remove(&key);
+      return;
+    };
+    let size = (size.width as f64, size.height as f64);
+    match snap_resize_to_aspect_ratio(size, lock.last_size, lock.ratio) {
+      Some(snapped) => {
+        lock.last_size = Some(snapped);
+        window
+          .lock()
+          .unwrap()
+          .set_inner_size(tao::dpi::PhysicalSize::new(
+            snapped.0 as u32,
+            snapped.1 as u32,
+          ));
+      }
+      None => lock.last_size = Some(size),
+    }
+  });
+}
+
+/// Parses a CSS `cursor` keyword, see [`Window::set_cursor_by_name`]
+fn cursor_icon_from_css(name: &str) -> Option<CursorIcon> {
+  Some(match name.trim().to_ascii_lowercase().as_str() {
+    "auto" | "default" => CursorIcon::Default,
+    "crosshair" => CursorIcon::Crosshair,
+    "pointer" => CursorIcon::Hand,
+    "move" => CursorIcon::Move,
+    "text" => CursorIcon::Text,
+    "wait" => CursorIcon::Wait,
+    "help" => CursorIcon::Help,
+    "progress" => CursorIcon::Progress,
+    "not-allowed" => CursorIcon::NotAllowed,
+    "e-resize" => CursorIcon::EastResize,
+    "n-resize" => CursorIcon::NorthResize,
+    "ne-resize" => CursorIcon::NortheastResize,
+    "nw-resize" => CursorIcon::NorthwestResize,
+    "s-resize" => CursorIcon::SouthResize,
+    "se-resize" => CursorIcon::SoutheastResize,
+    "sw-resize" => CursorIcon::SouthwestResize,
+    "w-resize" => CursorIcon::WestResize,
+    "ns-resize" => CursorIcon::NorthSouthResize,
+    "ew-resize" => CursorIcon::EastWestResize,
+    "nesw-resize" => CursorIcon::NortheastSouthwestResize,
+    "nwse-resize" => CursorIcon::NorthwestSoutheastResize,
+    "col-resize" => CursorIcon::ColumnResize,
+    "row-resize" => CursorIcon::RowResize,
+    "all-scroll" => CursorIcon::AllScroll,
+    "zoom-in" => CursorIcon::ZoomIn,
+    "zoom-out" => CursorIcon::ZoomOut,
+    "context-menu" => CursorIcon::ContextMenu,
+    "cell" => CursorIcon::Cell,
+    "vertical-text" => CursorIcon::VerticalText,
+    "alias" => CursorIcon::Alias,
+    "copy" => CursorIcon::Copy,
+    "no-drop" => CursorIcon::NoDrop,
+    "grab" => CursorIcon::Grab,
+    "grabbing" => CursorIcon::Grabbing,
+    _ => return None,
+  })
+}
+
+/// Width and height limits of a window's inner size; `None` leaves an axis unlimited.
+type SizeLimit = (Option<f64>, Option<f64>);
+
+/// Window properties tao has no getter for, as last set through this crate.
+#[derive(Debug, Clone, Copy)]
+struct TrackedWindowState {
+  opacity: f64,
+  cursor_icon: CursorIcon,
+  cursor_visible: bool,
+  level: WindowLevel,
+  resize_increments: Option<(f64, f64)>,
+  transparent: bool,
+  blur: bool,
+  theme: Option<TaoTheme>,
+  excluded_from_capture: bool,
+  /// Top, left, bottom and right insets set with `set_content_insets`
+  content_insets: Option<(f64, f64, f64, f64)>,
+  /// Logical minimum inner size set with `set_min_size_logical` or `set_min_inner_size`
+  min_size: SizeLimit,
+  /// Logical maximum inner size set with `set_max_inner_size`
+  max_size: SizeLimit,
+  ignore_cursor_events: bool,
+  cursor_grab: CursorGrabMode,
+  /// Width / height ratio set with `set_aspect_ratio`
+  aspect_ratio: Option<f64>,
+  closed: bool,
+}
+
+impl Default for TrackedWindowState {
+  fn default() -> Self {
+    Self {
+      opacity: 1.0,
+      cursor_icon: CursorIcon::Default,
+      cursor_visible: true,
+      level: WindowLevel::Normal,
+      resize_increments: None,
+      transparent: false,
+      blur: false,
+      theme: None,
+      excluded_from_capture: false,
+      content_insets: None,
+      min_size: (None, None),
+      max_size: (None, None),
+      ignore_cursor_events: false,
+      cursor_grab: CursorGrabMode::None,
+      aspect_ratio: None,
+      closed: false,
+    }
+  }
+}
+
+impl TrackedWindowState {
+  /// Records a cursor icon change; returns whether the cursor must be hidden again
+  fn apply_cursor_icon(&mut self, cursor_icon: CursorIcon) -> bool {
+    self.cursor_icon = cursor_icon;
+    !self.cursor_visible
+  }
+}
+
+/// Tracked state of each window, keyed by window id.
+static WINDOW_STATE: std::sync::LazyLock<
+  Mutex<std::collections::HashMap<u64, TrackedWindowState>>,
+> = std::sync::LazyLock::new(|| Mutex::new(std::collections::HashMap::new()));
+
+fn tracked_window_state(window: &tao::window::Window) -> TrackedWindowState {
+  let key = crate::tao::render::render_state_key(window);
+  WINDOW_STATE
+    .lock()
+    .unwrap()
+    .get(&key)
+    .copied()
+    .unwrap_or_default()
+}
+
+fn update_tracked_window_state(
+  window: &tao::window::Window,
+  update: impl FnOnce(&mut TrackedWindowState),
+) {
+  let key = crate::tao::render::render_state_key(window);
+  update(WINDOW_STATE.lock().unwrap().entry(key).or_default());
+}
+
+/// Whether a window is the last open one, so its close request ends a loop
+/// that exits on last window closed
+///
+/// Every window built by this crate is tracked from the start (its level is
+/// set when it is built), and stops counting once closed with `Window.close` or
+/// dropped.
+fn is_last_open_window(window_id: tao::window::WindowId) -> bool {
+  let key = crate::tao::render::window_id_key(window_id);
+  !any_other_window_open(&WINDOW_STATE.lock().unwrap(), key)
+}
+
+/// Whether a tracked window other than `key` is not closed
+fn any_other_window_open(
+  states: &std::collections::HashMap<u64, TrackedWindowState>,
+  key: u64,
+) -> bool {
+  states
+    .iter()
+    .any(|(other, state)| *other != key && !state.closed)
+}
+
+/// Returns the level of a window built with the given always-on-top and
+/// always-on-bottom flags; always on top wins if both are set.
+pub(crate) fn window_level_from_flags(always_on_top: bool, always_on_bottom: bool) -> WindowLevel {
+  if always_on_top {
+    WindowLevel::AlwaysOnTop
+  } else if always_on_bottom {
+    WindowLevel::AlwaysOnBottom
+  } else {
+    WindowLevel::Normal
+  }
+}
+
+/// Returns the level after enabling or disabling `target`
+///
+/// Disabling a level the window is not at leaves it unchanged.
+fn toggled_window_level(current: WindowLevel, target: WindowLevel, enabled: bool) -> WindowLevel {
+  if enabled {
+    target
+  } else if current == target {
+    WindowLevel::Normal
+  } else {
+    current
+  }
+}
+
+/// Sets and tracks the level of a window, both when it is built and later on.
+pub(crate) fn apply_window_level(window: &tao::window::Window, level: WindowLevel) {
+  let on_top = level == WindowLevel::AlwaysOnTop;
+  let on_bottom = level == WindowLevel::AlwaysOnBottom;
+  // Clear the other level first, so both are never enabled at once
+  if !on_top {
+    window.set_always_on_top(false);
+  }
+  if !on_bottom {
+    window.set_always_on_bottom(false);
+  }
+  if on_top {
+    window.set_always_on_top(true);
+  }
+  if on_bottom {
+    window.set_always_on_bottom(true);
+  }
+  update_tracked_window_state(window, |state| state.level = level);
+}
+
+fn window_opacity(window: &tao::window::Window) -> f64 {
+  tracked_window_state(window).opacity
+}
+
+fn set_window_opacity(window: &tao::window::Window, opacity: f64) {
+  let opacity = opacity.clamp(0.0, 1.0);
+  update_tracked_window_state(window, |state| state.opacity = opacity);
+
+  #[cfg(target_os = "windows")]
+  {
+    use tao::platform::windows::WindowExtWindows;
+
+    let hwnd = window.hwnd();
+    let ex_style = win32::ex_style(hwnd);
+    if ex_style & WS_EX_LAYERED == 0 {
+      win32::set_ex_style(hwnd, ex_style | WS_EX_LAYERED);
+    }
+    win32::set_layered_alpha(hwnd, (opacity * 255.0).round() as u8);
+  }
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use gtk::prelude::*;
+    use tao::platform::unix::WindowExtUnix;
+
+    window.gtk_window().set_opacity(opacity);
+  }
+  #[cfg(target_os = "macos")]
+  {
+    use tao::platform::macos::WindowExtMacOS;
+
+    appkit::set_alpha_value(window.ns_window(), opacity);
+  }
+}
+
+/// Normalizes per-axis resize increments; `None` when neither axis has one.
+///
+/// An axis without a (positive, finite) increment resizes freely, i.e. by 1.
+fn normalize_resize_increments(width: Option<f64>, height: Option<f64>) -> Option<(f64, f64)> {
+  let valid = |value: Option<f64>| value.filter(|value| value.is_finite() && *value > 0.0);
+  let (width, height) = (valid(width), valid(height));
+  if width.is_none() && height.is_none() {
+    return None;
+  }
+  Some((width.unwrap_or(1.0), height.unwrap_or(1.0)))
+}
+
+/// Converts logical resize increments to the whole pixels GTK geometry hints take.
+#[cfg_attr(
+  not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )),
+  allow(dead_code)
+)]
+fn gtk_resize_increments(increments: (f64, f64)) -> (i32, i32) {
+  let hint = |value: f64| value.round().max(1.0) as i32;
+  (hint(increments.0), hint(increments.1))
+}
+
+/// Sets the GTK geometry hints of a window from logical resize increments,
+/// logical minimum and maximum sizes and an aspect ratio.
+///
+/// GTK replaces all hints on every call, so all are always applied together.
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn set_gtk_geometry_hints(
+  gtk_window: &gtk::ApplicationWindow,
+  increments: Option<(f64, f64)>,
+  min_size: SizeLimit,
+  max_size: SizeLimit,
+  aspect_ratio: Option<f64>,
+) {
+  use gtk::gdk::WindowHints;
+  use gtk::prelude::*;
+
+  let (width_inc, height_inc) = increments.map(gtk_resize_increments).unwrap_or((1, 1));
+  // GTK takes both dimensions of a limit; unset ones don't limit
+  let min_hint = |size: Option<f64>| size.map_or(0, gtk_size_hint);
+  let max_hint = |size: Option<f64>| size.map_or(i32::MAX, gtk_size_hint);
+  let geometry = gtk::gdk::Geometry::new(
+    min_hint(min_size.0),
+    min_hint(min_size.1),
+    max_hint(max_size.0),
+    max_hint(max_size.1),
+    0,
+    0,
+    width_inc,
+    height_inc,
+    aspect_ratio.unwrap_or(0.0),
+    aspect_ratio.unwrap_or(0.0),
+    gtk::gdk::Gravity::NorthWest,
+  );
+  let mut mask = WindowHints::empty();
+  if increments.is_some() {
+    mask |= WindowHints::BASE_SIZE | WindowHints::RESIZE_INC;
+  }
+  if min_size != (None, None) {
+    mask |= WindowHints::MIN_SIZE;
+  }
+  if max_size != (None, None) {
+    mask |= WindowHints::MAX_SIZE;
+  }
+  if aspect_ratio.is_some() {
+    mask |= WindowHints::ASPECT;
+  }
+  gtk_window.set_geometry_hints(None::<&gtk::Window>, Some(&geometry), mask);
+}
+
+/// Sets the tracked GTK geometry hints of a window again once GTK is idle
+///
+/// tao applies size constraints from a request on the GTK main loop, setting
+/// only the minimum and maximum sizes, which drops the resize increment and
+/// aspect ratio hints. The idle source runs after that request.
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn restore_gtk_geometry_hints_when_idle(window: &tao::window::Window) {
+  use tao::platform::unix::WindowExtUnix;
+
+  let key = crate::tao::render::render_state_key(window);
+  let gtk_window = window.gtk_window().clone();
+  gtk::glib::idle_add_local_once(move || {
+    // Read when run, so hints set meanwhile are kept; a closed window has none
+    let Some(state) = WINDOW_STATE.lock().unwrap().get(&key).copied() else {
+      return;
+    };
+    set_gtk_geometry_hints(
+      &gtk_window,
+      state.resize_increments,
+      state.min_size,
+      state.max_size,
+      state.aspect_ratio,
+    );
+  });
+}
+
+/// Converts a logical size limit to a GTK geometry hint, saturating huge sizes
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn gtk_size_hint(size: f64) -> i32 {
+  size.round().min(i32::MAX as f64) as i32
+}
+
+/// Applies and records a logical minimum inner size, or removes it with `None`.
+pub(crate) fn apply_min_size(window: &tao::window::Window, min_size: Option<(f64, f64)>) {
+  let max_size = tracked_window_state(window).max_size;
+  let min_size = min_size.map_or((None, None), |(width, height)| (Some(width), Some(height)));
+  apply_size_limits(window, min_size, max_size);
+}
+
+/// Applies and records logical minimum and maximum inner sizes.
+fn apply_size_limits(window: &tao::window::Window, min_size: SizeLimit, max_size: SizeLimit) {
+  update_tracked_window_state(window, |state| {
+    state.min_size = min_size;
+    state.max_size = max_size;
+  });
+  let unit = |size: Option<f64>| size.map(|size| tao::dpi::LogicalUnit::new(size).into());
+  window.set_inner_size_constraints(tao::window::WindowSizeConstraints::new(
+    unit(min_size.0),
+    unit(min_size.1),
+    unit(max_size.0),
+    unit(max_size.1),
+  ));
+  // tao's constraints replace the other geometry hints on GTK
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    let state = tracked_window_state(window);
+    if state.resize_increments.is_some() || state.aspect_ratio.is_some() {
+      restore_gtk_geometry_hints_when_idle(window);
+    }
+  }
+}
+
+/// Splits size constraints into minimum and maximum sizes in physical pixels.
+fn size_constraint_limits(constraints: &WindowSizeConstraints) -> (SizeLimit, SizeLimit) {
+  (
+    (
+      constraints.min_width.map(f64::from),
+      constraints.min_height.map(f64::from),
+    ),
+    (
+      constraints.max_width.map(f64::from),
+      constraints.max_height.map(f64::from),
+    ),
+  )
+}
+
+/// Converts a size from JS to a limit on both dimensions, or none for `null`.
+fn physical_size_limit(size: Option<Size>) -> SizeLimit {
+  size.map_or((None, None), |size| (Some(size.width), Some(size.height)))
+}
+
+/// Clamps a minimum (`is_min`) or maximum size limit so it doesn't cross `other`,
+/// the opposite limit, in the dimensions both set; returns `None` when no clamping
+/// was needed.
+fn clamp_size_limit(size: SizeLimit, other: SizeLimit, is_min: bool) -> Option<SizeLimit> {
+  let clamp = |size: Option<f64>, other: Option<f64>| match (size, other) {
+    (Some(size), Some(other)) if is_min => Some(size.min(other)),
+    (Some(size), Some(other)) => Some(size.max(other)),
+    _ => size,
+  };
+  let clamped = (clamp(size.0, other.0), clamp(size.1, other.1));
+  (clamped != size).then_some(clamped)
+}
+
+/// Formats a size limit in physical pixels for warnings.
+fn size_limit_label(size: SizeLimit, scale_factor: f64) -> String {
+  let dimension = |size: Option<f64>| {
+    size.map_or("unlimited".to_string(), |size| {
+      (size * scale_factor).to_string()
+    })
+  };
+  format!("{}x{}", dimension(size.0), dimension(size.1))
+}
+
+/// Converts a physical size limit from JS to logical pixels, clamped against the
+/// opposite limit with a warning.
+fn logical_size_limit(
+  window: &tao::window::Window,
+  size: SizeLimit,
+  other: SizeLimit,
+  is_min: bool,
+) -> SizeLimit {
+  let scale_factor = window.scale_factor();
+  let size = (
+    size.0.map(|width| width.max(0.0) / scale_factor),
+    size.1.map(|height| height.max(0.0) / scale_factor),
+  );
+  match clamp_size_limit(size, other, is_min) {
+    Some(clamped) => {
+      log_warn!(
+        "window",
+        "{} inner size {} crosses the {} size; clamped to {}",
+        if is_min { "Minimum" } else { "Maximum" },
+        size_limit_label(size, scale_factor),
+        if is_min { "maximum" } else { "minimum" },
+        size_limit_label(clamped, scale_factor)
+      );
+      clamped
+    }
+    None => size,
+  }
+}
+
+/// Applies logical resize increments to a window; returns `false` where unsupported.
+fn apply_resize_increments(window: &tao::window::Window, increments: Option<(f64, f64)>) -> bool {
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use tao::platform::unix::WindowExtUnix;
+
+    let state = tracked_window_state(window);
+    set_gtk_geometry_hints(
+      window.gtk_window(),
+      increments,
+      state.min_size,
+      state.max_size,
+      state.aspect_ratio,
+    );
+    true
+  }
+  #[cfg(target_os = "macos")]
+  {
+    use tao::platform::macos::WindowExtMacOS;
+
+    let (width, height) = increments.unwrap_or((1.0, 1.0));
+    appkit::set_content_resize_increments(window.ns_window(), width, height);
+    true
+  }
+  #[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "macos"
+  )))]
+  {
+    let _ = (window, increments);
+    false
+  }
+}
+
+/// Returns the top, left, bottom and right insets between a content view of
+/// `content` size and its layout rect, both with a bottom-left origin.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn insets_from_layout(content: (f64, f64), layout: (f64, f64, f64, f64)) -> (f64, f64, f64, f64) {
+  let (width, height) = content;
+  let (x, y, layout_width, layout_height) = layout;
+  (
+    (height - y - layout_height).max(0.0),
+    x.max(0.0),
+    y.max(0.0),
+    (width - x - layout_width).max(0.0),
+  )
+}
+
+/// Returns the insets of the area not covered by platform chrome, in logical pixels.
+fn platform_content_insets(window: &tao::window::Window) -> (f64, f64, f64, f64) {
+  #[cfg(target_os = "macos")]
+  {
+    use tao::platform::macos::WindowExtMacOS;
+
+    // Both rects are in window coordinates, and the content view starts at the origin
+    let (_, _, width, height) = appkit::view_frame(window.ns_view());
+    insets_from_layout(
+      (width, height),
+      appkit::content_layout_rect(window.ns_window()),
+    )
+  }
+  #[cfg(not(target_os = "macos"))]
+  {
+    let _ = window;
+    (0.0, 0.0, 0.0, 0.0)
+  }
+}
+
+/// Sets the large (`ICON_BIG`) icon of a window on Windows.
+fn apply_taskbar_icon(window: &tao::window::Window, icon: tao::window::Icon) {
+  #[cfg(target_os = "windows")]
+  {
+    use tao::platform::windows::WindowExtWindows;
+    window.set_taskbar_icon(Some(icon));
+  }
+  #[cfg(not(target_os = "windows"))]
+  {
+    let _ = (window, icon);
+  }
+}
+
+/// Applies the capture exclusion hint; returns whether the platform accepted it.
+fn apply_capture_exclusion(window: &tao::window::Window, excluded: bool) -> bool {
+  #[cfg(target_os = "windows")]
+  {
+    use tao::platform::windows::WindowExtWindows;
+
+    // Not through tao's `set_content_protection`, which discards whether the
+    // affinity is supported (Windows 10 2004+)
+    win32::set_capture_excluded(window.hwnd(), excluded)
+  }
+  #[cfg(target_os = "macos")]
+  {
+    // tao sets the `sharingType` to none, or back to read-only
+    window.set_content_protection(excluded);
+    true
+  }
+  #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+  {
+    let _ = (window, excluded);
+    false
+  }
+}
+
+/// Returns the outer position that centers a window of `outer` size in an area.
+///
+/// A window larger than the area is aligned to its top-left corner so the title
+/// bar stays reachable.
+fn centered_position(
+  area_position: tao::dpi::PhysicalPosition<i32>,
+  area_size: tao::dpi::PhysicalSize<u32>,
+  outer: tao::dpi::PhysicalSize<u32>,
+) -> tao::dpi::PhysicalPosition<i32> {
+  let offset = |area: u32, window: u32| (area.saturating_sub(window) / 2) as i32;
+  tao::dpi::PhysicalPosition::new(
+    area_position.x + offset(area_size.width, outer.width),
+    area_position.y + offset(area_size.height, outer.height),
+  )
+}
+
+/// Returns the work area of the monitor a window is on, in physical pixels.
+fn monitor_work_area(
+  window: &tao::window::Window,
+) -> Option<(tao::dpi::PhysicalPosition<i32>, tao::dpi::PhysicalSize<u32>)> {
+  #[cfg(target_os = "windows")]
+  {
+    use tao::platform::windows::WindowExtWindows;
+
+    let (left, top, right, bottom) = win32::monitor_work_area(window.hwnd())?;
+    Some((
+      tao::dpi::PhysicalPosition::new(left, top),
+      tao::dpi::PhysicalSize::new((right - left).max(0) as u32, (bottom - top).max(0) as u32),
+    ))
+  }
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use gtk::prelude::*;
+    use tao::platform::unix::WindowExtUnix;
+
+    let gdk_window = window.gtk_window().window()?;
+    let monitor = gdk_window.display().monitor_at_window(&gdk_window)?;
+    let area = monitor.workarea();
+    // GDK reports application pixels
+    let scale = monitor.scale_factor().max(1);
+    Some((
+      tao::dpi::PhysicalPosition::new(area.x() * scale, area.y() * scale),
+      tao::dpi::PhysicalSize::new(
+        (area.width() * scale).max(0) as u32,
+        (area.height() * scale).max(0) as u32,
+      ),
+    ))
+  }
+  #[cfg(target_os = "macos")]
+  {
+    use tao::platform::macos::WindowExtMacOS;
+
+    let (primary_height, (x, y, width, height)) = appkit::screen_visible_frame(window.ns_window())?;
+    // Cocoa uses a bottom-left origin on the primary screen, tao a top-left one
+    let scale = window.scale_factor();
+    let top = primary_height - (y + height);
+    Some((
+      tao::dpi::PhysicalPosition::new((x * scale).round() as i32, (top * scale).round() as i32),
+      tao::dpi::PhysicalSize::new(
+        (width * scale).round() as u32,
+        (height * scale).round() as u32,
+      ),
+    ))
+  }
+  #[cfg(not(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "macos"
+  )))]
+  {
+    let _ = window;
+    None
+  }
+}
+
+/// Computes `NSApplicationPresentationOptions` for `set_fullscreen_autohide`.
+///
+/// Adjusts the flags to a combination AppKit accepts (it raises an exception
+/// otherwise): hiding the menu bar requires hiding the Dock.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn presentation_options(menu_autohide: bool, dock_autohide: bool, fullscreen: bool) -> usize {
+  const AUTO_HIDE_DOCK: usize = 1 << 0;
+  const HIDE_DOCK: usize = 1 << 1;
+  const AUTO_HIDE_MENU_BAR: usize = 1 << 2;
+  const HIDE_MENU_BAR: usize = 1 << 3;
+  const FULL_SCREEN: usize = 1 << 10;
+
+  let menu = if menu_autohide {
+    AUTO_HIDE_MENU_BAR
+  } else {
+    HIDE_MENU_BAR
+  };
+  let dock = if dock_autohide && menu_autohide {
+    AUTO_HIDE_DOCK
+  } else {
+    HIDE_DOCK
+  };
+  let fullscreen = if fullscreen { FULL_SCREEN } else { 0 };
+  menu | dock | fullscreen
+}
+
+/// Advances running fades and fires completion callbacks.
+fn tick_fades() -> Option<std::time::Instant> {
+  let now = std::time::Instant::now();
+  let finished = FADES.with(|fades| {
+    let mut fades = fades.borrow_mut();
+    let mut finished = Vec::new();
+    let mut i = 0;
+    while i < fades.len() {
+      let fade = &fades[i];
+      let Some(window) = fade.window.upgrade() else {
+        fades.remove(i);
+        continue;
+      };
+      let elapsed = now.saturating_duration_since(fade.start);
+      let t = if fade.duration.is_zero() {
+        1.0
+      } else {
+        (elapsed.as_secs_f64() / fade.duration.as_secs_f64()).min(1.0)
+      };
+      set_window_opacity(
+        &window.lock().unwrap(),
+        fade.from + (fade.to - fade.from) * t,
+      );
+      if t >= 1.0 {
+        finished.push(fades.remove(i));
+      } else {
+        i += 1;
+      }
+    }
+    finished
+  });
+
+  for fade in finished {
+    if let Some(callback) = fade.callback {
+      let _ = callback.call(Ok(true), ThreadsafeFunctionCallMode::NonBlocking);
+    }
+  }
+
+  FADES.with(|fades| (!fades.borrow().is_empty()).then(|| now + FADE_STEP))
+}
+
+/// Forwards events to the helpers that track window state (e.g. `SplitView`).
+///
+/// Called by the event loops for every event before their own handling.
+pub(crate) fn dispatch_internal_event(event: &tao::event::Event<'_, ()>) {
+  if let tao::event::Event::WindowEvent {
+    window_id, event, ..
+  } = event
+  {
+    crate::high_level::split_views_handle_event(*window_id, event);
+    confine_cursor_on_move(*window_id, event);
+    restore_ignored_cursor_on_focus(*window_id, event);
+    track_modifiers(*window_id, event);
+    track_pointer(*window_id, event);
+    keep_aspect_ratio_on_resize(*window_id, event);
+    crate::tao::render::redraw_after_resize(*window_id, event);
+    crate::tao::render::thread::track_resize(*window_id, event);
+    crate::tao::render::release_destroyed_window(*window_id, event);
+  }
+  crate::high_level::draw_splashes_on_expose(event);
+}
+
+/// Delivers an event to the `set_on_any_event` callback, if any.
+fn dispatch_any_event(handler: Option<&AnyEventCallback>, event: &tao::event::Event<'_, ()>) {
+  if let Some(handler) = handler {
+    let _ = handler.call(
+      Ok(event_to_json(event)),
+      ThreadsafeFunctionCallMode::NonBlocking,
+    );
+  }
+}
+
+/// Delivers a window event to the `set_on_window_event` callback, if any.
+fn dispatch_window_event(handler: Option<&WindowEventCallback>, event: &tao::event::Event<'_, ()>) {
+  let (
+    Some(handler),
+    tao::event::Event::WindowEvent {
+      window_id, event, ..
+    },
+  ) = (handler, event)
+  else {
+    return;
+  };
+  if let Some(data) = WindowEventData::from_tao(*window_id, event) {
+    let _ = handler.call(Ok(data), ThreadsafeFunctionCallMode::NonBlocking);
+  }
+}
+
+/// Delivers an event to the `run_app` handler, if any.
+fn dispatch_app_event(handler: Option<&AppEventHandler>, event: &tao::event::Event<'_, ()>) {
+  if let Some(handler) = handler {
+    if let Some(app_event) = AppEvent::from_tao(event) {
+      let _ = handler.call(Ok(app_event), ThreadsafeFunctionCallMode::NonBlocking);
+    }
+  }
+}
+
+/// Runs the periodic tasks that are driven from the event loop's idle point.
+///
+/// Called by the event loops on `MainEventsCleared`, with their timers if they
+/// have any. Also runs the work posted by render threads. Returns the earliest time at which a task wants to run again, so
+/// `Wait` loops can switch to `WaitUntil`.
+pub(crate) fn run_idle_tasks(
+  timers: Option<&std::cell::RefCell<LoopTimers>>,
+) -> Option<std::time::Instant> {
+  crate::tao::render::thread::run_loop_thread_work();
+  [
+    tick_keep_on_top(),
+    tick_fades(),
+    timers.and_then(tick_timers),
+    crate::high_level::tick_splashes(),
+  ]
+  .into_iter()
+  .flatten()
+  .min()
+}
+
+/// Re-asserts always-on-top for windows registered with [`Window::keep_on_top`] that are due.
+fn tick_keep_on_top() -> Option<std::time::Instant> {
+  KEEP_ON_TOP.with(|entries| {
+    let mut entries = entries.borrow_mut();
+    let now = std::time::Instant::now();
+    entries.retain_mut(|entry| {
+      let Some(window) = entry.window.upgrade() else {
+        return false;
+      };
+      if entry.next <= now {
+        ensure_window_on_top(&window.lock().unwrap());
+        entry.next = now + entry.interval;
+      }
+      true
+    });
+    entries.iter().map(|entry| entry.next).min()
+  })
+}
+
+/// A horizontal run of visible pixels in a shape mask: `(x_start, x_end, y)`, end exclusive.
+pub(crate) type ShapeRun = (i32, i32, i32);
+
+/// Converts an alpha (or RGBA) mask into runs of visible pixels.
+fn shape_mask_runs(mask: &[u8], width: u32, height: u32) -> Result<Vec<ShapeRun>> {
+  let pixels = width as usize * height as usize;
+  let stride = if mask.len() == pixels {
+    1
+  } else if mask.len() == pixels * 4 {
+    4
+  } else {
+    return Err(napi::Error::new(
+      napi::Status::GenericFailure,
+      format!(
+        "Shape mask size mismatch: got {} bytes, expected {} (alpha) or {} (RGBA) for {}x{}",
+        mask.len(),
+        pixels,
+        pixels * 4,
+        width,
+        height
+      ),
+    ));
+  };
+  Ok(alpha_runs(mask, width, height, stride, 128))
+}
+
+/// Collects the horizontal runs of pixels with alpha >= `threshold`.
+///
+/// `stride` is the number of bytes per pixel; the alpha channel is the last one.
+pub(crate) fn alpha_runs(
+  mask: &[u8],
+  width: u32,
+  height: u32,
+  stride: usize,
+  threshold: u8,
+) -> Vec<ShapeRun> {
+  let alpha =
+    |x: u32, y: u32| mask[(y as usize * width as usize + x as usize) * stride + stride - 1];
+
+  let mut runs = Vec::new();
+  for y in 0..height {
+    let mut start = None;
+    for x in 0..=width {
+      let visible = x < width && alpha(x, y) >= threshold;
+      match (visible, start) {
+        (true, None) => start = Some(x),
+        (false, Some(x0)) => {
+          runs.push((x0 as i32, x as i32, y as i32));
+          start = None;
+        }
+        _ => {}
+      }
+    }
+  }
+  runs
+}
+
+/// Applies a window shape built from mask runs, or removes it with `None`.
+fn apply_window_shape(window: &tao::window::Window, runs: Option<&[ShapeRun]>) {
+  #[cfg(target_os = "windows")]
+  {
+    use tao::platform::windows::WindowExtWindows;
+
+    win32::set_window_region(window.hwnd(), runs);
+  }
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use gtk::prelude::*;
+    use tao::platform::unix::WindowExtUnix;
+
+    let region = runs.map(|runs| shape_region(runs.iter().copied()));
+    let gtk_window = window.gtk_window();
+    gtk_window.shape_combine_region(region.as_ref());
+    gtk_window.input_shape_combine_region(region.as_ref());
+  }
+  #[cfg(target_os = "macos")]
+  {
+    let _ = (window, runs);
+  }
+}
+
+/// Builds the region covered by mask runs
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn shape_region(runs: impl IntoIterator<Item = ShapeRun>) -> gtk::cairo::Region {
+  let rectangles: Vec<_> = runs
+    .into_iter()
+    .map(|(x0, x1, y)| gtk::cairo::RectangleInt::new(x0, y, x1 - x0, 1))
+    .collect();
+  gtk::cairo::Region::create_rectangles(&rectangles)
+}
+
+/// Converts runs of physical pixels to the logical pixels of a window with
+/// `scale_factor`, covering every partly visible logical pixel
+///
+/// Rows that land on the same logical row stay separate runs; the region they
+/// are unioned into merges them.
+#[cfg_attr(
+  not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )),
+  allow(dead_code)
+)]
+fn logical_runs(runs: &[ShapeRun], scale_factor: f64) -> Vec<ShapeRun> {
+  if scale_factor == 1.0 || !scale_factor.is_finite() || scale_factor <= 0.0 {
+    return runs.to_vec();
+  }
+  let mut logical: Vec<ShapeRun> = runs
+    .iter()
+    .map(|&(x0, x1, y)| {
+      (
+        (x0 as f64 / scale_factor).floor() as i32,
+        (x1 as f64 / scale_factor).ceil() as i32,
+        (y as f64 / scale_factor).floor() as i32,
+      )
+    })
+    .collect();
+  logical.dedup();
+  logical
+}
+
+/// Whether the pixel at `(x, y)` lies in one of `runs`, which are sorted by row
+/// as [`alpha_runs`] returns them
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn runs_contain(runs: &[ShapeRun], x: i32, y: i32) -> bool {
+  let start = runs.partition_point(|&(_, _, run_y)| run_y < y);
+  runs[start..]
+    .iter()
+    .take_while(|&&(_, _, run_y)| run_y == y)
+    .any(|&(x0, x1, _)| x >= x0 && x < x1)
+}
+
+/// Restricts where the window receives pointer input to mask runs, or removes
+/// the restriction with `None`; clicks elsewhere pass through.
+///
+/// `runs` are in physical pixels. Only the input region changes: GTK takes it in
+/// logical pixels, and on Windows the window answers hit tests outside the runs
+/// as transparent, see [`apply_hit_test_region`].
+pub(crate) fn apply_input_shape(window: &tao::window::Window, runs: Option<&[ShapeRun]>) {
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use gtk::prelude::*;
+    use tao::platform::unix::WindowExtUnix;
+
+    let region = runs.map(|runs| shape_region(logical_runs(runs, window.scale_factor())));
+    window
+      .gtk_window()
+      .input_shape_combine_region(region.as_ref());
+  }
+  #[cfg(target_os = "windows")]
+  {
+    apply_hit_test_region(window, runs);
+  }
+  #[cfg(target_os = "macos")]
+  {
+    apply_window_shape(window, runs);
+  }
+}
+
+/// Re-evaluates the input region of a window for the current cursor position
+///
+/// Windows only passes clicks to other processes while the window is
+/// `WS_EX_TRANSPARENT`, which must follow the cursor; elsewhere the region
+/// applies by itself and this does nothing.
+pub(crate) fn refresh_input_shape(window: &tao::window::Window) {
+  #[cfg(target_os = "windows")]
+  {
+    use tao::platform::windows::WindowExtWindows;
+
+    refresh_click_through(window.hwnd(), tracked_window_state(window).opacity);
+  }
+  #[cfg(not(target_os = "windows"))]
+  {
+    let _ = window;
+  }
+}
+
+/// Input region of a window, see [`apply_hit_test_region`]
+#[cfg(target_os = "windows")]
+struct HitTestRegion {
+  /// Runs of client pixels that receive input
+  runs: Vec<ShapeRun>,
+  /// Extended style bits added while the cursor is over a click-through pixel
+  added_styles: i32,
+}
+
+/// Input regions keyed by HWND
+#[cfg(target_os = "windows")]
+static HIT_TEST_REGIONS: std::sync::LazyLock<
+  Mutex<std::collections::HashMap<isize, HitTestRegion>>,
+> = std::sync::LazyLock::new(|| Mutex::new(std::collections::HashMap::new()));
+
+#[cfg(target_os = "windows")]
+const HIT_TEST_SUBCLASS_ID: usize = 0x4854_5354;
+
+#[cfg(target_os = "windows")]
+const WS_EX_TRANSPARENT: i32 = 0x0000_0020;
+#[cfg(target_os = "windows")]
+const WS_EX_LAYERED: i32 = 0x0008_0000;
+
+/// Sets the input region of a window, or removes it with `None`
+///
+/// Unlike `SetWindowRgn`, this keeps the pixels outside the runs drawn. A
+/// subclass answers `WM_NCHITTEST` there with `HTTRANSPARENT`, which passes
+/// clicks to windows of the same thread; for other processes the window is made
+/// `WS_EX_TRANSPARENT` while the cursor is over such a pixel.
+#[cfg(target_os = "windows")]
+fn apply_hit_test_region(window: &tao::window::Window, runs: Option<&[ShapeRun]>) {
+  use tao::platform::windows::WindowExtWindows;
+
+  let hwnd = window.hwnd();
+  match runs {
+    Some(runs) => {
+      HIT_TEST_REGIONS
+        .lock()
+        .unwrap()
+        .entry(hwnd)
+        .or_insert_with(|| HitTestRegion {
+          runs: Vec::new(),
+          added_styles: 0,
+        })
+        .runs = runs.to_vec();
+      win32::set_subclass(hwnd, hit_test_subclass_proc, HIT_TEST_SUBCLASS_ID, 0);
+      refresh_click_through(hwnd, tracked_window_state(window).opacity);
+    }
+    None => {
+      if let Some(region) = HIT_TEST_REGIONS.lock().unwrap().remove(&hwnd) {
+        win32::set_ex_style(hwnd, win32::ex_style(hwnd) & !region.added_styles);
+        win32::remove_subclass(hwnd, hit_test_subclass_proc, HIT_TEST_SUBCLASS_ID);
+      }
+    }
+  }
+}
+
+/// Makes the window transparent to input while the cursor is outside its input
+/// region, and opaque again once it is back inside
+#[cfg(target_os = "windows")]
+fn refresh_click_through(hwnd: isize, opacity: f64) {
+  let mut regions = HIT_TEST_REGIONS.lock().unwrap();
+  let Some(region) = regions.get_mut(&hwnd) else {
+    return;
+  };
+  let Some((x, y)) = win32::cursor_position(hwnd) else {
+    return;
+  };
+  let pass_through = !runs_contain(&region.runs, x, y);
+  let style = win32::ex_style(hwnd);
+  if pass_through && region.added_styles == 0 {
+    region.added_styles = (WS_EX_TRANSPARENT | WS_EX_LAYERED) & !style;
+    win32::set_ex_style(hwnd, style | region.added_styles);
+    // A layered window is not drawn until its attributes are set
+    if region.added_styles & WS_EX_LAYERED != 0 {
+      win32::set_layered_alpha(hwnd, (opacity * 255.0).round() as u8);
+    }
+  } else if !pass_through && region.added_styles != 0 {
+    win32::set_ex_style(hwnd, style & !region.added_styles);
+    region.added_styles = 0;
+  }
+}
+
+/// Reports client pixels outside the input region as transparent to hit tests
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn hit_test_subclass_proc(
+  window: win32::HWND,
+  msg: u32,
+  wparam: win32::WPARAM,
+  lparam: win32::LPARAM,
+  _id: usize,
+  _data: usize,
+) -> win32::LRESULT {
+  const WM_NCDESTROY: u32 = 0x0082;
+  const WM_NCHITTEST: u32 = 0x0084;
+  const HTTRANSPARENT: isize = -1;
+
+  let hwnd = win32::handle(window);
+  match msg {
+    WM_NCHITTEST => {
+      let outside =
+        win32::screen_to_client(hwnd, win32::message_point(lparam)).is_some_and(|(x, y)| {
+          HIT_TEST_REGIONS
+            .lock()
+            .unwrap()
+            .get(&hwnd)
+            .is_some_and(|region| !runs_contain(&region.runs, x, y))
+        });
+      if outside {
+        return win32::LRESULT(HTTRANSPARENT);
+      }
     }
-    Ok(())
+    WM_NCDESTROY => {
+      HIT_TEST_REGIONS.lock().unwrap().remove(&hwnd);
+      win32::remove_subclass(hwnd, hit_test_subclass_proc, HIT_TEST_SUBCLASS_ID);
+    }
+    _ => {}
   }
 
-  /// Sets whether to ignore cursor events.
-  #[napi]
-  pub fn set_ignore_cursor_events(&self, ignore: bool) -> Result<()> {
-    if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().set_ignore_cursor_events(ignore);
+  win32::DefSubclassProc(window, msg, wparam, lparam)
+}
+
+/// Custom maximize button areas keyed by HWND, as `[left, top, right, bottom]`.
+#[cfg(target_os = "windows")]
+static SNAP_BUTTON_RECTS: std::sync::LazyLock<Mutex<std::collections::HashMap<isize, [i32; 4]>>> =
+  std::sync::LazyLock::new(|| Mutex::new(std::collections::HashMap::new()));
+
+#[cfg(target_os = "windows")]
+const SNAP_SUBCLASS_ID: usize = 0x534e_4150;
+
+/// Adds or removes the snap layouts styles and hit-test subclass of a window.
+///
+/// The subclass data holds the style bits that were added, so disabling removes
+/// exactly those and leaves styles the window already had.
+#[cfg(target_os = "windows")]
+fn apply_snap_layouts(window: &tao::window::Window, enabled: bool) {
+  use tao::platform::windows::WindowExtWindows;
+
+  const WS_MAXIMIZEBOX: i32 = 0x0001_0000;
+  const WS_THICKFRAME: i32 = 0x0004_0000;
+
+  let hwnd = window.hwnd();
+  let added_styles = win32::subclass_data(hwnd, snap_subclass_proc, SNAP_SUBCLASS_ID);
+  if enabled == added_styles.is_some() {
+    return;
+  }
+  let style = win32::style(hwnd);
+  match added_styles {
+    None => {
+      let added = (WS_MAXIMIZEBOX | WS_THICKFRAME) & !style;
+      win32::set_style(hwnd, style | added);
+      win32::set_subclass(hwnd, snap_subclass_proc, SNAP_SUBCLASS_ID, added as usize);
+    }
+    Some(added) => {
+      win32::set_style(hwnd, style & !(added as i32));
+      win32::remove_subclass(hwnd, snap_subclass_proc, SNAP_SUBCLASS_ID);
     }
-    Ok(())
   }
+  win32::refresh_frame(hwnd);
+}
 
-  /// Requests a redrawing of the window.
-  #[napi]
-  pub fn request_redraw(&self) -> Result<()> {
-    if let Some(inner) = &self.inner {
-      inner.lock().unwrap().request_redraw();
+/// Forgets the custom maximize button area of a window that is going away.
+#[cfg(target_os = "windows")]
+fn forget_snap_button_rect(window: &tao::window::Window) {
+  use tao::platform::windows::WindowExtWindows;
+
+  SNAP_BUTTON_RECTS.lock().unwrap().remove(&window.hwnd());
+}
+
+/// Reports the custom maximize button as HTMAXBUTTON so the shell shows snap layouts.
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn snap_subclass_proc(
+  window: win32::HWND,
+  msg: u32,
+  wparam: win32::WPARAM,
+  lparam: win32::LPARAM,
+  _id: usize,
+  _data: usize,
+) -> win32::LRESULT {
+  const WM_NCDESTROY: u32 = 0x0082;
+  const WM_NCHITTEST: u32 = 0x0084;
+  const WM_NCLBUTTONDOWN: u32 = 0x00A1;
+  const WM_NCLBUTTONUP: u32 = 0x00A2;
+  const HTMAXBUTTON: usize = 9;
+
+  let hwnd = win32::handle(window);
+  match msg {
+    WM_NCHITTEST => {
+      let rect = SNAP_BUTTON_RECTS.lock().unwrap().get(&hwnd).copied();
+      let point = win32::screen_to_client(hwnd, win32::message_point(lparam));
+      if let (Some([left, top, right, bottom]), Some((x, y))) = (rect, point) {
+        if x >= left && x < right && y >= top && y < bottom {
+          return win32::LRESULT(HTMAXBUTTON as isize);
+        }
+      }
     }
-    Ok(())
+    WM_NCDESTROY => {
+      SNAP_BUTTON_RECTS.lock().unwrap().remove(&hwnd);
+      win32::remove_subclass(hwnd, snap_subclass_proc, SNAP_SUBCLASS_ID);
+    }
+    // There is no real maximize button, so the click is handled here
+    WM_NCLBUTTONDOWN if wparam.0 == HTMAXBUTTON => return win32::LRESULT(0),
+    WM_NCLBUTTONUP if wparam.0 == HTMAXBUTTON => {
+      win32::toggle_maximized(hwnd);
+      return win32::LRESULT(0);
+    }
+    _ => {}
   }
 
-  /// Closes the window.
-  #[napi]
-  pub fn close(&self) -> Result<()> {
-    if let Some(inner) = &self.inner {
-      inner.lock().unwrap().request_redraw();
+  win32::DefSubclassProc(window, msg, wparam, lparam)
+}
+
+/// Transparency, opacity, blur and theme of a window, see [`Window::visual_state`].
+#[napi(object)]
+pub struct VisualState {
+  /// Whether the window was created transparent.
+  pub transparent: bool,
+  /// Window opacity, from 0.0 to 1.0.
+  pub opacity: f64,
+  /// Whether the background behind the window is blurred.
+  pub blur: bool,
+  /// Explicit theme, or `null` to follow the system theme.
+  pub theme: Option<TaoTheme>,
+}
+
+impl From<TrackedWindowState> for VisualState {
+  fn from(state: TrackedWindowState) -> Self {
+    Self {
+      transparent: state.transparent,
+      opacity: state.opacity,
+      blur: state.blur,
+      theme: state.theme,
     }
-    Ok(())
   }
 }
 
+/// Outcome of creating one window with [`WindowBuilder::build_many`].
+#[napi(object, object_from_js = false)]
+pub struct WindowBuildResult {
+  /// The created window, if creation succeeded.
+  pub window: Option<Window>,
+  /// Why the window could not be created, if it failed.
+  pub error: Option<String>,
+}
+
 /// Builder for creating windows.
 #[napi]
 pub struct WindowBuilder {
@@ -962,6 +5215,8 @@ impl WindowBuilder {
         resizable: true,
         decorated: true,
         always_on_top: false,
+        always_on_bottom: false,
+        resize_increments: None,
         visible: true,
         transparent: false,
         maximized: false,
@@ -969,6 +5224,7 @@ impl WindowBuilder {
         menubar: true,
         icon: None,
         theme: None,
+        size_constraints: None,
       },
       inner: None,
     })
@@ -1023,9 +5279,40 @@ impl WindowBuilder {
   }
 
   /// Sets whether the window is always on top.
+  ///
+  /// Mutually exclusive with `with_always_on_bottom`: enabling one disables the other.
   #[napi]
   pub fn with_always_on_top(&mut self, always_on_top: bool) -> Result<&Self> {
     self.attributes.always_on_top = always_on_top;
+    if always_on_top {
+      self.attributes.always_on_bottom = false;
+    }
+    Ok(self)
+  }
+
+  /// Sets whether the window is always below other windows, e.g. for a live wallpaper.
+  ///
+  /// Mutually exclusive with `with_always_on_top`: enabling one disables the other.
+  #[napi]
+  pub fn with_always_on_bottom(&mut self, always_on_bottom: bool) -> Result<&Self> {
+    self.attributes.always_on_bottom = always_on_bottom;
+    if always_on_bottom {
+      self.attributes.always_on_top = false;
+    }
+    Ok(self)
+  }
+
+  /// Sets the steps, in logical pixels, by which the user resizes the window.
+  ///
+  /// See `Window.setResizeIncrements`; ignored on Windows.
+  #[napi]
+  pub fn with_resize_increments(
+    &mut self,
+    width: Option<f64>,
+    height: Option<f64>,
+  ) -> Result<&Self> {
+    self.attributes.resize_increments =
+      normalize_resize_increments(width, height).map(|(width, height)| Size { width, height });
     Ok(self)
   }
 
@@ -1082,6 +5369,47 @@ impl WindowBuilder {
     Ok(self)
   }
 
+  /// Sets minimum and maximum inner sizes in physical pixels.
+  ///
+  /// A limit with one dimension left out doesn't constrain that dimension. A
+  /// maximum smaller than the minimum is clamped to it, with a warning.
+  #[napi]
+  pub fn with_size_constraints(&mut self, constraints: WindowSizeConstraints) -> Result<&Self> {
+    self.attributes.size_constraints = Some(constraints);
+    Ok(self)
+  }
+
+  /// Builds one window per entry of `attributes` on the same event loop.
+  ///
+  /// Returns one result per entry, in order. A window that fails to build does not
+  /// stop the others: its result holds the error instead of a window. This
+  /// builder's own attributes are not used.
+  #[napi]
+  pub fn build_many(
+    event_loop: &EventLoop,
+    attributes: Vec<WindowAttributes>,
+  ) -> Vec<WindowBuildResult> {
+    attributes
+      .into_iter()
+      .map(|attributes| {
+        let mut builder = WindowBuilder {
+          attributes,
+          inner: None,
+        };
+        match builder.build(event_loop) {
+          Ok(window) => WindowBuildResult {
+            window: Some(window),
+            error: None,
+          },
+          Err(error) => WindowBuildResult {
+            window: None,
+            error: Some(error.reason),
+          },
+        }
+      })
+      .collect()
+  }
+
   /// Builds the window.
   #[napi]
   pub fn build(&mut self, event_loop: &EventLoop) -> Result<Window> {
@@ -1096,9 +5424,11 @@ impl WindowBuilder {
     // Detect platform information
     let platform_info = crate::tao::platform::platform_info();
 
-    println!(
+    log_debug!(
+      "window",
       "Building window with transparency: {}, platform: {:?}",
-      self.attributes.transparent, platform_info.display_server
+      self.attributes.transparent,
+      platform_info.display_server
     );
 
     let mut builder = tao::window::WindowBuilder::new()
@@ -1109,7 +5439,6 @@ impl WindowBuilder {
       ))
       .with_resizable(self.attributes.resizable)
       .with_decorations(self.attributes.decorated)
-      .with_always_on_top(self.attributes.always_on_top)
       .with_visible(self.attributes.visible)
       .with_transparent(self.attributes.transparent);
 
@@ -1153,6 +5482,15 @@ impl WindowBuilder {
     // Set position if provided
     if let Some(x) = self.attributes.x {
       if let Some(y) = self.attributes.y {
+        if !platform_info.supports_positioning {
+          log_warn!(
+            "window",
+            "Window positioning is not supported on {:?}, position ({}, {}) may be ignored",
+            platform_info.display_server,
+            x,
+            y
+          );
+        }
         builder = builder.with_position(tao::dpi::LogicalPosition::new(x, y));
       }
     }
@@ -1178,8 +5516,820 @@ impl WindowBuilder {
       )
     })?;
 
+    // Match the titlebar to the requested theme
+    #[cfg(target_os = "windows")]
+    {
+      if let Some(theme) = &self.attributes.theme {
+        apply_titlebar_dark(&window, matches!(theme, TaoTheme::Dark));
+      }
+    }
+
+    apply_window_level(
+      &window,
+      window_level_from_flags(
+        self.attributes.always_on_top,
+        self.attributes.always_on_bottom,
+      ),
+    );
+    if self.attributes.transparent {
+      update_tracked_window_state(&window, |state| state.transparent = true);
+    }
+    if let Some(size) = &self.attributes.resize_increments {
+      let increments = normalize_resize_increments(Some(size.width), Some(size.height));
+      if apply_resize_increments(&window, increments) {
+        update_tracked_window_state(&window, |state| state.resize_increments = increments);
+      }
+    }
+    if let Some(constraints) = &self.attributes.size_constraints {
+      let (min, max) = size_constraint_limits(constraints);
+      let min_size = logical_size_limit(&window, min, (None, None), true);
+      let max_size = logical_size_limit(&window, max, min_size, false);
+      apply_size_limits(&window, min_size, max_size);
+    }
+
     Ok(Window {
       inner: Some(Arc::new(Mutex::new(window))),
     })
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_requested_size_snaps_to_aspect_ratio() {
+    let ratio = 16.0 / 9.0;
+    for (width, height) in [(800.0, 800.0), (1920.0, 600.0), (333.0, 1000.0)] {
+      let (granted_width, granted_height) = fit_to_aspect_ratio(width, height, ratio);
+      assert!(granted_width <= width && granted_height <= height);
+      assert!(conforms_to_aspect_ratio(
+        granted_width,
+        granted_height,
+        ratio
+      ));
+    }
+    assert_eq!(fit_to_aspect_ratio(800.0, 800.0, ratio), (800.0, 450.0));
+    assert!(!conforms_to_aspect_ratio(800.0, 800.0, ratio));
+  }
+
+  #[test]
+  fn test_interactive_resize_follows_dragged_edge() {
+    let ratio = 2.0;
+    // Dragging the right edge keeps the width, the bottom edge the height
+    assert_eq!(
+      snap_resize_to_aspect_ratio((600.0, 200.0), Some((400.0, 200.0)), ratio),
+      Some((600.0, 300.0))
+    );
+    assert_eq!(
+      snap_resize_to_aspect_ratio((400.0, 300.0), Some((400.0, 200.0)), ratio),
+      Some((600.0, 300.0))
+    );
+    assert_eq!(
+      snap_resize_to_aspect_ratio((401.0, 200.0), None, ratio),
+      None
+    );
+  }
+
+  #[test]
+  fn test_cursor_confinement_clamps_outside_points() {
+    let confinement = CursorConfinement {
+      window: std::sync::Weak::new(),
+      left: 10.0,
+      top: 20.0,
+      right: 110.0,
+      bottom: 70.0,
+    };
+    assert_eq!(confinement.clamp(50.0, 40.0), None);
+    assert_eq!(confinement.clamp(5.0, 40.0), Some((10.0, 40.0)));
+    assert_eq!(confinement.clamp(200.0, 100.0), Some((110.0, 70.0)));
+  }
+
+  #[test]
+  fn test_keyboard_modifiers_and_key_names() {
+    use tao::keyboard::{Key, ModifiersState as TaoModifiers};
+
+    assert!(modifiers_list(TaoModifiers::empty()).is_empty());
+    assert_eq!(
+      modifiers_list(TaoModifiers::SUPER | TaoModifiers::SHIFT),
+      vec![ModifiersState::Shift, ModifiersState::Super]
+    );
+    assert_eq!(
+      modifiers_list(TaoModifiers::all()),
+      vec![
+        ModifiersState::Shift,
+        ModifiersState::Control,
+        ModifiersState::Alt,
+        ModifiersState::Super
+      ]
+    );
+    assert_eq!(key_name(&Key::Character("a")), "a");
+    assert_eq!(key_name(&Key::Enter), "Enter");
+  }
+
+  #[test]
+  #[allow(deprecated)]
+  fn test_mouse_input_reports_last_cursor_position_and_clicks() {
+    use tao::event::{DeviceId, ElementState, WindowEvent as TaoWindowEvent};
+
+    let window_id = unsafe { tao::window::WindowId::dummy() };
+    let device_id = unsafe { DeviceId::dummy() };
+    let moved = TaoWindowEvent::CursorMoved {
+      device_id,
+      position: tao::dpi::PhysicalPosition::new(10.0, 20.0),
+      modifiers: Default::default(),
+    };
+    let button = |state| TaoWindowEvent::MouseInput {
+      device_id,
+      state,
+      button: tao::event::MouseButton::Left,
+      modifiers: Default::default(),
+    };
+    let mouse = |event: &TaoWindowEvent| {
+      track_pointer(window_id, event);
+      WindowEventData::from_tao(window_id, event)
+        .unwrap()
+        .mouse
+        .unwrap()
+    };
+
+    let data = mouse(&moved);
+    assert!(data.button.is_none());
+    assert_eq!((data.position.x, data.position.y), (10.0, 20.0));
+    assert_eq!(data.click_count, 0);
+
+    let data = mouse(&button(ElementState::Pressed));
+    assert!(matches!(data.button, Some(MouseButton::Left)));
+    assert!(matches!(data.state, Some(MouseButtonState::Pressed)));
+    assert_eq!((data.position.x, data.position.y), (10.0, 20.0));
+    assert_eq!(data.click_count, 1);
+    assert_eq!(mouse(&button(ElementState::Released)).click_count, 1);
+    assert_eq!(mouse(&button(ElementState::Pressed)).click_count, 2);
+
+    track_pointer(window_id, &TaoWindowEvent::Destroyed);
+    assert_eq!(window_pointer(window_id).click_count, 0);
+  }
+
+  #[test]
+  #[allow(deprecated)]
+  fn test_mouse_wheel_line_deltas_are_converted_to_pixels() {
+    use tao::event::{DeviceId, MouseScrollDelta, TouchPhase, WindowEvent as TaoWindowEvent};
+
+    let window_id = unsafe { tao::window::WindowId::dummy() };
+    let wheel = |delta| {
+      let event = TaoWindowEvent::MouseWheel {
+        device_id: unsafe { DeviceId::dummy() },
+        delta,
+        phase: TouchPhase::Moved,
+        modifiers: Default::default(),
+      };
+      let data = WindowEventData::from_tao(window_id, &event).unwrap();
+      assert!(matches!(data.event, WindowEvent::MouseWheel));
+      data.wheel.unwrap()
+    };
+
+    let lines = wheel(MouseScrollDelta::LineDelta(0.0, -2.0));
+    assert_eq!(lines.unit, ScrollUnit::Line);
+    assert_eq!(
+      (lines.delta.x, lines.delta.y),
+      (0.0, -2.0 * crate::tao::input::PIXELS_PER_LINE)
+    );
+    assert_eq!(lines.lines.map(|l| (l.x, l.y)), Some((0.0, -2.0)));
+
+    let pixels = wheel(MouseScrollDelta::PixelDelta(
+      tao::dpi::PhysicalPosition::new(3.5, 12.25),
+    ));
+    assert_eq!(pixels.unit, ScrollUnit::Pixel);
+    assert_eq!((pixels.delta.x, pixels.delta.y), (3.5, 12.25));
+    assert!(pixels.lines.is_none());
+  }
+
+  #[test]
+  fn test_exclusive_video_mode_prefers_current_resolution() {
+    let modes = [
+      (1280, 720, 32, 144),
+      (1920, 1080, 32, 60),
+      (1920, 1080, 32, 120),
+      (1920, 1080, 16, 120),
+      (2560, 1440, 32, 60),
+    ];
+    assert_eq!(pick_video_mode(&modes, (1920, 1080)), Some(2));
+    // Without the current resolution, the largest mode wins
+    assert_eq!(pick_video_mode(&modes, (3840, 2160)), Some(4));
+    // No modes, as on Linux: borderless fallback
+    assert_eq!(pick_video_mode(&[], (1920, 1080)), None);
+  }
+
+  #[test]
+  fn test_clamp_size_limit() {
+    // A minimum above the maximum is lowered to it, per dimension
+    assert_eq!(
+      clamp_size_limit((Some(900.0), Some(300.0)), (Some(800.0), Some(600.0)), true),
+      Some((Some(800.0), Some(300.0)))
+    );
+    // A maximum below the minimum is raised to it
+    assert_eq!(
+      clamp_size_limit(
+        (Some(200.0), Some(100.0)),
+        (Some(320.0), Some(240.0)),
+        false
+      ),
+      Some((Some(320.0), Some(240.0)))
+    );
+    assert_eq!(
+      clamp_size_limit((Some(400.0), Some(300.0)), (Some(800.0), Some(600.0)), true),
+      None
+    );
+    assert_eq!(
+      clamp_size_limit((Some(400.0), Some(300.0)), (None, None), false),
+      None
+    );
+    // Only dimensions limited on both sides are clamped
+    assert_eq!(
+      clamp_size_limit((Some(900.0), None), (None, Some(600.0)), true),
+      None
+    );
+  }
+
+  #[test]
+  fn test_size_constraint_limits() {
+    let (min, max) = size_constraint_limits(&WindowSizeConstraints {
+      min_width: Some(320),
+      min_height: None,
+      max_width: None,
+      max_height: None,
+    });
+    // Dimensions left out stay unlimited
+    assert_eq!(min, (Some(320.0), None));
+    assert_eq!(max, (None, None));
+
+    let (min, max) = size_constraint_limits(&WindowSizeConstraints {
+      min_width: None,
+      min_height: None,
+      max_width: None,
+      max_height: Some(720),
+    });
+    assert_eq!(min, (None, None));
+    assert_eq!(max, (None, Some(720.0)));
+  }
+
+  #[test]
+  fn test_find_video_mode_matches_exactly() {
+    let modes = [
+      (1920, 1080, 32, 60),
+      (1920, 1080, 32, 144),
+      (1280, 720, 32, 60),
+    ];
+    let mode = |width: f64, height: f64, refresh_rate: u32| VideoMode {
+      size: Size { width, height },
+      bit_depth: 32,
+      refresh_rate,
+    };
+    assert_eq!(find_video_mode(&modes, &mode(1920.0, 1080.0, 144)), Some(1));
+    assert_eq!(find_video_mode(&modes, &mode(1280.0, 720.0, 60)), Some(2));
+    assert_eq!(find_video_mode(&modes, &mode(1280.0, 720.0, 144)), None);
+    assert_eq!(find_video_mode(&[], &mode(1920.0, 1080.0, 60)), None);
+  }
+
+  #[test]
+  fn test_normalized_force_is_clamped() {
+    assert_eq!(normalized_force(&tao::event::Force::Normalized(0.25)), 0.25);
+    assert_eq!(normalized_force(&tao::event::Force::Normalized(1.5)), 1.0);
+    assert_eq!(
+      normalized_force(&tao::event::Force::Normalized(f64::NAN)),
+      0.0
+    );
+  }
+
+  #[test]
+  fn test_monitors_sort_primary_first_then_by_position() {
+    let monitor = |x: f64, y: f64, is_primary: bool| MonitorInfo {
+      name: None,
+      size: Size {
+        width: 1920.0,
+        height: 1080.0,
+      },
+      position: Position { x, y },
+      scale_factor: 1.0,
+      is_primary,
+    };
+    let mut monitors = vec![
+      monitor(1920.0, 0.0, false),
+      monitor(0.0, 0.0, true),
+      monitor(-1920.0, 0.0, false),
+      monitor(-1920.0, -1080.0, false),
+    ];
+    sort_monitors(&mut monitors);
+    let order: Vec<(f64, f64)> = monitors
+      .iter()
+      .map(|monitor| (monitor.position.x, monitor.position.y))
+      .collect();
+    assert_eq!(
+      order,
+      [
+        (0.0, 0.0),
+        (-1920.0, -1080.0),
+        (-1920.0, 0.0),
+        (1920.0, 0.0)
+      ]
+    );
+  }
+
+  #[test]
+  fn test_centered_position() {
+    use tao::dpi::{PhysicalPosition, PhysicalSize};
+
+    // Secondary monitor left of the primary, with a 40px taskbar at the top
+    let position = PhysicalPosition::new(-1920, 40);
+    let size = PhysicalSize::new(1920, 1040);
+    assert_eq!(
+      centered_position(position, size, PhysicalSize::new(800, 600)),
+      PhysicalPosition::new(-1360, 260)
+    );
+    // Larger than the work area: pinned to its top-left corner
+    assert_eq!(
+      centered_position(position, size, PhysicalSize::new(2000, 1200)),
+      position
+    );
+  }
+
+  #[test]
+  fn test_fractional_resize_increments_at_scale_1_5() {
+    // Half-pixel cell sizes on a 1.5x display
+    let increments = normalize_resize_increments(Some(7.5), Some(15.25)).unwrap();
+    assert_eq!(increments, (7.5, 15.25));
+    let physical = tao::dpi::LogicalSize::new(increments.0, increments.1).to_physical::<u32>(1.5);
+    assert_eq!((physical.width, physical.height), (11, 23));
+    assert_eq!(gtk_resize_increments(increments), (8, 15));
+
+    // Tiny or missing axes resize freely; both missing clears the increments
+    assert_eq!(gtk_resize_increments((0.4, 1.0)), (1, 1));
+    assert_eq!(
+      normalize_resize_increments(Some(8.0), None),
+      Some((8.0, 1.0))
+    );
+    assert_eq!(normalize_resize_increments(None, Some(f64::NAN)), None);
+  }
+
+  #[test]
+  fn test_take_due_timers() {
+    use std::time::{Duration, Instant};
+
+    let start = Instant::now();
+    let timer = |id, ms, repeat| TimerEntry {
+      id,
+      interval: Duration::from_millis(ms),
+      next: start + Duration::from_millis(ms),
+      repeat,
+    };
+    let mut timers = vec![timer(1, 30, true), timer(2, 10, false), timer(3, 100, true)];
+
+    assert!(take_due_timers(&mut timers, start).is_empty());
+    // Fired in deadline order; the one-shot timer is removed
+    assert_eq!(
+      take_due_timers(&mut timers, start + Duration::from_millis(35)),
+      [2, 1]
+    );
+    assert_eq!(timers.len(), 2);
+    assert_eq!(timers[0].next, start + Duration::from_millis(60));
+
+    // A long stall fires a repeating timer once and reschedules from now
+    let late = start + Duration::from_millis(500);
+    assert_eq!(take_due_timers(&mut timers, late), [1, 3]);
+    assert_eq!(timers[0].next, late + Duration::from_millis(30));
+  }
+
+  #[test]
+  fn test_os_error_code_per_platform_variant() {
+    assert_eq!(
+      os_error_code("os error at src/window.rs:10: Access is denied. (os error 5)"),
+      5
+    );
+    assert_eq!(
+      os_error_code("os error at src/window.rs:10: CGError 1001"),
+      1001
+    );
+    assert_eq!(os_error_code("os error at src/window.rs:10: "), -1);
+    assert_eq!(
+      os_error_code("os error at src/window.rs:10: Failed to create window"),
+      -1
+    );
+  }
+
+  #[test]
+  fn test_presentation_options_are_valid_combinations() {
+    // Auto-hide both
+    assert_eq!(presentation_options(true, true, true), 0b100_0000_0101);
+    // Hidden menu bar forces a hidden Dock
+    assert_eq!(presentation_options(false, true, true), 0b100_0000_1010);
+    assert_eq!(presentation_options(true, false, false), 0b0110);
+  }
+
+  #[test]
+  fn test_hidden_cursor_survives_icon_change() {
+    let mut state = TrackedWindowState {
+      cursor_visible: false,
+      ..Default::default()
+    };
+    assert!(state.apply_cursor_icon(CursorIcon::Wait));
+    assert!(!state.cursor_visible);
+    assert_eq!(state.cursor_icon, CursorIcon::Wait);
+
+    state.cursor_visible = true;
+    assert!(!state.apply_cursor_icon(CursorIcon::Default));
+  }
+
+  #[test]
+  fn test_alpha_runs_threshold() {
+    // One row of RGBA pixels with alpha 0, 40, 200, 255
+    let row = [0, 0, 0, 0, 0, 0, 0, 40, 0, 0, 0, 200, 0, 0, 0, 255];
+    assert_eq!(alpha_runs(&row, 4, 1, 4, 128), vec![(2, 4, 0)]);
+    assert_eq!(alpha_runs(&row, 4, 1, 4, 1), vec![(1, 4, 0)]);
+    assert_eq!(alpha_runs(&row, 4, 1, 4, 0), vec![(0, 4, 0)]);
+  }
+
+  #[test]
+  fn test_input_shape_runs_in_logical_pixels() {
+    let runs = vec![(3, 7, 0), (3, 7, 1), (0, 2, 3)];
+    assert_eq!(logical_runs(&runs, 1.0), runs);
+    // Partly covered logical pixels stay clickable; both rows fold into row 0
+    assert_eq!(logical_runs(&runs, 2.0), vec![(1, 4, 0), (0, 1, 1)]);
+    assert_eq!(logical_runs(&runs, 1.5), vec![(2, 5, 0), (0, 2, 2)]);
+
+    assert!(runs_contain(&runs, 3, 1));
+    assert!(!runs_contain(&runs, 7, 1));
+    assert!(!runs_contain(&runs, 0, 2));
+    assert!(runs_contain(&runs, 1, 3));
+  }
+
+  #[test]
+  fn test_insets_from_layout() {
+    // Fullsize content view: a 28 pt titlebar over a 400x300 content view
+    assert_eq!(
+      insets_from_layout((400.0, 300.0), (0.0, 0.0, 400.0, 272.0)),
+      (28.0, 0.0, 0.0, 0.0)
+    );
+    // Regular content view: the layout rect is the whole view
+    assert_eq!(
+      insets_from_layout((400.0, 300.0), (0.0, 0.0, 400.0, 300.0)),
+      (0.0, 0.0, 0.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn test_last_open_window_counts_tracked_windows() {
+    let mut states = std::collections::HashMap::new();
+    states.insert(1, TrackedWindowState::default());
+    assert!(!any_other_window_open(&states, 1));
+
+    states.insert(2, TrackedWindowState::default());
+    assert!(any_other_window_open(&states, 1));
+    assert!(any_other_window_open(&states, 2));
+
+    states.get_mut(&2).unwrap().closed = true;
+    assert!(!any_other_window_open(&states, 1));
+  }
+
+  #[test]
+  fn test_forgetting_a_window_drops_its_tracked_state() {
+    // No window has this id, so nothing else tracks or renders it
+    let key = u64::MAX - 1;
+    WINDOW_STATE.lock().unwrap().entry(key).or_default().opacity = 0.5;
+
+    forget_window(key);
+    assert!(!WINDOW_STATE.lock().unwrap().contains_key(&key));
+    assert!(!crate::tao::render::release_render_state(key));
+  }
+
+  #[test]
+  fn test_visual_state_reflects_tracked_state() {
+    let default = VisualState::from(TrackedWindowState::default());
+    assert!(!default.transparent && !default.blur);
+    assert_eq!(default.opacity, 1.0);
+    assert_eq!(default.theme, None);
+
+    let state = VisualState::from(TrackedWindowState {
+      transparent: true,
+      opacity: 0.5,
+      theme: Some(TaoTheme::Dark),
+      ..Default::default()
+    });
+    assert!(state.transparent);
+    assert_eq!(state.opacity, 0.5);
+    assert_eq!(state.theme, Some(TaoTheme::Dark));
+  }
+
+  #[test]
+  #[ignore = "requires a display"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_ignore_cursor_events_survives_hide_and_show() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let window =
+        crate::tao::test_display::build_window(event_loop, tao::window::WindowBuilder::new());
+
+      // tao has no getter for the applied state, so read back what it sets: the
+      // extended window style on Windows, the X11 input shape on Linux
+      #[cfg(target_os = "windows")]
+      let applied = |_: &mut tao::event_loop::EventLoop<()>, window: &Window| {
+        use tao::platform::windows::WindowExtWindows;
+        let hwnd = window.inner.as_ref().unwrap().lock().unwrap().hwnd();
+        win32::ex_style(hwnd) & WS_EX_TRANSPARENT != 0
+      };
+      #[cfg(target_os = "linux")]
+      let applied = |event_loop: &mut tao::event_loop::EventLoop<()>, window: &Window| {
+        use gtk::prelude::*;
+        use tao::platform::unix::WindowExtUnix;
+        use x11rb::protocol::shape::{ConnectionExt as _, SK};
+
+        // tao applies the request from the event loop
+        crate::tao::test_display::pump_until(
+          event_loop,
+          std::time::Duration::from_millis(200),
+          |_| false,
+        );
+        let inner = window.inner.as_ref().unwrap().lock().unwrap();
+        if crate::tao::platform::is_wayland_window(&inner) {
+          return window.is_ignoring_cursor_events();
+        }
+        let gdk_window = inner.gtk_window().window().unwrap();
+        let xid = gdk_window.downcast::<gdkx11::X11Window>().unwrap().xid();
+        let (connection, _) = x11rb::connect(None).unwrap();
+        let rectangles = connection
+          .shape_get_rectangles(xid as u32, SK::INPUT)
+          .unwrap()
+          .reply()
+          .unwrap()
+          .rectangles;
+        // tao shrinks the input shape of an ignoring window to one pixel
+        rectangles.len() == 1 && rectangles[0].width == 1 && rectangles[0].height == 1
+      };
+
+      assert!(!window.is_ignoring_cursor_events());
+      assert!(!applied(event_loop, &window));
+      window.set_ignore_cursor_events(true).unwrap();
+      window.set_visible(false).unwrap();
+      window.set_visible(true).unwrap();
+      assert!(window.is_ignoring_cursor_events());
+      assert!(applied(event_loop, &window));
+
+      window.set_ignore_cursor_events(false).unwrap();
+      assert!(!window.is_ignoring_cursor_events());
+      assert!(!applied(event_loop, &window));
+    });
+  }
+
+  #[test]
+  #[ignore = "requires a display"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_outer_size_includes_decorations() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let window =
+        crate::tao::test_display::build_window(event_loop, tao::window::WindowBuilder::new());
+
+      let inner = window.inner_size().unwrap();
+      let outer = window.outer_size().unwrap();
+      assert!(outer.width >= inner.width);
+      assert!(outer.height >= inner.height);
+    });
+  }
+
+  #[test]
+  #[ignore = "requires a display"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_cursor_grab_mode_tracks_last_applied_grab() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let window =
+        crate::tao::test_display::build_window(event_loop, tao::window::WindowBuilder::new());
+
+      assert_eq!(window.cursor_grab_mode(), CursorGrabMode::None);
+      // The grab may be refused, e.g. without focus; the mode only changes on success
+      match window.set_cursor_grab(CursorGrabMode::Confined) {
+        Ok(()) => assert_eq!(window.cursor_grab_mode(), CursorGrabMode::Confined),
+        Err(_) => assert_eq!(window.cursor_grab_mode(), CursorGrabMode::None),
+      }
+      window.set_cursor_grab(CursorGrabMode::None).unwrap();
+      assert_eq!(window.cursor_grab_mode(), CursorGrabMode::None);
+    });
+  }
+
+  #[test]
+  #[ignore = "requires a display"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_request_inner_size_respects_aspect_ratio() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let window =
+        crate::tao::test_display::build_window(event_loop, tao::window::WindowBuilder::new());
+
+      window.set_aspect_ratio(Some(4.0 / 3.0)).unwrap();
+      assert_eq!(window.aspect_ratio(), Some(4.0 / 3.0));
+      let granted = window.request_inner_size(1000.0, 500.0);
+      assert!(conforms_to_aspect_ratio(
+        granted.width,
+        granted.height,
+        4.0 / 3.0
+      ));
+      assert!(granted.width <= 1000.0 && granted.height <= 500.0);
+
+      assert!(window.set_aspect_ratio(Some(0.0)).is_err());
+      window.set_aspect_ratio(None).unwrap();
+      let granted = window.request_inner_size(1000.0, 500.0);
+      assert_eq!((granted.width, granted.height), (1000.0, 500.0));
+    });
+  }
+
+  #[test]
+  #[ignore = "requires a display"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_is_fullscreen_follows_set_fullscreen() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let window =
+        crate::tao::test_display::build_window(event_loop, tao::window::WindowBuilder::new());
+      let video_mode = VideoMode {
+        size: Size {
+          width: 640.0,
+          height: 480.0,
+        },
+        bit_depth: 32,
+        refresh_rate: 60,
+      };
+
+      assert!(!window.is_fullscreen());
+      assert!(window
+        .set_fullscreen(Some(TaoFullscreenType::Borderless), None, Some(video_mode))
+        .is_err());
+      assert!(!window.is_fullscreen());
+
+      let applied = window
+        .set_fullscreen(Some(TaoFullscreenType::Exclusive), None, None)
+        .unwrap();
+      assert!(applied.actual.is_some());
+      assert!(window.is_fullscreen());
+
+      window.set_fullscreen(None, None, None).unwrap();
+      assert!(!window.is_fullscreen());
+    });
+  }
+
+  #[test]
+  #[ignore = "requires a display"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_close_destroys_window() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let mut window =
+        crate::tao::test_display::build_window(event_loop, tao::window::WindowBuilder::new());
+      let window_id = window.inner.as_ref().unwrap().lock().unwrap().id();
+
+      window.close().unwrap();
+      window.close().unwrap();
+      assert!(window.is_closed());
+      assert!(window.inner.is_none());
+      let destroyed = crate::tao::test_display::pump_until(
+        event_loop,
+        std::time::Duration::from_secs(2),
+        |event| {
+          matches!(
+            event,
+            tao::event::Event::WindowEvent {
+              window_id: id,
+              event: tao::event::WindowEvent::Destroyed,
+              ..
+            } if *id == window_id
+          )
+        },
+      );
+      assert!(destroyed, "no Destroyed event for the closed window");
+    });
+  }
+
+  #[test]
+  #[ignore = "requires a display"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_set_decorated_keeps_inner_size() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let window = crate::tao::test_display::build_window(
+        event_loop,
+        tao::window::WindowBuilder::new().with_inner_size(tao::dpi::PhysicalSize::new(320, 240)),
+      );
+
+      let size = |window: &Window| {
+        let size = window.inner_size().unwrap();
+        (size.width, size.height)
+      };
+      // The window manager applies decoration changes asynchronously; wait for
+      // the configure it answers with before reading the size back
+      let settle = |event_loop: &mut tao::event_loop::EventLoop<()>| {
+        crate::tao::test_display::pump_until(
+          event_loop,
+          std::time::Duration::from_secs(2),
+          |event| {
+            matches!(
+              event,
+              tao::event::Event::WindowEvent {
+                event: tao::event::WindowEvent::Resized(_),
+                ..
+              }
+            )
+          },
+        );
+      };
+      settle(event_loop);
+      let before = size(&window);
+      window.set_decorated(false).unwrap();
+      settle(event_loop);
+      assert!(!window.is_decorated().unwrap());
+      assert_eq!(size(&window), before);
+      window.set_decorated(true).unwrap();
+      settle(event_loop);
+      assert!(window.is_decorated().unwrap());
+      assert_eq!(size(&window), before);
+    });
+  }
+
+  #[test]
+  fn test_window_level_transitions() {
+    use WindowLevel::*;
+    assert_eq!(window_level_from_flags(false, false), Normal);
+    assert_eq!(window_level_from_flags(false, true), AlwaysOnBottom);
+    assert_eq!(window_level_from_flags(true, true), AlwaysOnTop);
+
+    assert_eq!(toggled_window_level(Normal, AlwaysOnTop, true), AlwaysOnTop);
+    assert_eq!(
+      toggled_window_level(AlwaysOnTop, AlwaysOnBottom, true),
+      AlwaysOnBottom
+    );
+    // Disabling a level the window is not at keeps the current one
+    assert_eq!(
+      toggled_window_level(AlwaysOnBottom, AlwaysOnTop, false),
+      AlwaysOnBottom
+    );
+    assert_eq!(
+      toggled_window_level(AlwaysOnBottom, AlwaysOnBottom, false),
+      Normal
+    );
+  }
+
+  #[test]
+  #[ignore = "requires a display"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_window_level_top_bottom_normal() {
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let window =
+        crate::tao::test_display::build_window(event_loop, tao::window::WindowBuilder::new());
+
+      window.set_always_on_top(true).unwrap();
+      assert_eq!(window.window_level().unwrap(), WindowLevel::AlwaysOnTop);
+      window.set_always_on_bottom(true).unwrap();
+      assert_eq!(window.window_level().unwrap(), WindowLevel::AlwaysOnBottom);
+      assert!(!window.is_always_on_top().unwrap());
+      window.set_level_normal().unwrap();
+      assert_eq!(window.window_level().unwrap(), WindowLevel::Normal);
+      assert!(!window.is_always_on_top().unwrap());
+    });
+  }
+
+  #[test]
+  fn test_event_to_json_serializes_every_event() {
+    let parse = |event: &tao::event::Event<'_, ()>| {
+      serde_json::from_str::<serde_json::Value>(&event_to_json(event)).unwrap()
+    };
+
+    let resumed = parse(&tao::event::Event::Resumed);
+    assert_eq!(resumed["type"], "Resumed");
+    assert_eq!(resumed["debug"], "Resumed");
+
+    let cause = parse(&tao::event::Event::NewEvents(tao::event::StartCause::Init));
+    assert_eq!(cause["type"], "NewEvents");
+    assert_eq!(cause["debug"], "NewEvents(Init)");
+
+    let resized = window_event_json(&tao::event::WindowEvent::Resized(
+      tao::dpi::PhysicalSize::new(640, 480),
+    ));
+    assert_eq!(resized["type"], "Resized");
+    assert_eq!(resized["width"], 640);
+
+    let closed = window_event_json(&tao::event::WindowEvent::CloseRequested);
+    assert_eq!(closed["type"], "CloseRequested");
+  }
+
+  #[test]
+  fn test_cursor_icon_from_css() {
+    assert!(matches!(
+      cursor_icon_from_css("pointer"),
+      Some(CursorIcon::Hand)
+    ));
+    assert!(matches!(
+      cursor_icon_from_css("auto"),
+      Some(CursorIcon::Default)
+    ));
+    assert!(matches!(
+      cursor_icon_from_css(" EW-Resize "),
+      Some(CursorIcon::EastWestResize)
+    ));
+    assert!(matches!(
+      cursor_icon_from_css("grab"),
+      Some(CursorIcon::Grab)
+    ));
+    assert!(cursor_icon_from_css("none").is_none());
+    assert!(cursor_icon_from_css("hand").is_none());
+  }
+}
@@ -0,0 +1,92 @@
+//! Shared event loop for the tests that need a display
+//!
+//! GTK allows a single event loop per process, and windows must stay on the
+//! thread that created it. Every display test therefore runs its body on one
+//! long-lived loop thread, one test at a time.
+
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+
+type EventLoop = tao::event_loop::EventLoop<()>;
+type Job = Box<dyn FnOnce(&mut EventLoop) + Send>;
+
+/// Sends test bodies to the loop thread, started by the first display test
+static LOOP_THREAD: OnceLock<Mutex<mpsc::Sender<Job>>> = OnceLock::new();
+
+/// Runs a test body on the shared event loop thread
+///
+/// A panic in the body is re-raised in the calling test, and the loop stays
+/// usable for the next one.
+pub(crate) fn with_event_loop<F>(test: F)
+where
+  F: FnOnce(&mut EventLoop) + Send + 'static,
+{
+  let sender = LOOP_THREAD.get_or_init(|| {
+    let (sender, receiver) = mpsc::channel::<Job>();
+    std::thread::spawn(move || {
+      #[cfg(target_os = "linux")]
+      use tao::platform::unix::EventLoopBuilderExtUnix;
+      #[cfg(target_os = "windows")]
+      use tao::platform::windows::EventLoopBuilderExtWindows;
+
+      let mut event_loop = tao::event_loop::EventLoopBuilder::new()
+        .with_any_thread(true)
+        .build();
+      for job in receiver {
+        job(&mut event_loop);
+      }
+    });
+    Mutex::new(sender)
+  });
+
+  let (result_sender, result) = mpsc::channel();
+  let job: Job = Box::new(move |event_loop| {
+    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| test(event_loop)));
+    let _ = result_sender.send(outcome);
+  });
+  sender
+    .lock()
+    .unwrap()
+    .send(job)
+    .expect("Display test thread stopped");
+  if let Err(panic) = result.recv().expect("Display test thread stopped") {
+    std::panic::resume_unwind(panic);
+  }
+}
+
+/// Builds a window on the shared event loop, wrapped like the JS `Window` class
+pub(crate) fn build_window(
+  event_loop: &EventLoop,
+  builder: tao::window::WindowBuilder,
+) -> crate::tao::structs::Window {
+  let window = builder.build(event_loop).expect("Failed to create window");
+  crate::tao::structs::Window {
+    #[allow(clippy::arc_with_non_send_sync)]
+    inner: Some(Arc::new(Mutex::new(window))),
+  }
+}
+
+/// Pumps events until `done` accepts one; returns false if `timeout` elapses first
+pub(crate) fn pump_until<F>(
+  event_loop: &mut EventLoop,
+  timeout: std::time::Duration,
+  mut done: F,
+) -> bool
+where
+  F: FnMut(&tao::event::Event<'_, ()>) -> bool,
+{
+  use tao::event_loop::ControlFlow;
+  use tao::platform::run_return::EventLoopExtRunReturn;
+
+  let deadline = std::time::Instant::now() + timeout;
+  let mut found = false;
+  event_loop.run_return(|event, _, control_flow| {
+    *control_flow = ControlFlow::WaitUntil(deadline);
+    if done(&event) {
+      found = true;
+      *control_flow = ControlFlow::Exit;
+    } else if std::time::Instant::now() >= deadline {
+      *control_flow = ControlFlow::Exit;
+    }
+  });
+  found
+}
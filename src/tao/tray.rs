@@ -0,0 +1,286 @@
+//! System tray icons
+//!
+//! An icon in the notification area (Windows), the status area (Linux) or the
+//! menu bar (macOS), with a tooltip, a context menu and a click callback.
+//!
+//! The icons are built on the `tray-icon` crate and deliver their events through
+//! the platform's main loop, so the event loop must be running (`run`, or regular
+//! `run_iteration` / `process_pending` calls) for callbacks to fire.
+
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// Callback fired when a tray icon is clicked.
+pub type TrayClickCallback = ThreadsafeFunction<()>;
+
+/// Callback fired with the `id` of the tray menu item that was chosen.
+pub type TrayMenuItemCallback = ThreadsafeFunction<String>;
+
+/// An entry of a tray icon's context menu.
+#[napi(object)]
+pub struct TrayMenuItem {
+  /// Identifier passed to the `on_menu_item` callback.
+  pub id: String,
+  /// Text shown in the menu.
+  pub label: String,
+  /// Whether the item can be chosen (default: true).
+  pub enabled: Option<bool>,
+}
+
+/// Callbacks of a tray icon, shared with the global `tray-icon` event handlers
+#[derive(Default)]
+struct TrayHandlers {
+  on_click: Mutex<Option<TrayClickCallback>>,
+  on_menu_item: Mutex<Option<TrayMenuItemCallback>>,
+}
+
+impl TrayHandlers {
+  fn click(&self) {
+    if let Some(callback) = &*self.on_click.lock().unwrap() {
+      let _ = callback.call(Ok(()), ThreadsafeFunctionCallMode::NonBlocking);
+    }
+  }
+
+  fn menu_item(&self, id: &str) {
+    if let Some(callback) = &*self.on_menu_item.lock().unwrap() {
+      let _ = callback.call(Ok(id.to_string()), ThreadsafeFunctionCallMode::NonBlocking);
+    }
+  }
+}
+
+/// Handlers of the live tray icons, keyed by their `tray-icon` id
+///
+/// `tray-icon` reports the events of all icons and menus to one global handler,
+/// which looks the icon up here.
+static TRAY_HANDLERS: LazyLock<Mutex<HashMap<String, Arc<TrayHandlers>>>> =
+  LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Source of the ids of tray icons, which prefix the ids of their menu items
+static NEXT_TRAY_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+/// Installs the global `tray-icon` event handlers once
+static EVENT_HANDLERS: std::sync::Once = std::sync::Once::new();
+
+/// Separates the tray id from the item id in a menu item id
+const MENU_ID_SEPARATOR: char = '/';
+
+/// Routes `tray-icon` click and menu events to the handlers of their icon
+fn install_event_handlers() {
+  EVENT_HANDLERS.call_once(|| {
+    tray_icon::TrayIconEvent::set_event_handler(Some(|event: tray_icon::TrayIconEvent| {
+      let tray_icon::TrayIconEvent::Click {
+        id,
+        button: tray_icon::MouseButton::Left,
+        button_state: tray_icon::MouseButtonState::Up,
+        ..
+      } = event
+      else {
+        return;
+      };
+      let handlers = TRAY_HANDLERS.lock().unwrap().get(&id.0).cloned();
+      if let Some(handlers) = handlers {
+        handlers.click();
+      }
+    }));
+    tray_icon::menu::MenuEvent::set_event_handler(Some(|event: tray_icon::menu::MenuEvent| {
+      let Some((tray_id, item_id)) = split_menu_id(&event.id.0) else {
+        return;
+      };
+      let handlers = TRAY_HANDLERS.lock().unwrap().get(tray_id).cloned();
+      if let Some(handlers) = handlers {
+        handlers.menu_item(item_id);
+      }
+    }));
+  });
+}
+
+/// Builds the id of a menu item, unique across tray icons
+fn menu_id(tray_id: &str, item_id: &str) -> String {
+  format!("{}{}{}", tray_id, MENU_ID_SEPARATOR, item_id)
+}
+
+/// Splits a menu item id into the tray id and the item id given by JS
+fn split_menu_id(menu_id: &str) -> Option<(&str, &str)> {
+  menu_id.split_once(MENU_ID_SEPARATOR)
+}
+
+/// Converts RGBA pixels to a `tray-icon` icon
+fn tray_icon_image(rgba: &[u8], width: u32, height: u32) -> Result<tray_icon::Icon> {
+  let rgba = crate::tao::render::buffer_prefix(rgba, width, height, false)?;
+  tray_icon::Icon::from_rgba(rgba.to_vec(), width, height).map_err(|e| {
+    napi::Error::new(
+      napi::Status::GenericFailure,
+      format!("Invalid tray icon: {}", e),
+    )
+  })
+}
+
+/// Builds the context menu of a tray icon; `None` for no items
+fn tray_menu(tray_id: &str, items: &[TrayMenuItem]) -> Result<Option<tray_icon::menu::Menu>> {
+  if items.is_empty() {
+    return Ok(None);
+  }
+  let menu = tray_icon::menu::Menu::new();
+  for item in items {
+    let entry = tray_icon::menu::MenuItem::with_id(
+      menu_id(tray_id, &item.id),
+      &item.label,
+      item.enabled.unwrap_or(true),
+      None,
+    );
+    menu.append(&entry).map_err(|e| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to build tray menu: {}", e),
+      )
+    })?;
+  }
+  Ok(Some(menu))
+}
+
+/// An icon in the system tray
+///
+/// Must be created and used on the event loop thread, after the `EventLoop` is
+/// created. The icon is removed when it is destroyed or garbage-collected.
+///
+/// ## Platform-specific
+///
+/// - **Linux**: An AppIndicator, which needs `libayatana-appindicator3` (or
+///   `libappindicator3`) at runtime and is shown by desktops supporting
+///   StatusNotifierItem; GNOME needs the AppIndicator extension. AppIndicators
+///   report no clicks, so `on_click` never fires: any click opens the menu.
+/// - **Windows**: An icon in the notification area; a left click fires
+///   `on_click`, a right click opens the menu.
+/// - **macOS**: A menu bar item. A left click fires `on_click`, a right click
+///   opens the menu.
+#[napi]
+pub struct TrayIcon {
+  id: String,
+  handlers: Arc<TrayHandlers>,
+  tray: Option<tray_icon::TrayIcon>,
+}
+
+#[napi]
+impl TrayIcon {
+  /// Creates a tray icon from RGBA pixels (`width * height * 4` bytes)
+  #[napi(constructor)]
+  pub fn new(rgba: Buffer, width: u32, height: u32, tooltip: Option<String>) -> Result<Self> {
+    // The icon is served by the main loop of the thread that creates it
+    if !crate::tao::platform::is_event_loop_thread() {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        "TrayIcon must be created on the event loop thread, after the EventLoop".to_string(),
+      ));
+    }
+    install_event_handlers();
+    let id = NEXT_TRAY_ID
+      .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+      .to_string();
+    let mut builder = tray_icon::TrayIconBuilder::new()
+      .with_id(id.as_str())
+      .with_icon(tray_icon_image(&rgba, width, height)?)
+      .with_menu_on_left_click(false);
+    if let Some(tooltip) = tooltip {
+      builder = builder.with_tooltip(tooltip);
+    }
+    let tray = builder.build().map_err(|e| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to create tray icon: {}", e),
+      )
+    })?;
+
+    let handlers = Arc::new(TrayHandlers::default());
+    TRAY_HANDLERS
+      .lock()
+      .unwrap()
+      .insert(id.clone(), handlers.clone());
+    Ok(Self {
+      id,
+      handlers,
+      tray: Some(tray),
+    })
+  }
+
+  /// Replaces the icon image with RGBA pixels (`width * height * 4` bytes)
+  #[napi]
+  pub fn set_icon(&mut self, rgba: Buffer, width: u32, height: u32) -> Result<()> {
+    let icon = tray_icon_image(&rgba, width, height)?;
+    self.tray()?.set_icon(Some(icon)).map_err(|e| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to set tray icon: {}", e),
+      )
+    })
+  }
+
+  /// Sets the tooltip shown when hovering the icon; `null` removes it
+  #[napi]
+  pub fn set_tooltip(&mut self, tooltip: Option<String>) -> Result<()> {
+    self.tray()?.set_tooltip(tooltip).map_err(|e| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to set tray tooltip: {}", e),
+      )
+    })
+  }
+
+  /// Replaces the context menu; an empty list removes it
+  #[napi]
+  pub fn set_menu(&mut self, items: Vec<TrayMenuItem>) -> Result<()> {
+    let menu = tray_menu(&self.id, &items)?;
+    self
+      .tray()?
+      .set_menu(menu.map(|menu| Box::new(menu) as Box<dyn tray_icon::menu::ContextMenu>));
+    Ok(())
+  }
+
+  /// Sets the callback fired when the icon is clicked, or clears it
+  #[napi]
+  pub fn set_on_click(&self, callback: Option<TrayClickCallback>) {
+    *self.handlers.on_click.lock().unwrap() = callback;
+  }
+
+  /// Sets the callback fired with the `id` of a chosen menu item, or clears it
+  #[napi]
+  pub fn set_on_menu_item(&self, callback: Option<TrayMenuItemCallback>) {
+    *self.handlers.on_menu_item.lock().unwrap() = callback;
+  }
+
+  /// Removes the icon from the tray; later calls other than `destroy` throw
+  #[napi]
+  pub fn destroy(&mut self) {
+    self.tray = None;
+    TRAY_HANDLERS.lock().unwrap().remove(&self.id);
+  }
+
+  fn tray(&mut self) -> Result<&mut tray_icon::TrayIcon> {
+    self.tray.as_mut().ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "Tray icon was destroyed".to_string(),
+      )
+    })
+  }
+}
+
+impl Drop for TrayIcon {
+  fn drop(&mut self) {
+    self.destroy();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_menu_ids_keep_the_item_id() {
+    let id = menu_id("3", "open/recent");
+    assert_eq!(split_menu_id(&id), Some(("3", "open/recent")));
+    assert_ne!(menu_id("3", "quit"), menu_id("4", "quit"));
+  }
+}
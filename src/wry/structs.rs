@@ -5,10 +5,12 @@
 use napi::bindgen_prelude::*;
 use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use napi_derive::napi;
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
 
 use crate::tao::structs::EventLoop;
-use crate::wry::enums::WryTheme;
+use crate::wry::enums::{PageLoadEvent, WryTheme};
 use crate::wry::types::Result;
 #[cfg(any(
   target_os = "linux",
@@ -96,17 +98,137 @@ pub struct Rect {
   pub height: u32,
 }
 
-/// A responder for a request.
-#[napi(object)]
+/// A request to a custom protocol, answered asynchronously with `respond`.
+///
+/// Requests that are never answered stay pending in the webview until the
+/// responder is garbage collected, at which point they fail with status 500.
+#[napi]
 pub struct RequestAsyncResponder {
-  /// The URI of the request.
-  pub uri: String,
+  uri: String,
+  method: String,
+  headers: HashMap<String, String>,
+  body: Vec<u8>,
+  responder: Mutex<Option<wry::RequestAsyncResponder>>,
+}
+
+impl RequestAsyncResponder {
+  fn new(request: wry::http::Request<Vec<u8>>, responder: wry::RequestAsyncResponder) -> Self {
+    let headers = request
+      .headers()
+      .iter()
+      .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+      .collect();
+    Self {
+      uri: request.uri().to_string(),
+      method: request.method().to_string(),
+      headers,
+      body: request.into_body(),
+      responder: Mutex::new(Some(responder)),
+    }
+  }
+}
+
+#[napi]
+impl RequestAsyncResponder {
+  /// The full URI of the request.
+  #[napi(getter)]
+  pub fn uri(&self) -> String {
+    self.uri.clone()
+  }
+
+  /// The path of the request URI, e.g. `/assets/main.js`.
+  #[napi(getter)]
+  pub fn path(&self) -> String {
+    request_path(&self.uri)
+  }
+
   /// The HTTP method of the request.
-  pub method: String,
+  #[napi(getter)]
+  pub fn method(&self) -> String {
+    self.method.clone()
+  }
+
+  /// The request headers whose values are valid strings.
+  #[napi(getter)]
+  pub fn headers(&self) -> HashMap<String, String> {
+    self.headers.clone()
+  }
+
   /// The body of the request.
+  #[napi(getter)]
+  pub fn body(&self) -> Buffer {
+    self.body.clone().into()
+  }
+
+  /// Answers the request. Fails if the request was already answered.
+  #[napi]
+  pub fn respond(&self, response: CustomProtocolResponse) -> Result<()> {
+    let http_response = protocol_response(
+      response.status,
+      response.mime_type.as_deref(),
+      response.body.to_vec(),
+    )?;
+    let responder = self.responder.lock().unwrap().take().ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Request to '{}' was already answered", self.uri),
+      )
+    })?;
+    responder.respond(http_response);
+    Ok(())
+  }
+}
+
+impl Drop for RequestAsyncResponder {
+  fn drop(&mut self) {
+    if let Some(responder) = self.responder.get_mut().unwrap().take() {
+      if let Ok(response) = protocol_response(Some(500), None, Vec::new()) {
+        responder.respond(response);
+      }
+    }
+  }
+}
+
+/// A response to a custom protocol request.
+#[napi(object)]
+pub struct CustomProtocolResponse {
+  /// The HTTP status code. Defaults to 200.
+  pub status: Option<u16>,
+  /// The `Content-Type` of the body, e.g. `text/html`.
+  pub mime_type: Option<String>,
+  /// The response body.
   pub body: Buffer,
 }
 
+/// Callback receiving requests to a custom protocol.
+pub type CustomProtocolHandler = ThreadsafeFunction<RequestAsyncResponder>;
+
+/// Gets the path of a request URI, without the query or fragment.
+fn request_path(uri: &str) -> String {
+  uri
+    .parse::<wry::http::Uri>()
+    .map(|uri| uri.path().to_string())
+    .unwrap_or_else(|_| "/".to_string())
+}
+
+/// Builds the HTTP response for a custom protocol request.
+fn protocol_response(
+  status: Option<u16>,
+  mime_type: Option<&str>,
+  body: Vec<u8>,
+) -> Result<wry::http::Response<Vec<u8>>> {
+  let mut builder = wry::http::Response::builder().status(status.unwrap_or(200));
+  if let Some(mime_type) = mime_type {
+    builder = builder.header(wry::http::header::CONTENT_TYPE, mime_type);
+  }
+  builder.body(body).map_err(|e| {
+    napi::Error::new(
+      napi::Status::InvalidArg,
+      format!("Invalid protocol response: {}", e),
+    )
+  })
+}
+
 /// The web context for a webview.
 #[napi]
 pub struct WebContext {
@@ -238,12 +360,113 @@ pub struct WebViewAttributes {
 
 pub type IpcHandler = ThreadsafeFunction<String>;
 
+/// An IPC message posted by a page with `window.ipc.postMessage`.
+#[napi(object)]
+pub struct IpcRequest {
+  /// The ID of the webview that posted the message, as returned by `WebView.id`.
+  pub webview_id: String,
+  /// The message body.
+  pub body: String,
+  /// The URL of the page that posted the message.
+  pub url: String,
+}
+
+/// Callback receiving IPC messages as [`IpcRequest`]s.
+pub type IpcRequestHandler = ThreadsafeFunction<IpcRequest>;
+
+/// A page load reported by [`WebViewBuilder::with_on_page_load`].
+#[napi(object)]
+pub struct PageLoadInfo {
+  /// Whether the page started or finished loading.
+  pub event: PageLoadEvent,
+  /// The URL of the page.
+  pub url: String,
+}
+
+/// Callback receiving page loads as [`PageLoadInfo`]s.
+pub type PageLoadHandler = ThreadsafeFunction<PageLoadInfo>;
+
+/// A navigation-decision callback, called synchronously on the JS thread.
+struct NavigationHandler {
+  env: napi::sys::napi_env,
+  callback: FunctionRef<String, bool>,
+}
+
+impl NavigationHandler {
+  /// Asks the callback whether to allow navigating to `url`; errors allow it.
+  ///
+  /// Navigations starting while a [`WebView`] method holds its webview (wry may
+  /// pump events during a call) are allowed without asking: the callback could
+  /// call that webview again and deadlock on its lock.
+  fn allows(&self, url: String) -> bool {
+    if WEBVIEW_LOCKS.with(|locks| locks.get()) > 0 {
+      log_warn!(
+        "webview",
+        "Navigation to {} started during a webview call, allowing it without the navigation handler",
+        url
+      );
+      return true;
+    }
+    let env = Env::from_raw(self.env);
+    // A scope per call, or the handles would pile up until a blocking `run` returns
+    match env.run_in_scope(|| {
+      self
+        .callback
+        .borrow_back(&env)
+        .and_then(|callback| callback.call(url))
+    }) {
+      Ok(allow) => allow,
+      Err(e) => {
+        log_warn!(
+          "webview",
+          "Navigation handler failed, allowing navigation: {}",
+          e
+        );
+        true
+      }
+    }
+  }
+}
+
+thread_local! {
+  /// Number of webviews locked by [`lock_webview`] on this thread
+  static WEBVIEW_LOCKS: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
+}
+
+/// A webview locked by a [`WebView`] method, counted in `WEBVIEW_LOCKS`
+struct WebViewLock<'a>(std::sync::MutexGuard<'a, wry::WebView>);
+
+impl std::ops::Deref for WebViewLock<'_> {
+  type Target = wry::WebView;
+
+  fn deref(&self) -> &wry::WebView {
+    &self.0
+  }
+}
+
+impl Drop for WebViewLock<'_> {
+  fn drop(&mut self) {
+    WEBVIEW_LOCKS.with(|locks| locks.set(locks.get() - 1));
+  }
+}
+
+/// Locks a webview, so navigation handlers know not to call back into it
+fn lock_webview(inner: &Mutex<wry::WebView>) -> WebViewLock<'_> {
+  let guard = inner.lock().unwrap();
+  WEBVIEW_LOCKS.with(|locks| locks.set(locks.get() + 1));
+  WebViewLock(guard)
+}
+
 /// Builder for creating webviews.
 #[napi]
 pub struct WebViewBuilder {
   attributes: WebViewAttributes,
   ipc_handler: Option<IpcHandler>,
   ipc_handlers: Vec<IpcHandler>,
+  ipc_request_handlers: Vec<IpcRequestHandler>,
+  custom_protocols: Vec<(String, CustomProtocolHandler)>,
+  page_load_handler: Option<PageLoadHandler>,
+  navigation_handler: Option<NavigationHandler>,
   #[allow(dead_code)]
   inner: Option<wry::WebViewBuilder<'static>>,
 }
@@ -288,21 +511,31 @@ impl WebViewBuilder {
       },
       ipc_handler: None,
       ipc_handlers: Vec::new(),
+      ipc_request_handlers: Vec::new(),
+      custom_protocols: Vec::new(),
+      page_load_handler: None,
+      navigation_handler: None,
       inner: None,
     })
   }
 
-  /// Sets the URL to load.
+  /// Sets the URL to load, replacing HTML content set before.
   #[napi]
   pub fn with_url(&mut self, url: String) -> Result<&Self> {
     self.attributes.url = Some(url);
+    self.attributes.html = None;
     Ok(self)
   }
 
-  /// Sets the HTML content to load.
+  /// Sets an HTML string to load, replacing a URL set before.
+  ///
+  /// The page's base URL is `about:blank` on every platform, so relative asset
+  /// references do not resolve; use `withHtmlAndBaseUrl` to load assets next to
+  /// the page.
   #[napi]
   pub fn with_html(&mut self, html: String) -> Result<&Self> {
     self.attributes.html = Some(html);
+    self.attributes.url = None;
     Ok(self)
   }
 
@@ -450,7 +683,10 @@ impl WebViewBuilder {
     Ok(self)
   }
 
-  /// Sets whether to enable devtools.
+  /// Sets whether to enable devtools (default: true).
+  ///
+  /// With `false`, the page can't open the inspector and `WebView.openDevtools`
+  /// fails.
   #[napi]
   pub fn with_devtools(&mut self, devtools: bool) -> Result<&Self> {
     self.attributes.devtools = devtools;
@@ -519,6 +755,78 @@ impl WebViewBuilder {
     Ok(self)
   }
 
+  /// Adds a handler receiving IPC messages along with the webview that posted them.
+  ///
+  /// Unlike `withIpcHandler`, which passes the body only, the handler receives an
+  /// `IpcRequest` with the posting webview's ID and page URL, so one handler can
+  /// serve several webviews. Messages are delivered on the Node event loop in the
+  /// order they were posted. Replies to `WebView.request` and bridge messages are
+  /// not delivered.
+  #[napi(ts_args_type = "callback: (error: Error | null, request: IpcRequest) => void")]
+  pub fn with_ipc_request_handler(&mut self, callback: IpcRequestHandler) -> Result<&Self> {
+    self.ipc_request_handlers.push(callback);
+    Ok(self)
+  }
+
+  /// Serves requests to a custom URL scheme, e.g. `app`, from the handler.
+  ///
+  /// The handler receives a `RequestAsyncResponder` per request and answers it
+  /// with `respond`, so content can be read asynchronously; respond with status
+  /// 404 for paths that don't exist. Registering a scheme again replaces its
+  /// handler.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: Pages are served from `http://<scheme>.localhost/`
+  ///   instead of `<scheme>://localhost/`.
+  /// - **Linux**: A scheme can only be registered once per web context, so it
+  ///   can't be registered for a second webview.
+  #[napi(
+    ts_args_type = "scheme: string, handler: (error: Error | null, request: RequestAsyncResponder) => void"
+  )]
+  pub fn with_custom_protocol(
+    &mut self,
+    scheme: String,
+    handler: CustomProtocolHandler,
+  ) -> Result<&Self> {
+    self.custom_protocols.retain(|(name, _)| *name != scheme);
+    self.custom_protocols.push((scheme, handler));
+    Ok(self)
+  }
+
+  /// Sets a callback receiving page loads, including the initial one.
+  ///
+  /// Each navigation fires `Started` once it commits and `Completed` when the page
+  /// has loaded, both with the page URL. Client-side redirects start a new load and
+  /// fire `Started` again; server redirects are followed within one load and report
+  /// the final URL.
+  #[napi(ts_args_type = "callback: (error: Error | null, load: PageLoadInfo) => void")]
+  pub fn with_on_page_load(&mut self, callback: PageLoadHandler) -> Result<&Self> {
+    self.page_load_handler = Some(callback);
+    Ok(self)
+  }
+
+  /// Sets a callback deciding whether to allow each navigation.
+  ///
+  /// Called with the target URL before the webview navigates, including the
+  /// initial load; returning `false` cancels the navigation. Unlike the other
+  /// callbacks it runs synchronously while the event loop is processing events, so
+  /// it should return quickly. Navigation is allowed if the callback throws, and
+  /// without calling it when the navigation starts during a call to a `WebView`
+  /// method.
+  #[napi]
+  pub fn with_navigation_handler(
+    &mut self,
+    env: Env,
+    callback: Function<String, bool>,
+  ) -> Result<&Self> {
+    self.navigation_handler = Some(NavigationHandler {
+      env: env.raw(),
+      callback: callback.create_ref()?,
+    });
+    Ok(self)
+  }
+
   /// Loads HTML content from a file with proper context resolution.
   /// This sets the base URL so that relative imports (like ./styles.css, ./main.js)
   /// and import.meta.url resolve correctly.
@@ -607,6 +915,32 @@ impl WebViewBuilder {
     window: &crate::tao::structs::Window,
     label: String,
     ipc_listeners_override: Option<Arc<Mutex<Vec<IpcHandler>>>>,
+  ) -> Result<WebView> {
+    self.build_in_window(window, label, ipc_listeners_override, false)
+  }
+
+  /// Builds the webview as a child of an existing window, positioned by its bounds.
+  ///
+  /// Unlike `buildOnWindow`, several child webviews can share one window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Only supported on X11.
+  #[napi]
+  pub fn build_as_child(
+    &mut self,
+    window: &crate::tao::structs::Window,
+    label: String,
+  ) -> Result<WebView> {
+    self.build_in_window(window, label, None, true)
+  }
+
+  fn build_in_window(
+    &mut self,
+    window: &crate::tao::structs::Window,
+    label: String,
+    ipc_listeners_override: Option<Arc<Mutex<Vec<IpcHandler>>>>,
+    as_child: bool,
   ) -> Result<WebView> {
     let window_lock = window.inner.as_ref().ok_or_else(|| {
       napi::Error::new(
@@ -683,6 +1017,15 @@ impl WebViewBuilder {
       webview_builder = webview_builder.with_initialization_script(&script.js);
     }
 
+    webview_builder = apply_custom_protocols(webview_builder, self.custom_protocols.drain(..));
+    let instance = NEXT_WEBVIEW_INSTANCE.fetch_add(1, Ordering::SeqCst);
+    webview_builder = apply_navigation_handlers(
+      webview_builder,
+      instance,
+      self.page_load_handler.take(),
+      self.navigation_handler.take(),
+    );
+
     // Build the webview
     #[cfg(any(
       target_os = "linux",
@@ -692,19 +1035,13 @@ impl WebViewBuilder {
       target_os = "openbsd"
     ))]
     {
-      extern "C" {
-        fn gtk_bin_get_child(bin: *mut std::ffi::c_void) -> *mut std::ffi::c_void;
-        fn gtk_container_remove(container: *mut std::ffi::c_void, widget: *mut std::ffi::c_void);
-        fn gtk_widget_show_all(widget: *mut std::ffi::c_void);
-      }
+      use gtk::prelude::*;
 
-      let window_ptr = window_inner.gtk_window();
-      let window_ptr_raw = unsafe { *(window_ptr as *const _ as *const *mut std::ffi::c_void) };
+      let gtk_window = window_inner.gtk_window();
 
-      unsafe {
-        let child = gtk_bin_get_child(window_ptr_raw);
-        if !child.is_null() {
-          gtk_container_remove(window_ptr_raw, child);
+      if !as_child {
+        if let Some(child) = gtk_window.child() {
+          gtk_window.remove(&child);
         }
       }
 
@@ -712,22 +1049,28 @@ impl WebViewBuilder {
       let (webview_builder_with_ipc, listeners) = setup_ipc_handler(
         self.ipc_handler.take(),
         self.ipc_handlers.drain(..).collect(),
+        self.ipc_request_handlers.drain(..).collect(),
+        label.clone(),
+        instance,
         webview_builder,
         ipc_listeners_override,
       );
       let ipc_listeners = listeners;
       webview_builder = webview_builder_with_ipc;
 
-      let webview = webview_builder.build_gtk(window_ptr).map_err(|e| {
+      let webview = if as_child {
+        webview_builder.build_as_child(&*window_inner)
+      } else {
+        webview_builder.build_gtk(gtk_window)
+      }
+      .map_err(|e| {
         napi::Error::new(
           napi::Status::GenericFailure,
           format!("Failed to create webview: {}", e),
         )
       })?;
 
-      unsafe {
-        gtk_widget_show_all(window_ptr_raw);
-      }
+      gtk_window.show_all();
 
       #[allow(clippy::arc_with_non_send_sync)]
       let webview_inner = Arc::new(Mutex::new(webview));
@@ -735,6 +1078,9 @@ impl WebViewBuilder {
         inner: Some(webview_inner),
         label,
         ipc_listeners,
+        muted: Arc::new(AtomicBool::new(false)),
+        devtools: self.attributes.devtools,
+        instance,
       })
     }
 
@@ -750,13 +1096,21 @@ impl WebViewBuilder {
       let (webview_builder_with_ipc, listeners) = setup_ipc_handler(
         self.ipc_handler.take(),
         self.ipc_handlers.drain(..).collect(),
+        self.ipc_request_handlers.drain(..).collect(),
+        label.clone(),
+        instance,
         webview_builder,
         ipc_listeners_override,
       );
       let ipc_listeners = listeners;
       webview_builder = webview_builder_with_ipc;
 
-      let webview = webview_builder.build(&*window_inner).map_err(|e| {
+      let webview = if as_child {
+        webview_builder.build_as_child(&*window_inner)
+      } else {
+        webview_builder.build(&*window_inner)
+      }
+      .map_err(|e| {
         napi::Error::new(
           napi::Status::GenericFailure,
           format!("Failed to create webview: {}", e),
@@ -768,6 +1122,9 @@ impl WebViewBuilder {
         inner: Some(webview_inner),
         label,
         ipc_listeners,
+        muted: Arc::new(AtomicBool::new(false)),
+        devtools: self.attributes.devtools,
+        instance,
       })
     }
   }
@@ -899,6 +1256,15 @@ impl WebViewBuilder {
       webview_builder = webview_builder.with_initialization_script(&script.js);
     }
 
+    webview_builder = apply_custom_protocols(webview_builder, self.custom_protocols.drain(..));
+    let instance = NEXT_WEBVIEW_INSTANCE.fetch_add(1, Ordering::SeqCst);
+    webview_builder = apply_navigation_handlers(
+      webview_builder,
+      instance,
+      self.page_load_handler.take(),
+      self.navigation_handler.take(),
+    );
+
     // Build the webview
     #[cfg(any(
       target_os = "linux",
@@ -908,42 +1274,35 @@ impl WebViewBuilder {
       target_os = "openbsd"
     ))]
     {
-      extern "C" {
-        fn gtk_bin_get_child(bin: *mut std::ffi::c_void) -> *mut std::ffi::c_void;
-        fn gtk_container_remove(container: *mut std::ffi::c_void, widget: *mut std::ffi::c_void);
-        fn gtk_widget_show_all(widget: *mut std::ffi::c_void);
-      }
+      use gtk::prelude::*;
 
-      let window_ptr = window.gtk_window();
-      let window_ptr_raw = unsafe { *(window_ptr as *const _ as *const *mut std::ffi::c_void) };
+      let gtk_window = window.gtk_window();
 
-      unsafe {
-        let child = gtk_bin_get_child(window_ptr_raw);
-        if !child.is_null() {
-          gtk_container_remove(window_ptr_raw, child);
-        }
+      if let Some(child) = gtk_window.child() {
+        gtk_window.remove(&child);
       }
 
       // IPC Handler
       let (webview_builder_with_ipc, listeners) = setup_ipc_handler(
         self.ipc_handler.take(),
         self.ipc_handlers.drain(..).collect(),
+        self.ipc_request_handlers.drain(..).collect(),
+        label.clone(),
+        instance,
         webview_builder,
         ipc_listeners_override,
       );
       let ipc_listeners = listeners;
       webview_builder = webview_builder_with_ipc;
 
-      let webview = webview_builder.build_gtk(window_ptr).map_err(|e| {
+      let webview = webview_builder.build_gtk(gtk_window).map_err(|e| {
         napi::Error::new(
           napi::Status::GenericFailure,
           format!("Failed to create webview: {}", e),
         )
       })?;
 
-      unsafe {
-        gtk_widget_show_all(window_ptr_raw);
-      }
+      gtk_window.show_all();
 
       #[allow(clippy::arc_with_non_send_sync)]
       let webview_inner = Arc::new(Mutex::new(webview));
@@ -951,6 +1310,9 @@ impl WebViewBuilder {
         inner: Some(webview_inner),
         label,
         ipc_listeners,
+        muted: Arc::new(AtomicBool::new(false)),
+        devtools: self.attributes.devtools,
+        instance,
       })
     }
 
@@ -966,6 +1328,9 @@ impl WebViewBuilder {
       let (webview_builder_with_ipc, listeners) = setup_ipc_handler(
         self.ipc_handler.take(),
         self.ipc_handlers.drain(..).collect(),
+        self.ipc_request_handlers.drain(..).collect(),
+        label.clone(),
+        instance,
         webview_builder,
         ipc_listeners_override,
       );
@@ -984,11 +1349,17 @@ impl WebViewBuilder {
         inner: Some(webview_inner),
         label,
         ipc_listeners,
+        muted: Arc::new(AtomicBool::new(false)),
+        devtools: self.attributes.devtools,
+        instance,
       })
     }
   }
 }
 
+/// Source of the instance numbers telling apart webviews, whose labels need not be unique.
+static NEXT_WEBVIEW_INSTANCE: AtomicU64 = AtomicU64::new(1);
+
 /// The main webview struct.
 #[napi]
 pub struct WebView {
@@ -996,6 +1367,73 @@ pub struct WebView {
   pub(crate) inner: Option<Arc<Mutex<wry::WebView>>>,
   label: String,
   pub(crate) ipc_listeners: Arc<Mutex<Vec<IpcHandler>>>,
+  muted: Arc<AtomicBool>,
+  /// Whether devtools were enabled when the webview was built
+  devtools: bool,
+  /// Instance number from [`NEXT_WEBVIEW_INSTANCE`], which RPC replies are matched against
+  instance: u64,
+}
+
+/// Builds a script that mutes or unmutes all media elements of the page.
+///
+/// While muted, a `MutationObserver` also mutes elements added later.
+#[cfg(not(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd",
+  target_os = "windows",
+  target_os = "macos"
+)))]
+fn mute_media_script(muted: bool) -> String {
+  format!(
+    r#"(function(muted) {{
+  window.__webviewMuted = muted;
+  var apply = function() {{
+    document.querySelectorAll('audio, video').forEach(function(el) {{ el.muted = window.__webviewMuted; }});
+  }};
+  apply();
+  if (!window.__webviewMuteObserver) {{
+    window.__webviewMuteObserver = new MutationObserver(function() {{
+      if (window.__webviewMuted) apply();
+    }});
+    window.__webviewMuteObserver.observe(document.documentElement, {{ childList: true, subtree: true }});
+  }}
+}})({});"#,
+    muted
+  )
+}
+
+impl WebView {
+  /// Creates another handle to the same underlying webview.
+  pub(crate) fn clone_handle(&self) -> Self {
+    Self {
+      inner: self.inner.clone(),
+      label: self.label.clone(),
+      ipc_listeners: self.ipc_listeners.clone(),
+      muted: self.muted.clone(),
+      devtools: self.devtools,
+      instance: self.instance,
+    }
+  }
+
+  /// Fails unless devtools are compiled in and were enabled at build time.
+  fn check_devtools(&self) -> Result<()> {
+    if !cfg!(any(debug_assertions, feature = "devtools")) {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        "Devtools are not available: built without the `devtools` feature".to_string(),
+      ));
+    }
+    if !self.devtools {
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        "Devtools are disabled for this webview, see withDevtools".to_string(),
+      ));
+    }
+    Ok(())
+  }
 }
 
 #[napi]
@@ -1016,44 +1454,183 @@ impl WebView {
   #[napi]
   pub fn evaluate_script(&self, js: String) -> Result<()> {
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().evaluate_script(&js);
+      let _ = lock_webview(inner).evaluate_script(&js);
+    }
+    Ok(())
+  }
+
+  /// Mutes or unmutes all audio of the webview.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Native WebKitGTK page muting.
+  /// - **Windows**: `ICoreWebView2_8::put_IsMuted`, which needs WebView2 Runtime 95+.
+  /// - **macOS**: WebKit's page muting (`_setPageMuted:`).
+  ///
+  /// All of these cover every audio source of the page, including Web Audio, and
+  /// persist across navigations.
+  #[napi]
+  pub fn set_muted(&self, muted: bool) -> Result<()> {
+    let Some(inner) = &self.inner else {
+      return Ok(());
+    };
+    self.muted.store(muted, Ordering::Relaxed);
+    let webview = lock_webview(inner);
+
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    ))]
+    {
+      use webkit2gtk::WebViewExt;
+      use wry::WebViewExtUnix;
+
+      webview.webview().set_is_muted(muted);
+    }
+    #[cfg(target_os = "windows")]
+    {
+      use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2_8;
+      use windows::core::Interface;
+      use wry::WebViewExtWindows;
+
+      unsafe {
+        webview
+          .webview()
+          .cast::<ICoreWebView2_8>()
+          .and_then(|core| core.SetIsMuted(muted))
+      }
+      .map_err(|e| {
+        napi::Error::new(
+          napi::Status::GenericFailure,
+          format!("Failed to mute webview: {}", e),
+        )
+      })?;
+    }
+    #[cfg(target_os = "macos")]
+    {
+      use wry::WebViewExtMacOS;
+
+      // _WKMediaMutedState: audio only, capture devices are left alone
+      const WK_MEDIA_AUDIO_MUTED: usize = 1 << 0;
+
+      let wk_webview = webview.webview();
+      crate::tao::platform::appkit::set_page_muted(
+        &*wk_webview as *const _ as *mut std::ffi::c_void,
+        if muted { WK_MEDIA_AUDIO_MUTED } else { 0 },
+      );
+    }
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+      target_os = "windows",
+      target_os = "macos"
+    )))]
+    {
+      webview
+        .evaluate_script(&mute_media_script(muted))
+        .map_err(|e| {
+          napi::Error::new(
+            napi::Status::GenericFailure,
+            format!("Failed to mute webview: {}", e),
+          )
+        })?;
     }
     Ok(())
   }
 
+  /// Gets whether the webview was muted with `set_muted`.
+  #[napi]
+  pub fn is_muted(&self) -> bool {
+    self.muted.load(Ordering::Relaxed)
+  }
+
   /// Opens the developer tools.
+  ///
+  /// Fails if devtools were disabled with `withDevtools(false)` or are not compiled
+  /// in (a build without the `devtools` feature).
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Some WebKitGTK builds ship without the local inspector; a warning
+  ///   is logged when it doesn't open. Remote inspection can then be enabled by
+  ///   starting the process with `WEBKIT_INSPECTOR_SERVER=127.0.0.1:9222`.
   #[napi]
   pub fn open_devtools(&self) -> Result<()> {
-    if let Some(inner) = &self.inner {
-      inner.lock().unwrap().open_devtools();
+    let Some(inner) = &self.inner else {
+      return Ok(());
+    };
+    self.check_devtools()?;
+    #[cfg(any(debug_assertions, feature = "devtools"))]
+    {
+      let webview = lock_webview(inner);
+      webview.open_devtools();
+      #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+      ))]
+      if !webview.is_devtools_open() {
+        log_warn!(
+          "webview",
+          "Devtools did not open: this WebKitGTK build has no inspector; \
+           set WEBKIT_INSPECTOR_SERVER=127.0.0.1:9222 for remote inspection"
+        );
+      }
     }
+    #[cfg(not(any(debug_assertions, feature = "devtools")))]
+    let _ = inner;
     Ok(())
   }
 
   /// Closes the developer tools.
+  ///
+  /// Fails like `openDevtools` when devtools are unavailable.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Not supported, does nothing.
   #[napi]
   pub fn close_devtools(&self) -> Result<()> {
-    if let Some(inner) = &self.inner {
-      inner.lock().unwrap().close_devtools();
-    }
+    let Some(inner) = &self.inner else {
+      return Ok(());
+    };
+    self.check_devtools()?;
+    #[cfg(any(debug_assertions, feature = "devtools"))]
+    lock_webview(inner).close_devtools();
+    #[cfg(not(any(debug_assertions, feature = "devtools")))]
+    let _ = inner;
     Ok(())
   }
 
   /// Checks if the developer tools are open.
+  ///
+  /// Always `false` when devtools are unavailable.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Not supported, always `false`.
   #[napi]
   pub fn is_devtools_open(&self) -> Result<bool> {
-    if let Some(inner) = &self.inner {
-      Ok(inner.lock().unwrap().is_devtools_open())
-    } else {
-      Ok(false)
+    #[cfg(any(debug_assertions, feature = "devtools"))]
+    if let (Some(inner), true) = (&self.inner, self.devtools) {
+      return Ok(lock_webview(inner).is_devtools_open());
     }
+    Ok(false)
   }
 
   /// Reloads the current page.
   #[napi]
   pub fn reload(&self) -> Result<()> {
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().reload();
+      let _ = lock_webview(inner).reload();
     }
     Ok(())
   }
@@ -1062,7 +1639,7 @@ impl WebView {
   #[napi]
   pub fn print(&self) -> Result<()> {
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().print();
+      let _ = lock_webview(inner).print();
     }
     Ok(())
   }
@@ -1071,7 +1648,7 @@ impl WebView {
   #[napi]
   pub fn load_url(&self, url: String) -> Result<()> {
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().load_url(&url);
+      let _ = lock_webview(inner).load_url(&url);
     }
     Ok(())
   }
@@ -1080,7 +1657,7 @@ impl WebView {
   #[napi]
   pub fn load_html(&self, html: String) -> Result<()> {
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().load_html(&html);
+      let _ = lock_webview(inner).load_html(&html);
     }
     Ok(())
   }
@@ -1129,7 +1706,7 @@ impl WebView {
     };
 
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().load_html(&html_with_base);
+      let _ = lock_webview(inner).load_html(&html_with_base);
     }
     Ok(())
   }
@@ -1160,7 +1737,7 @@ impl WebView {
     };
 
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().load_html(&html_with_base);
+      let _ = lock_webview(inner).load_html(&html_with_base);
     }
     Ok(())
   }
@@ -1180,10 +1757,7 @@ impl WebView {
     }
 
     if let Some(inner) = &self.inner {
-      let _ = inner
-        .lock()
-        .unwrap()
-        .load_url_with_headers(&url, header_map);
+      let _ = lock_webview(inner).load_url_with_headers(&url, header_map);
     }
     Ok(())
   }
@@ -1197,7 +1771,7 @@ impl WebView {
     callback: ThreadsafeFunction<String>,
   ) -> Result<()> {
     if let Some(inner) = &self.inner {
-      let guard = inner.lock().unwrap();
+      let guard = lock_webview(inner);
       guard
         .evaluate_script_with_callback(&js, move |result: String| {
           let _ = callback.call(Ok(result), ThreadsafeFunctionCallMode::NonBlocking);
@@ -1212,21 +1786,92 @@ impl WebView {
     Ok(())
   }
 
+  /// Evaluates JavaScript code and resolves with its result as JSON text.
+  ///
+  /// The code runs like a global `eval`, so the value of its last expression
+  /// statement is the result, e.g. `document.title` resolves with `"\"Home\""`.
+  /// `undefined` and other values JSON cannot represent resolve with `"null"`, and
+  /// promises are not awaited.
+  ///
+  /// Rejects with the error message if the code throws, and if the webview has been
+  /// destroyed, before or while the code runs.
+  ///
+  /// Pages whose Content-Security-Policy blocks `eval` get the code as a plain
+  /// script instead, which the policy does not apply to. Its result is then
+  /// serialized by the platform, and a thrown error or an `undefined` result
+  /// rejects without the error message, or resolves with `"null"` on Windows.
+  #[napi(ts_return_type = "Promise<string>")]
+  pub fn evaluate_script_with_result<'env>(
+    &self,
+    env: &'env Env,
+    js: String,
+  ) -> Result<Object<'env>> {
+    let (deferred, promise) = env.create_deferred::<String, RpcResolver>()?;
+    let Some(inner) = &self.inner else {
+      deferred.reject(napi::Error::new(
+        napi::Status::GenericFailure,
+        "WebView has been destroyed".to_string(),
+      ));
+      return Ok(promise);
+    };
+    let script = eval_with_result_script(&js).map_err(|e| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to serialize script: {}", e),
+      )
+    })?;
+
+    // Shared with the callback, so an evaluation error can still be reported here
+    let pending = Arc::new(PendingEval(Mutex::new(Some(deferred))));
+    let callback_pending = pending.clone();
+    let id = NEXT_EVAL_ID.fetch_add(1, Ordering::Relaxed);
+    EVAL_FALLBACKS.with(|fallbacks| {
+      let mut fallbacks = fallbacks.borrow_mut();
+      // Callbacks of destroyed webviews may never run
+      fallbacks.retain(|_, fallback| fallback.webview.strong_count() > 0);
+      fallbacks.insert(
+        id,
+        EvalFallback {
+          webview: Arc::downgrade(inner),
+          js,
+        },
+      );
+    });
+    let evaluated =
+      lock_webview(inner).evaluate_script_with_callback(&script, move |result: String| {
+        let fallback = EVAL_FALLBACKS.with(|fallbacks| fallbacks.borrow_mut().remove(&id));
+        match parse_eval_result(&result) {
+          Some(EvalOutcome::Reply(reply)) => callback_pending.settle(reply),
+          Some(EvalOutcome::Blocked) => match fallback {
+            Some(fallback) => fallback.evaluate(callback_pending.clone()),
+            None => callback_pending.settle(RpcReply::Error(
+              "The page's Content-Security-Policy blocks eval".to_string(),
+            )),
+          },
+          // wry reports an empty result when the webview went away mid-evaluation
+          None => callback_pending.settle(RpcReply::Error(
+            "WebView was destroyed before the script finished".to_string(),
+          )),
+        }
+      });
+    if let Err(e) = evaluated {
+      EVAL_FALLBACKS.with(|fallbacks| fallbacks.borrow_mut().remove(&id));
+      pending.settle(RpcReply::Error(format!("Failed to evaluate script: {}", e)));
+    }
+    Ok(promise)
+  }
+
   /// Clears all browsing data (cookies, cache, local storage, etc.).
   /// This is an advanced method for better control over the webview.
   #[napi]
   pub fn clear_all_browsing_data(&self) -> Result<()> {
     if let Some(inner) = &self.inner {
-      inner
-        .lock()
-        .unwrap()
-        .clear_all_browsing_data()
-        .map_err(|e| {
-          napi::Error::new(
-            napi::Status::GenericFailure,
-            format!("Failed to clear browsing data: {:?}", e),
-          )
-        })?;
+      lock_webview(inner).clear_all_browsing_data().map_err(|e| {
+        napi::Error::new(
+          napi::Status::GenericFailure,
+          format!("Failed to clear browsing data: {:?}", e),
+        )
+      })?;
     }
     Ok(())
   }
@@ -1253,7 +1898,7 @@ impl WebView {
     let cookie = cookie_builder.build();
 
     if let Some(inner) = &self.inner {
-      inner.lock().unwrap().set_cookie(&cookie).map_err(|e| {
+      lock_webview(inner).set_cookie(&cookie).map_err(|e| {
         napi::Error::new(
           napi::Status::GenericFailure,
           format!("Failed to set cookie: {:?}", e),
@@ -1268,7 +1913,7 @@ impl WebView {
   #[napi]
   pub fn get_cookies(&self) -> Result<Vec<CookieInfo>> {
     if let Some(inner) = &self.inner {
-      let cookies = inner.lock().unwrap().cookies().map_err(|e| {
+      let cookies = lock_webview(inner).cookies().map_err(|e| {
         napi::Error::new(
           napi::Status::GenericFailure,
           format!("Failed to get cookies: {:?}", e),
@@ -1295,7 +1940,7 @@ impl WebView {
   #[napi]
   pub fn get_cookies_for_url(&self, url: String) -> Result<Vec<CookieInfo>> {
     if let Some(inner) = &self.inner {
-      let cookies = inner.lock().unwrap().cookies_for_url(&url).map_err(|e| {
+      let cookies = lock_webview(inner).cookies_for_url(&url).map_err(|e| {
         napi::Error::new(
           napi::Status::GenericFailure,
           format!("Failed to get cookies for URL: {:?}", e),
@@ -1339,7 +1984,7 @@ impl WebView {
     let cookie = cookie_builder.build();
 
     if let Some(inner) = &self.inner {
-      inner.lock().unwrap().delete_cookie(&cookie).map_err(|e| {
+      lock_webview(inner).delete_cookie(&cookie).map_err(|e| {
         napi::Error::new(
           napi::Status::GenericFailure,
           format!("Failed to delete cookie: {:?}", e),
@@ -1353,7 +1998,7 @@ impl WebView {
   #[napi(getter)]
   pub fn url(&self) -> Result<Option<String>> {
     if let Some(inner) = &self.inner {
-      match inner.lock().unwrap().url() {
+      match lock_webview(inner).url() {
         Ok(u) => Ok(Some(u.to_string())),
         Err(_) => Ok(None),
       }
@@ -1367,7 +2012,7 @@ impl WebView {
   #[napi]
   pub fn set_zoom(&self, zoom: f64) -> Result<()> {
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().zoom(zoom);
+      let _ = lock_webview(inner).zoom(zoom);
     }
     Ok(())
   }
@@ -1376,7 +2021,7 @@ impl WebView {
   #[napi]
   pub fn bounds(&self) -> Result<Rect> {
     if let Some(inner) = &self.inner {
-      let b = inner.lock().unwrap().bounds().map_err(|e| {
+      let b = lock_webview(inner).bounds().map_err(|e| {
         napi::Error::new(
           napi::Status::GenericFailure,
           format!("Failed to get bounds: {:?}", e),
@@ -1408,7 +2053,7 @@ impl WebView {
   #[napi]
   pub fn set_bounds(&self, rect: Rect) -> Result<()> {
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().set_bounds(wry::Rect {
+      let _ = lock_webview(inner).set_bounds(wry::Rect {
         position: tao::dpi::LogicalPosition::new(rect.x as f64, rect.y as f64).into(),
         size: tao::dpi::LogicalSize::new(rect.width as f64, rect.height as f64).into(),
       });
@@ -1420,7 +2065,7 @@ impl WebView {
   #[napi]
   pub fn set_background_color(&self, r: u8, g: u8, b: u8, a: u8) -> Result<()> {
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().set_background_color((r, g, b, a));
+      let _ = lock_webview(inner).set_background_color((r, g, b, a));
     }
     Ok(())
   }
@@ -1429,7 +2074,7 @@ impl WebView {
   #[napi]
   pub fn set_visible(&self, visible: bool) -> Result<()> {
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().set_visible(visible);
+      let _ = lock_webview(inner).set_visible(visible);
     }
     Ok(())
   }
@@ -1438,7 +2083,7 @@ impl WebView {
   #[napi]
   pub fn focus(&self) -> Result<()> {
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().focus();
+      let _ = lock_webview(inner).focus();
     }
     Ok(())
   }
@@ -1447,7 +2092,7 @@ impl WebView {
   #[napi]
   pub fn focus_parent(&self) -> Result<()> {
     if let Some(inner) = &self.inner {
-      let _ = inner.lock().unwrap().focus_parent();
+      let _ = lock_webview(inner).focus_parent();
     }
     Ok(())
   }
@@ -1472,11 +2117,369 @@ impl WebView {
     );
     self.evaluate_script(js)
   }
+
+  /// Calls a method registered in the page and resolves with its result.
+  ///
+  /// The page registers methods with `window.__webview_rpc__.handle(method, fn)`;
+  /// `fn` receives `params` and returns a string or a promise of one. The dispatcher
+  /// is injected into every page as an initialization script, and the call is
+  /// matched to its reply over IPC with a correlation id, so replies never reach
+  /// the `on` listeners.
+  ///
+  /// Rejects if the page has no handler for `method`, the handler throws or
+  /// rejects, or no reply arrives within `timeout_ms` (default: 30000).
+  #[napi(ts_return_type = "Promise<string>")]
+  pub fn request<'env>(
+    &self,
+    env: &'env Env,
+    method: String,
+    params: String,
+    timeout_ms: Option<u32>,
+  ) -> Result<Object<'env>> {
+    let inner = self.inner.as_ref().ok_or_else(|| {
+      napi::Error::new(
+        napi::Status::GenericFailure,
+        "WebView not initialized".to_string(),
+      )
+    })?;
+    let (deferred, promise) = env.create_deferred::<String, RpcResolver>()?;
+    let id = NEXT_RPC_ID.fetch_add(1, Ordering::SeqCst);
+    let key = (self.instance, id);
+    let timeout = std::time::Duration::from_millis(timeout_ms.unwrap_or(30_000) as u64);
+    RPC_TIMEOUT_WORKER.call_once(|| {
+      std::thread::spawn(expire_rpc_requests);
+    });
+    PENDING_RPC.lock().unwrap().insert(
+      key,
+      PendingRequest {
+        deferred,
+        method: method.clone(),
+        timeout,
+        deadline: std::time::Instant::now() + timeout,
+      },
+    );
+    RPC_DEADLINE_CHANGED.notify_one();
+
+    let serialize = |value: &str| {
+      serde_json::to_string(value).map_err(|e| {
+        napi::Error::new(
+          napi::Status::GenericFailure,
+          format!("Failed to serialize request: {}", e),
+        )
+      })
+    };
+    let js = format!(
+      "window.__webview_rpc__ ? window.__webview_rpc__.__request({id}, {method}, {params}) \
+       : window.ipc.postMessage(JSON.stringify({{ __rpc: {id}, error: 'RPC dispatcher not loaded' }}))",
+      id = id,
+      method = serialize(&method)?,
+      params = serialize(&params)?,
+    );
+    if let Err(e) = lock_webview(inner).evaluate_script(&js) {
+      PENDING_RPC.lock().unwrap().remove(&key);
+      return Err(napi::Error::new(
+        napi::Status::GenericFailure,
+        format!("Failed to send request: {}", e),
+      ));
+    }
+    Ok(promise)
+  }
+}
+
+/// Page-side RPC dispatcher used by [`WebView::request`]. The script is
+/// idempotent so it can be injected repeatedly.
+const RPC_SCRIPT: &str = r#"(function () {
+  if (window.__webview_rpc__) return;
+  var handlers = {};
+  function reply(id, key, value) {
+    var message = { __rpc: id };
+    message[key] = value;
+    window.ipc.postMessage(JSON.stringify(message));
+  }
+  window.__webview_rpc__ = {
+    handle: function (method, fn) { handlers[method] = fn; },
+    __request: function (id, method, params) {
+      var handler = handlers[method];
+      if (!handler) return reply(id, 'error', 'No handler for method ' + method);
+      Promise.resolve().then(function () { return handler(params); }).then(
+        function (result) { reply(id, 'result', result == null ? '' : String(result)); },
+        function (error) { reply(id, 'error', String(error && error.message || error)); }
+      );
+    }
+  };
+})();"#;
+
+type RpcResolver = Box<dyn FnOnce(Env) -> Result<String> + Send>;
+
+static NEXT_RPC_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A [`WebView::request`] waiting for its reply.
+struct PendingRequest {
+  deferred: napi::JsDeferred<String, RpcResolver>,
+  method: String,
+  timeout: std::time::Duration,
+  deadline: std::time::Instant,
+}
+
+/// Requests waiting for a reply from the page, keyed by webview instance and
+/// correlation id.
+static PENDING_RPC: LazyLock<Mutex<HashMap<(u64, u64), PendingRequest>>> =
+  LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Wakes the timeout worker when a request with a new deadline is added.
+static RPC_DEADLINE_CHANGED: std::sync::Condvar = std::sync::Condvar::new();
+
+/// Starts [`expire_rpc_requests`] with the first request.
+static RPC_TIMEOUT_WORKER: std::sync::Once = std::sync::Once::new();
+
+/// Rejects the pending requests whose deadline has passed, sleeping until the
+/// next one in between. Runs for the lifetime of the process, shared by all
+/// webviews.
+fn expire_rpc_requests() {
+  let mut pending = PENDING_RPC.lock().unwrap();
+  loop {
+    let now = std::time::Instant::now();
+    let expired: Vec<(u64, u64)> = pending
+      .iter()
+      .filter(|(_, request)| request.deadline <= now)
+      .map(|(key, _)| *key)
+      .collect();
+    for key in expired {
+      if let Some(request) = pending.remove(&key) {
+        request.deferred.reject(napi::Error::new(
+          napi::Status::GenericFailure,
+          format!(
+            "Request '{}' timed out after {:?}",
+            request.method, request.timeout
+          ),
+        ));
+      }
+    }
+    pending = match pending.values().map(|request| request.deadline).min() {
+      Some(deadline) => {
+        RPC_DEADLINE_CHANGED
+          .wait_timeout(pending, deadline.saturating_duration_since(now))
+          .unwrap()
+          .0
+      }
+      None => RPC_DEADLINE_CHANGED.wait(pending).unwrap(),
+    };
+  }
+}
+
+/// A reply to a [`WebView::request`], parsed from an IPC message.
+#[derive(Debug, PartialEq)]
+enum RpcReply {
+  Result(String),
+  Error(String),
+}
+
+/// Parses an RPC reply; returns `None` if the message is not one.
+fn parse_rpc_reply(msg: &str) -> Option<(u64, RpcReply)> {
+  if !msg.starts_with("{\"__rpc\":") {
+    return None;
+  }
+  let envelope = serde_json::from_str::<serde_json::Value>(msg).ok()?;
+  let id = envelope["__rpc"].as_u64()?;
+  if let Some(error) = envelope["error"].as_str() {
+    return Some((id, RpcReply::Error(error.to_string())));
+  }
+  let result = envelope["result"].as_str()?;
+  Some((id, RpcReply::Result(result.to_string())))
+}
+
+/// The promise of a [`WebView::evaluate_script_with_result`] call.
+///
+/// wry may drop the callback without calling it, e.g. for a script queued before
+/// the page has loaded when the webview is destroyed; the promise is then rejected
+/// once the last handle is gone.
+struct PendingEval(Mutex<Option<napi::JsDeferred<String, RpcResolver>>>);
+
+impl PendingEval {
+  /// Settles the promise, unless it is settled already.
+  fn settle(&self, reply: RpcReply) {
+    let Some(deferred) = self.0.lock().unwrap().take() else {
+      return;
+    };
+    match reply {
+      RpcReply::Result(result) => deferred.resolve(Box::new(move |_| Ok(result))),
+      RpcReply::Error(error) => {
+        deferred.reject(napi::Error::new(napi::Status::GenericFailure, error))
+      }
+    }
+  }
+}
+
+impl Drop for PendingEval {
+  fn drop(&mut self) {
+    self.settle(RpcReply::Error(
+      "WebView was destroyed before the script finished".to_string(),
+    ));
+  }
+}
+
+/// Source of the ids matching [`EVAL_FALLBACKS`] entries to their evaluation
+static NEXT_EVAL_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Code of a [`WebView::evaluate_script_with_result`] call, kept in case the page
+/// blocks `eval`.
+struct EvalFallback {
+  webview: std::sync::Weak<Mutex<wry::WebView>>,
+  js: String,
+}
+
+impl EvalFallback {
+  /// Runs the code as a plain script and settles the promise with its result
+  fn evaluate(self, pending: Arc<PendingEval>) {
+    let Some(webview) = self.webview.upgrade() else {
+      return;
+    };
+    // A webview method is running further up the stack
+    let Ok(webview) = webview.try_lock() else {
+      pending.settle(RpcReply::Error(
+        "WebView was busy when the script was to run".to_string(),
+      ));
+      return;
+    };
+    let callback_pending = pending.clone();
+    let evaluated = webview.evaluate_script_with_callback(&self.js, move |result: String| {
+      callback_pending.settle(if result.is_empty() {
+        RpcReply::Error(
+          "Script failed or returned undefined; the page's Content-Security-Policy blocks \
+           eval, so no error message is available"
+            .to_string(),
+        )
+      } else {
+        RpcReply::Result(result)
+      });
+    });
+    if let Err(e) = evaluated {
+      pending.settle(RpcReply::Error(format!("Failed to evaluate script: {}", e)));
+    }
+  }
+}
+
+thread_local! {
+  /// Code of pending [`WebView::evaluate_script_with_result`] calls, keyed by
+  /// evaluation id. wry calls back on the thread owning the webviews, which
+  /// aren't `Send`.
+  static EVAL_FALLBACKS: std::cell::RefCell<HashMap<u64, EvalFallback>> =
+    std::cell::RefCell::new(HashMap::new());
+}
+
+/// The outcome of the script built by [`eval_with_result_script`]
+#[derive(Debug, PartialEq)]
+enum EvalOutcome {
+  Reply(RpcReply),
+  /// The page's Content-Security-Policy blocks `eval`; the code has not run
+  Blocked,
+}
+
+/// Wraps code for [`WebView::evaluate_script_with_result`] so its result or error
+/// comes back as an `{"ok": json}` or `{"error": message}` envelope.
+///
+/// `eval` is tried on a constant first, so a page blocking it reports
+/// `{"blocked": true}` before any of the code ran.
+fn eval_with_result_script(js: &str) -> serde_json::Result<String> {
+  Ok(format!(
+    "(function () {{ try {{ (0, eval)('0'); }} catch (e) {{ return JSON.stringify({{ blocked: true }}); }} \
+     try {{ var value = JSON.stringify((0, eval)({})); \
+     return JSON.stringify({{ ok: value === undefined ? 'null' : value }}); }} \
+     catch (e) {{ return JSON.stringify({{ error: String(e && e.message || e) }}); }} }})()",
+    serde_json::to_string(js)?
+  ))
+}
+
+/// Parses the envelope of [`eval_with_result_script`] as passed on by wry, which
+/// JSON-encodes the returned string once more; returns `None` if there is none.
+fn parse_eval_result(result: &str) -> Option<EvalOutcome> {
+  let envelope = serde_json::from_str::<String>(result).ok()?;
+  let envelope = serde_json::from_str::<serde_json::Value>(&envelope).ok()?;
+  if envelope["blocked"].as_bool() == Some(true) {
+    return Some(EvalOutcome::Blocked);
+  }
+  if let Some(error) = envelope["error"].as_str() {
+    return Some(EvalOutcome::Reply(RpcReply::Error(error.to_string())));
+  }
+  Some(EvalOutcome::Reply(RpcReply::Result(
+    envelope["ok"].as_str()?.to_string(),
+  )))
+}
+
+/// Settles the request an IPC message from webview `instance` replies to.
+///
+/// Returns true if the message was an RPC reply and has been consumed, in which
+/// case it must not be forwarded to the raw IPC listeners.
+fn dispatch_rpc_reply(instance: u64, msg: &str) -> bool {
+  let Some((id, reply)) = parse_rpc_reply(msg) else {
+    return false;
+  };
+  // A missing request timed out already or was sent to another webview, whose
+  // page cannot settle it; drop the reply
+  if let Some(PendingRequest { deferred, .. }) = PENDING_RPC.lock().unwrap().remove(&(instance, id))
+  {
+    match reply {
+      RpcReply::Result(result) => deferred.resolve(Box::new(move |_| Ok(result))),
+      RpcReply::Error(error) => {
+        deferred.reject(napi::Error::new(napi::Status::GenericFailure, error))
+      }
+    }
+  }
+  true
+}
+
+/// Registers custom protocol handlers, forwarding each request to Node.
+fn apply_custom_protocols(
+  mut webview_builder: wry::WebViewBuilder<'static>,
+  protocols: impl Iterator<Item = (String, CustomProtocolHandler)>,
+) -> wry::WebViewBuilder<'static> {
+  for (scheme, handler) in protocols {
+    webview_builder =
+      webview_builder.with_asynchronous_custom_protocol(scheme, move |_, request, responder| {
+        let request = RequestAsyncResponder::new(request, responder);
+        handler.call(Ok(request), ThreadsafeFunctionCallMode::NonBlocking);
+      });
+  }
+  webview_builder
+}
+
+/// Registers the page load and navigation-decision callbacks.
+///
+/// A page load always discards the bridge messages the previous page of webview
+/// `instance` left incomplete.
+fn apply_navigation_handlers(
+  mut webview_builder: wry::WebViewBuilder<'static>,
+  instance: u64,
+  page_load_handler: Option<PageLoadHandler>,
+  navigation_handler: Option<NavigationHandler>,
+) -> wry::WebViewBuilder<'static> {
+  webview_builder = webview_builder.with_on_page_load_handler(move |event, url| {
+    let event = match event {
+      wry::PageLoadEvent::Started => {
+        crate::high_level::discard_partial_bridge_messages(instance);
+        PageLoadEvent::Started
+      }
+      wry::PageLoadEvent::Finished => PageLoadEvent::Completed,
+    };
+    if let Some(handler) = &page_load_handler {
+      handler.call(
+        Ok(PageLoadInfo { event, url }),
+        ThreadsafeFunctionCallMode::NonBlocking,
+      );
+    }
+  });
+  if let Some(handler) = navigation_handler {
+    webview_builder = webview_builder.with_navigation_handler(move |url| handler.allows(url));
+  }
+  webview_builder
 }
 
 fn setup_ipc_handler(
   builder_ipc_handler: Option<IpcHandler>,
   additional_handlers: Vec<IpcHandler>,
+  request_handlers: Vec<IpcRequestHandler>,
+  webview_id: String,
+  instance: u64,
   webview_builder: wry::WebViewBuilder<'static>,
   ipc_listeners_override: Option<Arc<Mutex<Vec<IpcHandler>>>>,
 ) -> (wry::WebViewBuilder<'static>, Arc<Mutex<Vec<IpcHandler>>>) {
@@ -1489,27 +2492,195 @@ fn setup_ipc_handler(
   }
 
   let listeners_clone = ipc_listeners.clone();
-  let webview_builder = webview_builder.with_ipc_handler(move |req| {
-    let msg = req.into_body();
+  let webview_builder = webview_builder
+    .with_initialization_script(RPC_SCRIPT)
+    .with_ipc_handler(move |req| {
+      let url = req.uri().to_string();
+      let msg = req.into_body();
+
+      // Replies to `WebView::request` settle their promise only
+      if dispatch_rpc_reply(instance, &msg) {
+        return;
+      }
 
-    // Check if we have any listeners registered
-    let listener_count = {
-      let listeners = listeners_clone.lock().unwrap();
-      listeners.len()
-    };
+      // Bridge messages are routed to their channel callbacks only
+      if crate::high_level::dispatch_bridge_message(instance, &msg) {
+        return;
+      }
 
-    if listener_count == 0 {
-      return;
-    }
+      // Each handler's calls are queued in order on the Node event loop
+      for handler in &request_handlers {
+        let request = IpcRequest {
+          webview_id: webview_id.clone(),
+          body: msg.clone(),
+          url: url.clone(),
+        };
+        handler.call(Ok(request), ThreadsafeFunctionCallMode::NonBlocking);
+      }
 
-    // Call each listener with the message using Blocking mode for immediate execution
-    let listeners = listeners_clone.lock().unwrap();
-    for (idx, listener) in listeners.iter().enumerate() {
-      let status = listener.call(Ok(msg.clone()), ThreadsafeFunctionCallMode::NonBlocking);
-      println!("Listener #{} call returned status: {:?}", idx, status);
-      //Ok(idx, status);
-    }
-  });
+      // Check if we have any listeners registered
+      let listener_count = {
+        let listeners = listeners_clone.lock().unwrap();
+        listeners.len()
+      };
+
+      if listener_count == 0 {
+        return;
+      }
+
+      // Call each listener with the message using Blocking mode for immediate execution
+      let listeners = listeners_clone.lock().unwrap();
+      for (idx, listener) in listeners.iter().enumerate() {
+        let status = listener.call(Ok(msg.clone()), ThreadsafeFunctionCallMode::NonBlocking);
+        log_trace!(
+          "ipc",
+          "Listener #{} call returned status: {:?}",
+          idx,
+          status
+        );
+      }
+    });
 
   (webview_builder, ipc_listeners)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_rpc_reply() {
+    assert_eq!(
+      parse_rpc_reply(r#"{"__rpc":7,"result":"ok"}"#),
+      Some((7, RpcReply::Result("ok".to_string())))
+    );
+    assert_eq!(
+      parse_rpc_reply(r#"{"__rpc":8,"error":"No handler for method x"}"#),
+      Some((8, RpcReply::Error("No handler for method x".to_string())))
+    );
+    // Plain IPC messages and bridge messages are left to the other listeners
+    assert_eq!(parse_rpc_reply("hello"), None);
+    assert_eq!(parse_rpc_reply(r#"{"__bridge":1,"id":0}"#), None);
+    assert_eq!(parse_rpc_reply(r#"{"__rpc":9}"#), None);
+  }
+
+  #[test]
+  fn test_protocol_response() {
+    let response = protocol_response(None, Some("text/html"), b"<p>hi</p>".to_vec()).unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+      response.headers()[wry::http::header::CONTENT_TYPE],
+      "text/html"
+    );
+    assert_eq!(response.body(), b"<p>hi</p>");
+
+    // Missing assets are answered with the handler's status
+    let response = protocol_response(Some(404), None, Vec::new()).unwrap();
+    assert_eq!(response.status(), 404);
+    assert!(response.headers().is_empty());
+
+    assert!(protocol_response(Some(1000), None, Vec::new()).is_err());
+  }
+
+  #[test]
+  fn test_request_path() {
+    assert_eq!(
+      request_path("app://localhost/assets/main.js"),
+      "/assets/main.js"
+    );
+    assert_eq!(
+      request_path("http://app.localhost/index.html?v=2#top"),
+      "/index.html"
+    );
+    assert_eq!(request_path("app://localhost"), "/");
+  }
+
+  #[test]
+  fn test_parse_eval_result() {
+    let script = eval_with_result_script("var a = \"\\\"\"; a").unwrap();
+    assert!(script.contains(r#"(0, eval)("var a = \"\\\"\"; a")"#));
+
+    assert_eq!(
+      parse_eval_result(r#""{\"ok\":\"{\\\"title\\\":\\\"Home\\\"}\"}""#),
+      Some(EvalOutcome::Reply(RpcReply::Result(
+        r#"{"title":"Home"}"#.to_string()
+      )))
+    );
+    assert_eq!(
+      parse_eval_result(r#""{\"error\":\"x is not defined\"}""#),
+      Some(EvalOutcome::Reply(RpcReply::Error(
+        "x is not defined".to_string()
+      )))
+    );
+    assert_eq!(
+      parse_eval_result(r#""{\"blocked\":true}""#),
+      Some(EvalOutcome::Blocked)
+    );
+    // wry passes an empty result when the evaluation was cancelled
+    assert_eq!(parse_eval_result(""), None);
+  }
+
+  #[test]
+  fn test_last_of_url_and_html_wins() {
+    let mut builder = WebViewBuilder::new().unwrap();
+    builder.with_url("https://example.com".to_string()).unwrap();
+    builder.with_html("<p>hi</p>".to_string()).unwrap();
+    assert_eq!(builder.attributes.url, None);
+    assert_eq!(builder.attributes.html.as_deref(), Some("<p>hi</p>"));
+
+    builder.with_url("https://example.com".to_string()).unwrap();
+    assert_eq!(builder.attributes.html, None);
+    assert_eq!(
+      builder.attributes.url.as_deref(),
+      Some("https://example.com")
+    );
+  }
+
+  #[test]
+  #[ignore = "requires a display and a webview runtime"]
+  #[cfg(any(target_os = "linux", target_os = "windows"))]
+  fn test_with_html_page_title() {
+    use tao::event::Event;
+    use tao::event_loop::ControlFlow;
+    use tao::platform::run_return::EventLoopExtRunReturn;
+
+    crate::tao::test_display::with_event_loop(|event_loop| {
+      let window =
+        crate::tao::test_display::build_window(event_loop, tao::window::WindowBuilder::new());
+      let mut builder = WebViewBuilder::new().unwrap();
+      builder
+        .with_html("<html><head><title>Inline page</title></head></html>".to_string())
+        .unwrap();
+      let webview = builder
+        .build_on_window(&window, "inline".to_string(), None)
+        .unwrap();
+
+      let title = Arc::new(Mutex::new(None::<String>));
+      let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+      event_loop.run_return(|event, _, control_flow| {
+        *control_flow = ControlFlow::Poll;
+        if !matches!(event, Event::MainEventsCleared) {
+          return;
+        }
+        if title.lock().unwrap().is_some() || std::time::Instant::now() > deadline {
+          *control_flow = ControlFlow::Exit;
+          return;
+        }
+        let result = title.clone();
+        let _ = webview
+          .inner
+          .as_ref()
+          .unwrap()
+          .lock()
+          .unwrap()
+          .evaluate_script_with_callback("document.title", move |value| {
+            // The title is empty until the page has loaded
+            if value != "\"\"" && !value.is_empty() {
+              *result.lock().unwrap() = Some(value);
+            }
+          });
+      });
+      assert_eq!(title.lock().unwrap().as_deref(), Some("\"Inline page\""));
+    });
+  }
+}